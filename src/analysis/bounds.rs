@@ -1,9 +1,11 @@
 use std::{collections::vec_deque::VecDeque, slice::Iter};
 
+use log::warn;
+
 use crate::{
     analysis::{
         function_parameters::CParameter,
-        functions::{find_function, find_index_to_ignore, finish_function_name},
+        functions::{find_function, find_index_to_ignore, finish_function_c_identifier},
         imports::Imports,
         out_parameters::use_function_return_for_result,
         ref_mode::RefMode,
@@ -12,7 +14,9 @@ use crate::{
     config,
     consts::TYPE_PARAMETERS_START,
     env::Env,
-    library::{Basic, Class, Concurrency, Function, ParameterDirection, Type, TypeId},
+    library::{
+        Basic, Class, Concurrency, Function, ParameterDirection, Type, TypeId, MAIN_NAMESPACE,
+    },
     traits::IntoString,
 };
 
@@ -46,6 +50,8 @@ pub struct Bound {
     /// Bound does not have an alias when `param: impl type_str` is used
     pub alias: Option<char>,
     pub type_str: String,
+    /// Further types an `IsA` bound has to be or implement
+    pub additional_type_strs: Vec<String>,
     pub callback_modified: bool,
 }
 
@@ -99,15 +105,30 @@ impl Bounds {
 
         if !par.instance_parameter && par.direction != ParameterDirection::Out {
             if let Some(bound_type) = Bounds::type_for(env, par.typ) {
-                ret = Some(Bounds::get_to_glib_extra(
-                    &bound_type,
-                    *par.nullable,
-                    par.instance_parameter,
-                    par.move_,
-                ));
+                let additional_type_strs = if bound_type.need_isa() {
+                    additional_bounds(env, func, par, configured_functions)
+                } else {
+                    Vec::new()
+                };
+                ret = Some(if additional_type_strs.is_empty() {
+                    Bounds::get_to_glib_extra(
+                        &bound_type,
+                        *par.nullable,
+                        par.instance_parameter,
+                        par.move_,
+                    )
+                } else {
+                    // `as_ref()` and `upcast()` are ambiguous with several `IsA` bounds
+                    let target = format!("::<{type_string}>()");
+                    match (*par.nullable, par.move_) {
+                        (true, true) => format!(".map(|p| p.upcast{target})"),
+                        (true, false) => format!(".map(|p| p.upcast_ref{target})"),
+                        (false, true) => format!(".upcast{target}"),
+                        (false, false) => format!(".upcast_ref{target}"),
+                    }
+                });
                 if r#async && (par.name == "callback" || par.name.ends_with("_callback")) {
-                    let func_name = func.c_identifier.as_ref().unwrap();
-                    let finish_func_name = finish_function_name(func_name);
+                    let finish_func_name = finish_function_c_identifier(func);
                     if let Some(function) = find_function(env, &finish_func_name) {
                         // FIXME: This should work completely based on the analysis of the finish()
                         // function but that a) happens afterwards and b) is
@@ -185,6 +206,10 @@ impl Bounds {
                 }
                 if (!need_is_into_check || !*par.nullable) && par.c_type != "GDestroyNotify" {
                     self.add_parameter(&par.name, &type_string, bound_type, r#async);
+                    if let Some(bound) = self.used.iter_mut().find(|b| b.parameter_name == par.name)
+                    {
+                        bound.additional_type_strs = additional_type_strs;
+                    }
                 }
             }
         } else if par.instance_parameter {
@@ -263,6 +288,7 @@ impl Bounds {
             parameter_name: name.to_owned(),
             alias,
             type_str: type_str.to_owned(),
+            additional_type_strs: Vec::new(),
             callback_modified: false,
         });
     }
@@ -277,7 +303,12 @@ impl Bounds {
         for used in &self.used {
             match used.bound_type {
                 NoWrapper => (),
-                IsA(_) => imports.add("glib::prelude::*"),
+                IsA(_) => {
+                    imports.add("glib::prelude::*");
+                    for type_str in &used.additional_type_strs {
+                        imports.add_used_type(type_str);
+                    }
+                }
                 AsRef(_) => imports.add_used_type(&used.type_str),
             }
         }
@@ -318,6 +349,50 @@ impl PropertyBound {
     }
 }
 
+/// Returns the Rust types of the `additional_bounds` configured for the
+/// parameter
+fn additional_bounds(
+    env: &Env,
+    func: &Function,
+    par: &CParameter,
+    configured_functions: &[&config::functions::Function],
+) -> Vec<String> {
+    configured_functions
+        .iter()
+        .flat_map(|f| f.parameters.iter())
+        .filter(|p| p.ident.is_match(&par.name))
+        .flat_map(|p| p.additional_bounds.iter())
+        .filter_map(|name| {
+            let tid = match env.library.find_type(MAIN_NAMESPACE, name) {
+                Some(tid) => tid,
+                None => {
+                    warn!(
+                        "Unknown type `{}` in additional_bounds of `{}` of {}",
+                        name, par.name, func.name
+                    );
+                    return None;
+                }
+            };
+            match env.library.type_(tid) {
+                Type::Class(_) | Type::Interface(_) => Some(
+                    RustType::builder(env, tid)
+                        .ref_mode(RefMode::ByRefFake)
+                        .try_build()
+                        .into_string(),
+                ),
+                _ => {
+                    warn!(
+                        "`{}` in additional_bounds of `{}` of {} is neither a class nor an \
+                         interface",
+                        name, par.name, func.name
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 fn find_out_parameters(
     env: &Env,
     function: &Function,
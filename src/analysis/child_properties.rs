@@ -131,6 +131,7 @@ fn analyze_property(
                 parameter_name: TYPE_PARAMETERS_START.to_string(),
                 alias: Some(TYPE_PARAMETERS_START.to_owned()),
                 type_str: r_type,
+                additional_type_strs: Vec::new(),
                 callback_modified: false,
             };
             // TODO: bounds_str push?!?!
@@ -110,7 +110,7 @@ fn analyze_property(
         .or(prop.version);
 
     let for_builder = prop.construct_only || prop.construct || prop.writable;
-    if !for_builder {
+    if !for_builder || env.uses_removed_type([prop.typ]) {
         return None;
     }
     let imports = &mut imports.with_defaults(prop_version, &None);
@@ -137,11 +137,13 @@ fn analyze_property(
         func_name: String::new(),
         func_name_alias: None,
         nullable,
+        null_panic_message: None,
         get_out_ref_mode,
         set_in_ref_mode,
         set_bound: None,
         bounds,
         version: prop_version,
         deprecated_version: prop.deprecated_version,
+        not_version: env.types_version_end([prop.typ]),
     })
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap},
     iter,
 };
 
@@ -8,7 +8,7 @@ use crate::library::*;
 #[derive(Debug)]
 struct Node {
     supers: Vec<TypeId>,
-    subs: HashSet<TypeId>,
+    subs: BTreeSet<TypeId>,
 }
 
 #[derive(Debug)]
@@ -56,7 +56,7 @@ fn get_node<'a>(
         tid,
         Node {
             supers,
-            subs: HashSet::new(),
+            subs: BTreeSet::new(),
         },
     );
     hier.get_mut(&tid)
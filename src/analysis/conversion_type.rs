@@ -89,6 +89,7 @@ impl ConversionType {
             FixedArray(..) => Self::Pointer,
             List(_) => Self::Pointer,
             SList(_) => Self::Pointer,
+            Array(_) => Self::Pointer,
             PtrArray(_) => Self::Pointer,
             Function(super::library::Function { name, .. }) if name == "AsyncReadyCallback" => {
                 Self::Direct
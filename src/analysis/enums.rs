@@ -60,6 +60,9 @@ pub fn new(env: &Env, obj: &GObject, imports: &mut Imports) -> Option<Info> {
         if obj.generate_display_trait {
             imports.add("std::fmt");
         }
+        for import in &obj.extra_imports {
+            imports.add(import);
+        }
     }
 
     let mut functions = functions::analyze(
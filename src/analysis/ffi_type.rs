@@ -26,6 +26,14 @@ pub fn used_ffi_type(env: &Env, type_id: TypeId, c_type: &str) -> Option<String>
 
 pub fn ffi_type(env: &Env, tid: TypeId, c_type: &str) -> Result {
     let (ptr, inner) = rustify_pointers(c_type);
+    if let Some(rust_type) = env.config.platform_types.get(&inner) {
+        let res = if ptr.is_empty() {
+            rust_type.clone()
+        } else {
+            format!("{ptr} {rust_type}")
+        };
+        return Ok(res.into());
+    }
     let res = if ptr.is_empty() {
         if let Some(c_tid) = env.library.find_type(0, c_type) {
             // Fast track plain basic types avoiding some checks
@@ -74,12 +74,18 @@ pub struct CParameter {
     pub ref_mode: RefMode,
     pub try_from_glib: TryFromGlib,
     pub move_: bool,
+    /// Whether a `GValue` is taken as `&dyn ToValue` and converted with
+    /// `to_value()`
+    pub to_value: bool,
 }
 
 #[derive(Clone, Debug)]
 pub enum TransformationType {
     ToGlibDirect {
         name: String,
+        /// Cast of the value for the FFI call, e.g. of an integer handle
+        /// passed as `gpointer`
+        pointer_cast: String,
     },
     ToGlibScalar {
         name: String,
@@ -110,6 +116,18 @@ pub enum TransformationType {
     },
     IntoRaw(String),
     ToSome(String),
+    /// An `inout` parameter taken as `&mut T` that is converted into a
+    /// temporary before the call and back after it
+    ToGlibInOutScalar {
+        name: String,
+    },
+    /// A slice copied into a temporary `GArray` before the call, which is
+    /// released after it unless its ownership is transferred
+    ToGlibArray {
+        name: String,
+        element_type: String,
+        transfer: library::Transfer,
+    },
 }
 
 impl TransformationType {
@@ -123,6 +141,8 @@ impl TransformationType {
                 | Self::ToGlibUnknown { .. }
                 | Self::ToSome(_)
                 | Self::IntoRaw(_)
+                | Self::ToGlibInOutScalar { .. }
+                | Self::ToGlibArray { .. }
         )
     }
 
@@ -180,6 +200,22 @@ impl Parameters {
         };
         self.transformations.push(transformation);
     }
+
+    /// Returns the first `inout` parameter that isn't a scalar, those can't be
+    /// converted yet.
+    pub fn unsupported_inout(&self) -> Option<&CParameter> {
+        self.transformations
+            .iter()
+            .find(|trans| {
+                self.c_parameters[trans.ind_c].direction == library::ParameterDirection::InOut
+                    && !matches!(
+                        trans.transformation_type,
+                        TransformationType::ToGlibDirect { .. }
+                            | TransformationType::ToGlibInOutScalar { .. }
+                    )
+            })
+            .map(|trans| &self.c_parameters[trans.ind_c])
+    }
 }
 
 pub fn analyze(
@@ -226,7 +262,13 @@ pub fn analyze(
         let configured_parameters = configured_functions.matched_parameters(&name);
 
         let c_type = par.c_type.clone();
-        let typ = override_string_type_parameter(env, par.typ, &configured_parameters);
+        let type_override = configured_parameters
+            .iter()
+            .find_map(|p| p.type_name.as_ref())
+            .and_then(|typ| env.library.find_type(0, typ));
+        let typ = type_override.unwrap_or_else(|| {
+            override_string_type_parameter(env, par.typ, &configured_parameters)
+        });
 
         let ind_c = parameters.c_parameters.len();
         let mut ind_rust = Some(parameters.rust_parameters.len());
@@ -304,14 +346,35 @@ pub fn analyze(
         }
 
         let immutable = configured_parameters.iter().any(|p| p.constant);
-        let ref_mode =
-            RefMode::without_unneeded_mut(env, par, immutable, in_trait && par.instance_parameter);
+        let ref_mode = configured_parameters
+            .iter()
+            .find_map(|p| p.ref_mode)
+            .filter(|_| par.direction.is_in())
+            .unwrap_or_else(|| {
+                RefMode::without_unneeded_mut(
+                    env,
+                    par,
+                    immutable,
+                    in_trait && par.instance_parameter,
+                )
+            });
 
         let nullable_override = configured_parameters.iter().find_map(|p| p.nullable);
-        let nullable = nullable_override.unwrap_or(par.nullable);
+        // Caller allocated outs are always filled in place, they are only
+        // returned as `Option<T>` if configured
+        let nullable = nullable_override.unwrap_or(Nullable(
+            *par.nullable && !(par.direction.is_out() && par.caller_allocates),
+        ));
 
         let try_from_glib = TryFromGlib::from_parameter(env, typ, &configured_parameters);
 
+        let to_value = configured_parameters.iter().any(|p| p.to_value)
+            && !par.instance_parameter
+            && par.direction.is_in()
+            && ref_mode == RefMode::ByRef
+            && !move_
+            && typ.full_name(&env.library) == "GObject.Value";
+
         let c_par = CParameter {
             name: name.clone(),
             typ,
@@ -328,6 +391,7 @@ pub fn analyze(
             destroy_index: par.destroy,
             try_from_glib: try_from_glib.clone(),
             move_,
+            to_value,
         };
         parameters.c_parameters.push(c_par);
 
@@ -345,10 +409,26 @@ pub fn analyze(
             ind_rust = None;
         }
 
+        let inout_scalar = par.direction == library::ParameterDirection::InOut
+            && matches!(conversion, ConversionType::Direct | ConversionType::Scalar);
         let transformation_type = match conversion {
             ConversionType::Direct => {
-                if par.c_type != "GLib.Pid" {
-                    TransformationType::ToGlibDirect { name }
+                if type_override.is_some() && is_pointer_c_type(&par.c_type) {
+                    // e.g. an integer handle passed as `gpointer`
+                    let pointer_cast = if is_const_pointer_c_type(&par.c_type) {
+                        " as *const _"
+                    } else {
+                        " as *mut _"
+                    };
+                    TransformationType::ToGlibDirect {
+                        name,
+                        pointer_cast: pointer_cast.into(),
+                    }
+                } else if par.c_type != "GLib.Pid" {
+                    TransformationType::ToGlibDirect {
+                        name,
+                        pointer_cast: String::new(),
+                    }
                 } else {
                     TransformationType::ToGlibScalar {
                         name,
@@ -391,7 +471,7 @@ pub fn analyze(
                 instance_parameter: par.instance_parameter,
                 transfer,
                 ref_mode,
-                to_glib_extra: Default::default(),
+                to_glib_extra: to_value_extra(to_value, *nullable).to_owned(),
                 explicit_target_type: Default::default(),
                 pointer_cast: if matches!(env.library.type_(typ), library::Type::CArray(_))
                     && par.c_type == "gpointer"
@@ -422,11 +502,23 @@ pub fn analyze(
                     transformation_type = Some(TransformationType::ToSome(name.clone()));
                 }
             }
+            TransformationType::ToGlibScalar { ref name, .. } if inout_scalar => {
+                transformation_type =
+                    Some(TransformationType::ToGlibInOutScalar { name: name.clone() });
+            }
             TransformationType::ToGlibPointer { ref name, .. } => {
                 if async_func && name == data_param_name {
                     // Do the conversion of user_data for async functions.
                     // In async functions, this argument is used to send the callback.
                     transformation_type = Some(TransformationType::IntoRaw(name.clone()));
+                } else if let library::Type::Array(inner_tid) = env.library.type_(typ) {
+                    if par.direction == library::ParameterDirection::In {
+                        transformation_type = Some(TransformationType::ToGlibArray {
+                            name: name.clone(),
+                            element_type: RustType::try_new(env, *inner_tid).into_string(),
+                            transfer,
+                        });
+                    }
                 }
             }
             _ => (),
@@ -440,6 +532,14 @@ pub fn analyze(
     parameters
 }
 
+pub fn is_pointer_c_type(c_type: &str) -> bool {
+    c_type == "gpointer" || c_type == "gconstpointer" || c_type.ends_with('*')
+}
+
+fn is_const_pointer_c_type(c_type: &str) -> bool {
+    c_type == "gconstpointer" || c_type.starts_with("const ")
+}
+
 fn get_length_type(
     env: &Env,
     array_name: &str,
@@ -486,6 +586,15 @@ fn is_length(par: &library::Parameter) -> bool {
     par.name.contains("length")
 }
 
+/// Conversion of a `GValue` parameter taken as `&dyn ToValue`
+fn to_value_extra(to_value: bool, nullable: bool) -> &'static str {
+    match (to_value, nullable) {
+        (false, _) => "",
+        (true, false) => ".to_value()",
+        (true, true) => ".map(|v| v.to_value())",
+    }
+}
+
 fn has_length(env: &Env, typ: TypeId) -> bool {
     use crate::library::{Basic::*, Type};
     let typ = env.library.type_(typ);
@@ -502,3 +611,73 @@ fn has_length(env: &Env, typ: TypeId) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(
+        direction: library::ParameterDirection,
+        transformation_type: TransformationType,
+    ) -> Parameters {
+        let mut parameters = Parameters::new(1);
+        parameters.c_parameters.push(CParameter {
+            name: "value".into(),
+            typ: TypeId::tid_none(),
+            c_type: "gpointer".into(),
+            instance_parameter: false,
+            direction,
+            nullable: Nullable(false),
+            transfer: Transfer::None,
+            caller_allocates: false,
+            is_error: false,
+            scope: ParameterScope::None,
+            user_data_index: None,
+            destroy_index: None,
+            ref_mode: RefMode::None,
+            try_from_glib: TryFromGlib::Default,
+            move_: false,
+            to_value: false,
+        });
+        parameters.transformations.push(Transformation {
+            ind_c: 0,
+            ind_rust: Some(0),
+            transformation_type,
+        });
+        parameters
+    }
+
+    #[test]
+    fn test_to_value_extra() {
+        assert_eq!(to_value_extra(false, false), "");
+        assert_eq!(to_value_extra(false, true), "");
+        assert_eq!(to_value_extra(true, false), ".to_value()");
+        assert_eq!(to_value_extra(true, true), ".map(|v| v.to_value())");
+    }
+
+    #[test]
+    fn test_unsupported_inout() {
+        use library::ParameterDirection::{In, InOut};
+
+        let scalar = || TransformationType::ToGlibInOutScalar {
+            name: "value".into(),
+        };
+        let pointer = || TransformationType::ToGlibPointer {
+            name: "value".into(),
+            instance_parameter: false,
+            transfer: Transfer::None,
+            ref_mode: RefMode::ByRefMut,
+            to_glib_extra: String::new(),
+            explicit_target_type: String::new(),
+            pointer_cast: String::new(),
+            in_trait: false,
+            nullable: false,
+            move_: false,
+        };
+        assert!(parameters(InOut, scalar()).unsupported_inout().is_none());
+        assert!(parameters(In, pointer()).unsupported_inout().is_none());
+        let parameters = parameters(InOut, pointer());
+        let par = parameters.unsupported_inout().map(|par| par.name.as_str());
+        assert_eq!(par, Some("value"));
+    }
+}
@@ -5,7 +5,7 @@
 
 use std::{
     borrow::Borrow,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
 };
 
 use log::warn;
@@ -15,6 +15,7 @@ use crate::{
     analysis::{
         self,
         bounds::{Bounds, CallbackInfo},
+        ffi_type::ffi_type,
         function_parameters::{self, CParameter, Parameters, Transformation, TransformationType},
         imports::Imports,
         is_gpointer,
@@ -27,7 +28,11 @@ use crate::{
         trampolines::Trampoline,
     },
     codegen::Visibility,
-    config::{self, gobjects::GStatus},
+    config::{
+        self,
+        attributes::{self, AttributeBehavior},
+        gobjects::GStatus,
+    },
     env::Env,
     library::{
         self, Function, FunctionKind, ParameterDirection, ParameterScope, Transfer, Type,
@@ -84,6 +89,9 @@ pub struct Info {
     pub doc_ignore_parameters: HashSet<String>,
     pub r#async: bool,
     pub unsafe_: bool,
+    /// Whether the method is forced to be generated on the type itself
+    /// instead of its extension trait
+    pub inherent: bool,
     pub trampoline: Option<AsyncTrampoline>,
     pub callbacks: Vec<Trampoline>,
     pub destroys: Vec<Trampoline>,
@@ -94,10 +102,33 @@ pub struct Info {
     pub hidden: bool,
     /// Whether the function can't be generated
     pub commented: bool,
+    /// Why the function can't be generated, set if it's `commented`
+    pub commented_reason: Option<String>,
     /// In order to generate docs links we need to know in which namespace
     /// this potential global function is defined
     pub ns_id: NsId,
     pub generate_doc: bool,
+    /// Whether `#[must_use]` is added because the return value must be checked
+    pub must_use: bool,
+    /// Code used instead of the generated function body
+    pub custom_body: Option<String>,
+    /// Whether the function is annotated as the finish function of an
+    /// asynchronous function
+    pub is_finish_func: bool,
+    /// The unsafe `_raw` variant generated instead of the function
+    pub raw: Option<RawFunction>,
+}
+
+/// Signature of the unsafe `_raw` variant of a function that can't be
+/// generated, which passes everything but the instance parameter unchanged
+#[derive(Debug)]
+pub struct RawFunction {
+    /// Conversion of the instance parameter, if any
+    pub instance: Option<TransformationType>,
+    /// Names and FFI types of the parameters, without the instance parameter
+    pub parameters: Vec<(String, String)>,
+    /// FFI type of the return value, if any
+    pub ret: Option<String>,
 }
 
 impl Info {
@@ -154,7 +185,7 @@ impl Info {
             .rust_parameters
             .iter()
             .any(|param| param.typ.full_name(&env.library) == "Gio.AsyncResult");
-        self.name.ends_with("_finish") && has_async_result
+        (self.is_finish_func || self.name.ends_with("_finish")) && has_async_result
     }
 }
 
@@ -170,29 +201,32 @@ pub fn analyze<F: Borrow<library::Function>>(
     deps: Option<&[library::TypeId]>,
 ) -> Vec<Info> {
     let mut funcs = Vec::new();
+    let function_names = generated_function_names(functions, |func| {
+        !is_skipped(env, obj, type_tid, func) && !is_shadowed(env, obj, type_tid, functions, func)
+    });
+    let prefixes: Vec<_> = obj
+        .strip_prefixes
+        .iter()
+        .chain(&env.config.strip_prefixes)
+        .collect();
+    let stripped_names = strip_prefixes(type_tid.unwrap_or_default(), &function_names, &prefixes);
 
-    'func: for func in functions {
+    for func in functions {
         let func = func.borrow();
         let configured_functions = obj.functions.matched(&func.name);
-        let mut status = obj.status;
-        for f in &configured_functions {
-            match f.status {
-                GStatus::Ignore => continue 'func,
-                GStatus::Manual => {
-                    status = GStatus::Manual;
-                    break;
-                }
-                GStatus::Generate => (),
-            }
-        }
-
-        if env.is_totally_deprecated(
-            Some(type_tid.unwrap_or_default().ns_id),
-            func.deprecated_version,
-        ) {
+        let status = match configured_status(obj.status, &configured_functions) {
+            Some(status) => status,
+            None => continue,
+        };
+        if is_skipped(env, obj, type_tid, func) || is_shadowed(env, obj, type_tid, functions, func)
+        {
             continue;
         }
-        let name = nameutil::mangle_keywords(&*func.name).into_owned();
+        let name = stripped_names
+            .get(func.name.as_str())
+            .copied()
+            .unwrap_or(&func.name);
+        let name = nameutil::mangle_keywords(name).into_owned();
         let signature_params = Signature::new(func);
         let mut not_version = None;
         if func.kind == library::FunctionKind::Method {
@@ -207,10 +241,16 @@ pub fn analyze<F: Borrow<library::Function>>(
                 }
             }
         }
+        // Functions removed in a later version are hidden when that version is enabled
+        if let Some(version_end) = version_end(env, &configured_functions, type_tid, func) {
+            not_version = Some(not_version.map_or(version_end, |v| v.min(version_end)));
+        }
         if let Some(signatures) = signatures.as_mut() {
             signatures.insert(name.clone(), signature_params);
         }
 
+        let in_trait = in_trait && !configured_functions.iter().any(|f| f.inherent);
+
         let mut info = analyze_function(
             env,
             obj,
@@ -222,6 +262,7 @@ pub fn analyze<F: Borrow<library::Function>>(
             in_trait,
             is_boxed,
             &configured_functions,
+            &function_names,
             imports,
         );
         info.not_version = not_version;
@@ -231,6 +272,211 @@ pub fn analyze<F: Borrow<library::Function>>(
     funcs
 }
 
+/// Names of the functions ending up in the bindings, only these can clash with
+/// renamed getters
+fn generated_function_names<F: Borrow<library::Function>>(
+    functions: &[F],
+    is_generated: impl Fn(&library::Function) -> bool,
+) -> HashSet<&str> {
+    functions
+        .iter()
+        .map(Borrow::borrow)
+        .filter(|func| is_generated(func))
+        .map(|func| func.name.as_str())
+        .collect()
+}
+
+/// Whether an automatically renamed function would take the name of another
+/// function, it keeps its `get_` prefix then
+fn rename_clashes(
+    type_tid: library::TypeId,
+    func_name: &str,
+    new_name: &str,
+    function_names: &HashSet<&str>,
+) -> bool {
+    let clashes = function_names.contains(new_name);
+    if clashes {
+        warn_main!(
+            type_tid,
+            "`{}`: not renamed to `{}`, which is the name of another function",
+            func_name,
+            new_name
+        );
+    }
+    clashes
+}
+
+/// Returns the names of the generated functions which lose one of the
+/// configured prefixes. A function keeps its name if it would take the name of
+/// another function, or the same name as another function without its prefix.
+fn strip_prefixes<'a>(
+    type_tid: library::TypeId,
+    function_names: &HashSet<&'a str>,
+    prefixes: &[&String],
+) -> HashMap<&'a str, &'a str> {
+    let mut stripped: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for &name in function_names {
+        let new_name = nameutil::strip_symbol_prefix(name, prefixes.iter().copied());
+        if new_name != name {
+            stripped.entry(new_name).or_default().push(name);
+        }
+    }
+
+    let mut names = HashMap::new();
+    for (new_name, mut old_names) in stripped {
+        old_names.sort_unstable();
+        if let [name] = old_names[..] {
+            if !rename_clashes(type_tid, name, new_name, function_names) {
+                names.insert(name, new_name);
+            }
+        } else {
+            warn_main!(
+                type_tid,
+                "`{}`: not renamed to `{}`, which is the name of each of them without its prefix",
+                old_names.join("`, `"),
+                new_name
+            );
+        }
+    }
+    names
+}
+
+/// Returns the name `name` is automatically renamed to, if any: constructors
+/// lose the `new_` of `new_from`/`new_with`/`new_for` and getters their `get_`
+/// prefix, or get an `is_` one if they return a boolean
+pub fn auto_rename(
+    func: &library::Function,
+    name: &str,
+    is_constructor: bool,
+    rename_getter: bool,
+    rename_bool_getter: bool,
+) -> Option<String> {
+    if is_constructor {
+        if name.starts_with("new_from")
+            || name.starts_with("new_with")
+            || name.starts_with("new_for")
+        {
+            Some(name[4..].to_string())
+        } else {
+            None
+        }
+    } else if rename_getter {
+        let is_bool_getter = rename_bool_getter
+            && func
+                .parameters
+                .iter()
+                .all(|param| param.direction == library::ParameterDirection::In)
+            && (func.ret.typ == library::TypeId::tid_bool()
+                || func.ret.typ == library::TypeId::tid_c_bool());
+        getter_rules::try_rename_would_be_getter(name, is_bool_getter)
+            .ok()
+            .map(getter_rules::NewName::unwrap)
+    } else {
+        None
+    }
+}
+
+/// Status of a function after applying its configuration, `None` if it's ignored
+fn configured_status(
+    status: GStatus,
+    configured_functions: &[&config::functions::Function],
+) -> Option<GStatus> {
+    for f in configured_functions {
+        match f.status {
+            GStatus::Ignore => return None,
+            GStatus::Manual => return Some(GStatus::Manual),
+            GStatus::Generate => (),
+        }
+    }
+    Some(status)
+}
+
+/// Version removing the function from the API, if any. Functions using a
+/// removed type other than their own are removed along with it.
+fn version_end(
+    env: &Env,
+    configured_functions: &[&config::functions::Function],
+    type_tid: Option<library::TypeId>,
+    func: &library::Function,
+) -> Option<Version> {
+    let version_end = configured_functions
+        .iter()
+        .find_map(|f| f.version_end)
+        .or_else(|| {
+            env.config
+                .attribute_value(&func.attributes, AttributeBehavior::VersionEnd)
+                .and_then(|v| v.parse().ok())
+        });
+    let types = func
+        .parameters
+        .iter()
+        .chain(Some(&func.ret))
+        .map(|par| par.typ)
+        .filter(|&tid| Some(tid) != type_tid);
+    version_end
+        .into_iter()
+        .chain(env.types_version_end(types))
+        .min()
+}
+
+/// Whether the function is left out of the bindings, regardless of its signature
+fn is_skipped(
+    env: &Env,
+    obj: &config::gobjects::GObject,
+    type_tid: Option<library::TypeId>,
+    func: &library::Function,
+) -> bool {
+    let configured_functions = obj.functions.matched(&func.name);
+    configured_status(obj.status, &configured_functions).is_none()
+        || env
+            .config
+            .attribute_value(&func.attributes, AttributeBehavior::Skip)
+            .and_then(attributes::parse_bool)
+            .unwrap_or(false)
+        || env.is_totally_deprecated(
+            Some(type_tid.unwrap_or_default().ns_id),
+            configured_functions
+                .iter()
+                .find_map(|f| f.deprecated_version)
+                .or(func.deprecated_version),
+        )
+        || version_end(env, &configured_functions, type_tid, func)
+            .map_or(false, |v| v <= env.config.min_cfg_version)
+}
+
+fn is_renamed(env: &Env, obj: &config::gobjects::GObject, func: &library::Function) -> bool {
+    obj.functions
+        .matched(&func.name)
+        .iter()
+        .any(|f| f.rename.is_some())
+        || env
+            .config
+            .attribute_value(&func.attributes, AttributeBehavior::Rename)
+            .is_some()
+}
+
+/// Whether the function replacing this one is generated under its name instead
+fn is_shadowed<F: Borrow<library::Function>>(
+    env: &Env,
+    obj: &config::gobjects::GObject,
+    type_tid: Option<library::TypeId>,
+    functions: &[F],
+    func: &library::Function,
+) -> bool {
+    let shadowed_by = match func.shadowed_by {
+        Some(ref shadowed_by) => shadowed_by,
+        None => return false,
+    };
+    !is_renamed(env, obj, func)
+        && functions
+            .iter()
+            .map(Borrow::borrow)
+            .find(|f| &f.name == shadowed_by)
+            .map_or(false, |f| {
+                !is_skipped(env, obj, type_tid, f) && !is_renamed(env, obj, f)
+            })
+}
+
 fn fixup_gpointer_parameter(
     env: &Env,
     type_tid: library::TypeId,
@@ -491,6 +737,27 @@ fn analyze_callbacks(
                 );
             }
         }
+        // An async callback with a destroy notify is taken out of its box when
+        // called, which isn't possible if it shares the user data
+        let shared_async_with_destroy = callbacks.iter().any(|callback| {
+            callback.scope.is_async()
+                && cross_user_data_check
+                    .values()
+                    .any(|p| *p == callback.user_data_index)
+                && callbacks
+                    .iter()
+                    .filter(|c| c.user_data_index == callback.user_data_index)
+                    .count()
+                    > 1
+        });
+        if shared_async_with_destroy {
+            warn_main!(
+                type_tid,
+                "`{}`: async callbacks with a destroy notify can't share the user data",
+                func_name
+            );
+            *commented = true;
+        }
     }
 
     // Check for cross "user data".
@@ -554,6 +821,7 @@ fn analyze_function(
     in_trait: bool,
     is_boxed: bool,
     configured_functions: &[&config::functions::Function],
+    function_names: &HashSet<&str>,
     imports: &mut Imports,
 ) -> Info {
     let ns_id = type_tid.map_or(MAIN_NAMESPACE, |t| t.ns_id);
@@ -574,6 +842,7 @@ fn analyze_function(
     };
 
     let mut commented = false;
+    let mut commented_reason = None;
     let mut bounds: Bounds = Default::default();
     let mut to_glib_extras = HashMap::<usize, String>::new();
     let mut used_types: Vec<String> = Vec::with_capacity(4);
@@ -598,43 +867,58 @@ fn analyze_function(
             func.name
         );
         commented = true;
+        commented_reason = Some("destroy callback without callbacks".to_owned());
     }
 
-    let mut new_name = configured_functions.iter().find_map(|f| f.rename.clone());
+    // A function that shadows another one takes its name, which then goes
+    // through the renaming rules
+    let shadowed_name = func
+        .shadows
+        .as_ref()
+        .map(|shadows| nameutil::mangle_keywords(shadows.as_str()).into_owned());
+    let base_name = shadowed_name.as_deref().unwrap_or(&name);
+    let mut new_name = configured_functions
+        .iter()
+        .find_map(|f| f.rename.clone())
+        .or_else(|| {
+            env.config
+                .attribute_value(&func.attributes, AttributeBehavior::Rename)
+                .map(ToOwned::to_owned)
+        });
     let is_constructor = configured_functions.iter().find_map(|f| f.is_constructor);
 
     let bypass_auto_rename = configured_functions.iter().any(|f| f.bypass_auto_rename);
     let is_constructor = is_constructor.unwrap_or(false);
     if !bypass_auto_rename && new_name.is_none() {
-        if func.kind == library::FunctionKind::Constructor || is_constructor {
-            if func.kind == library::FunctionKind::Constructor && is_constructor {
-                warn_main!(
-                    type_tid,
-                    "`{}`: config forces 'constructor' on an already gir-annotated 'constructor'",
-                    func_name
-                );
-            }
-
-            if name.starts_with("new_from")
-                || name.starts_with("new_with")
-                || name.starts_with("new_for")
-            {
-                new_name = Some(name[4..].to_string());
-            }
-        } else {
-            let nb_in_params = func
-                .parameters
+        if func.kind == library::FunctionKind::Constructor && is_constructor {
+            warn_main!(
+                type_tid,
+                "`{}`: config forces 'constructor' on an already gir-annotated 'constructor'",
+                func_name
+            );
+        }
+        let is_constructor = func.kind == library::FunctionKind::Constructor || is_constructor;
+        new_name = auto_rename(
+            func,
+            base_name,
+            is_constructor,
+            configured_functions
+                .iter()
+                .find_map(|f| f.rename_getter)
+                .unwrap_or(env.config.rename_getters),
+            configured_functions
                 .iter()
-                .filter(|param| library::ParameterDirection::In == param.direction)
-                .fold(0, |acc, _| acc + 1);
-            let is_bool_getter = (func.parameters.len() == nb_in_params)
-                && (func.ret.typ == library::TypeId::tid_bool()
-                    || func.ret.typ == library::TypeId::tid_c_bool());
-            new_name = getter_rules::try_rename_would_be_getter(&name, is_bool_getter)
-                .ok()
-                .map(getter_rules::NewName::unwrap);
+                .find_map(|f| f.rename_bool_getter)
+                .unwrap_or(env.config.rename_bool_getters),
+        );
+        if !is_constructor {
+            new_name = new_name
+                .filter(|new_name| !rename_clashes(type_tid, func_name, new_name, function_names));
         }
     }
+    if new_name.is_none() {
+        new_name = shadowed_name;
+    }
 
     let version = configured_functions
         .iter()
@@ -643,7 +927,10 @@ fn analyze_function(
         .or(func.version);
 
     let version = env.config.filter_version(version);
-    let deprecated_version = func.deprecated_version;
+    let deprecated_version = configured_functions
+        .iter()
+        .find_map(|f| f.deprecated_version)
+        .or(func.deprecated_version);
     let visibility = configured_functions
         .iter()
         .find_map(|f| f.visibility)
@@ -666,6 +953,8 @@ fn analyze_function(
     let disable_length_detect = configured_functions.iter().any(|f| f.disable_length_detect);
     let no_future = configured_functions.iter().any(|f| f.no_future);
     let unsafe_ = configured_functions.iter().any(|f| f.unsafe_);
+    let inherent = func.kind == library::FunctionKind::Method
+        && configured_functions.iter().any(|f| f.inherent);
     let assertion = configured_functions.iter().find_map(|f| f.assertion);
 
     let imports = &mut imports.with_defaults(version, &cfg_condition);
@@ -679,7 +968,13 @@ fn analyze_function(
         &mut used_types,
         imports,
     );
-    commented |= ret.commented;
+    if ret.commented {
+        commented = true;
+        commented_reason.get_or_insert_with(|| "unsupported return type".to_owned());
+    }
+    // The caller has to ensure that a borrowed return value doesn't outlive
+    // its owner
+    let unsafe_ = unsafe_ || ret.borrowed;
 
     let mut params = func.parameters.clone();
     let mut parameters = function_parameters::analyze(
@@ -697,6 +992,7 @@ fn analyze_function(
             if env.config.work_mode.is_normal() {
                 warn!("Function \"{}\" returns callback", func.name);
                 commented = true;
+                commented_reason.get_or_insert_with(|| "returns a callback".to_owned());
             }
         }
     }
@@ -764,6 +1060,7 @@ fn analyze_function(
                     to_glib_extras.insert(pos, to_glib_extra);
                 }
 
+                let was_commented = commented;
                 analyze_async(
                     env,
                     func,
@@ -777,6 +1074,11 @@ fn analyze_function(
                     configured_functions,
                     &parameters,
                 );
+                if commented && !was_commented {
+                    commented_reason.get_or_insert_with(|| {
+                        format!("unsupported async callback parameter `{}`", par.name)
+                    });
+                }
                 let type_error = !(r#async
                     && *env.library.type_(par.typ) == Type::Basic(library::Basic::Pointer))
                     && RustType::builder(env, par.typ)
@@ -787,12 +1089,18 @@ fn analyze_function(
                         .is_err();
                 if type_error {
                     commented = true;
+                    commented_reason.get_or_insert_with(|| {
+                        format!("unsupported type of parameter `{}`", par.name)
+                    });
                 }
             }
             if r#async && trampoline.is_none() {
                 commented = true;
+                commented_reason
+                    .get_or_insert_with(|| "async function without finish function".to_owned());
             }
         } else {
+            let was_commented = commented;
             analyze_callbacks(
                 env,
                 func,
@@ -813,6 +1121,21 @@ fn analyze_function(
                 concurrency,
                 type_tid,
             );
+            if commented && !was_commented {
+                let unsupported_par = parameters.c_parameters.iter().find(|par| {
+                    !env.library.type_(par.typ).is_function()
+                        && RustType::builder(env, par.typ)
+                            .direction(par.direction)
+                            .scope(par.scope)
+                            .try_from_glib(&par.try_from_glib)
+                            .try_build_param()
+                            .is_err()
+                });
+                commented_reason.get_or_insert_with(|| match unsupported_par {
+                    Some(par) => format!("unsupported type of parameter `{}`", par.name),
+                    None => "unsupported callback parameters".to_owned(),
+                });
+            }
         }
     }
 
@@ -829,9 +1152,23 @@ fn analyze_function(
             && !parameters.transformations.iter().any(is_len_for_par)
         {
             commented = true;
+            commented_reason
+                .get_or_insert_with(|| format!("array parameter `{}` without length", par.name));
         }
     }
 
+    if let Some(par) = parameters.unsupported_inout() {
+        warn_main!(
+            type_tid,
+            "Function {} has unsupported inout parameter `{}`",
+            func.c_identifier.as_ref().unwrap_or(&func.name),
+            par.name
+        );
+        commented = true;
+        commented_reason
+            .get_or_insert_with(|| format!("unsupported inout parameter `{}`", par.name));
+    }
+
     let (outs, unsupported_outs) = out_parameters::analyze(
         env,
         func,
@@ -846,6 +1183,7 @@ fn analyze_function(
             func.c_identifier.as_ref().unwrap_or(&func.name)
         );
         commented = true;
+        commented_reason.get_or_insert_with(|| "unsupported out parameters".to_owned());
     } else if status.need_generate() && !commented {
         if !outs.is_empty() {
             out_parameters::analyze_imports(env, &func.parameters, imports);
@@ -903,7 +1241,7 @@ fn analyze_function(
         }
 
         imports.add_used_types(&used_types);
-        if ret.base_tid.is_some() {
+        if ret.base_tid.is_some() || parameters.c_parameters.iter().any(|p| p.to_value) {
             imports.add("glib::prelude::*");
         }
 
@@ -920,6 +1258,27 @@ fn analyze_function(
         assertion.unwrap_or_else(|| SafetyAssertionMode::of(env, is_method, &parameters));
 
     let generate_doc = configured_functions.iter().all(|f| f.generate_doc);
+    // Ignoring the `None` returned by a fallible constructor is almost always a bug.
+    // `Result` is already `#[must_use]` itself.
+    let must_use = configured_functions
+        .iter()
+        .find_map(|f| f.must_use)
+        .unwrap_or_else(|| {
+            (func.kind == library::FunctionKind::Constructor || is_constructor)
+                && !func.throws
+                && !r#async
+                && ret.nullable_return_is_error.is_none()
+                && ret.parameter.is_some()
+                && !ret.is_non_nullable()
+        });
+    let raw = if commented && configured_functions.iter().any(|f| f.generate_raw) {
+        analyze_raw(env, func, &parameters, &to_glib_extras)
+    } else {
+        None
+    };
+    if raw.is_some() && status.need_generate() {
+        imports.add("glib::translate::*");
+    }
 
     Info {
         name,
@@ -945,18 +1304,74 @@ fn analyze_function(
         doc_ignore_parameters,
         r#async,
         unsafe_,
+        inherent,
         trampoline,
         async_future,
         callbacks,
         destroys,
         remove_params: cross_user_data_check.values().copied().collect::<Vec<_>>(),
         commented,
+        commented_reason,
         hidden: false,
         ns_id,
         generate_doc,
+        must_use,
+        custom_body: configured_functions.iter().find_map(|f| f.body.clone()),
+        is_finish_func: func.async_func.is_some(),
+        raw,
     }
 }
 
+fn analyze_raw(
+    env: &Env,
+    func: &library::Function,
+    analyzed_parameters: &Parameters,
+    to_glib_extras: &HashMap<usize, String>,
+) -> Option<RawFunction> {
+    // The extra conversions are only applied to functions that are generated
+    let instance = analyzed_parameters
+        .transformations
+        .iter()
+        .find(|tr| analyzed_parameters.c_parameters[tr.ind_c].instance_parameter)
+        .map(|tr| {
+            let mut transformation_type = tr.transformation_type.clone();
+            if let Some(to_glib_extra) = to_glib_extras.get(&tr.ind_c) {
+                transformation_type.set_to_glib_extra(to_glib_extra);
+            }
+            transformation_type
+        });
+    let mut parameters = Vec::with_capacity(func.parameters.len());
+    for par in func.parameters.iter().filter(|par| !par.instance_parameter) {
+        // Also fails for varargs
+        let typ = match ffi_type(env, par.typ, &par.c_type) {
+            Ok(typ) => typ.into_string(),
+            Err(_) => {
+                warn!(
+                    "No raw variant of `{}`, unsupported type of parameter `{}`",
+                    func.c_identifier.as_ref().unwrap(),
+                    par.name
+                );
+                return None;
+            }
+        };
+        parameters.push((nameutil::mangle_keywords(&*par.name).into_owned(), typ));
+    }
+    let ret = if func.ret.typ == library::TypeId::tid_none() {
+        None
+    } else {
+        Some(
+            ffi_type(env, func.ret.typ, &func.ret.c_type)
+                .ok()?
+                .into_string(),
+        )
+    };
+    Some(RawFunction {
+        instance,
+        parameters,
+        ret,
+    })
+}
+
 pub fn is_carray_with_direct_elements(env: &Env, typ: library::TypeId) -> bool {
     match *env.library.type_(typ) {
         Type::CArray(inner_tid) => {
@@ -989,8 +1404,7 @@ fn analyze_async(
     {
         // Checks for /*Ignored*/ or other error comments
         *commented |= callback_type.contains("/*");
-        let func_name = func.c_identifier.as_ref().unwrap();
-        let finish_func_name = finish_function_name(func_name);
+        let finish_func_name = finish_function_c_identifier(func);
         let mut output_params = vec![];
         let mut ffi_ret = None;
         if let Some(function) = find_function(env, &finish_func_name) {
@@ -1299,6 +1713,23 @@ pub fn find_function<'a>(env: &'a Env, c_identifier: &str) -> Option<&'a Functio
     None
 }
 
+/// Returns the C identifier of the finish function of the given async
+/// function, from its `glib:finish-func` annotation if it has one.
+pub fn finish_function_c_identifier(func: &library::Function) -> String {
+    func.finish_func
+        .as_ref()
+        .and_then(|finish_func| sibling_c_identifier(func, finish_func))
+        .unwrap_or_else(|| finish_function_name(func.c_identifier.as_ref().unwrap()))
+}
+
+/// Returns the C identifier of the function named `name` that is defined
+/// next to the given function, e.g. its synchronous or finish variant.
+pub fn sibling_c_identifier(func: &library::Function, name: &str) -> Option<String> {
+    let c_identifier = func.c_identifier.as_ref()?;
+    let prefix = c_identifier.strip_suffix(func.name.as_str())?;
+    Some(format!("{prefix}{name}"))
+}
+
 /// Given async function name tries to guess the name of finish function.
 pub fn finish_function_name(mut func_name: &str) -> String {
     if func_name.ends_with("_async") {
@@ -1331,4 +1762,108 @@ mod tests {
         );
         assert_eq!("g_bus_get_finish", &finish_function_name("g_bus_get"));
     }
+
+    #[test]
+    fn test_configured_status_ignored_clash() {
+        let toml: ::toml::value::Table = ::toml::from_str(
+            r#"
+[[f]]
+name = "get_visible"
+[[f]]
+name = "is_visible"
+ignore = true
+"#,
+        )
+        .unwrap();
+        let fns = config::functions::Functions::parse(toml.get("f"), "a");
+        assert_eq!(
+            configured_status(GStatus::Generate, &fns.matched("get_visible")),
+            Some(GStatus::Generate)
+        );
+        assert_eq!(
+            configured_status(GStatus::Generate, &fns.matched("is_visible")),
+            None
+        );
+        assert_eq!(
+            configured_status(GStatus::Manual, &fns.matched("other")),
+            Some(GStatus::Manual)
+        );
+
+        let functions = [
+            bool_getter("get_visible"),
+            bool_getter("is_visible"),
+            bool_getter("get_active"),
+            bool_getter("is_active"),
+        ];
+        let function_names = generated_function_names(&functions, |func| {
+            configured_status(GStatus::Generate, &fns.matched(&func.name)).is_some()
+        });
+        let renamed = |func: &library::Function| {
+            auto_rename(func, &func.name, false, true, true).filter(|new_name| {
+                !rename_clashes(Default::default(), &func.name, new_name, &function_names)
+            })
+        };
+        // `get_visible` can take the name of the ignored `is_visible`
+        assert_eq!(renamed(&functions[0]).as_deref(), Some("is_visible"));
+        // `get_active` keeps its name as `is_active` is generated
+        assert_eq!(renamed(&functions[2]), None);
+    }
+
+    #[test]
+    fn test_strip_prefixes() {
+        let function_names = [
+            "vendor_foo",
+            "foo",
+            "vendor_bar",
+            "legacy_bar",
+            "vendor_baz",
+            "qux",
+        ]
+        .into_iter()
+        .collect();
+        let prefixes = ["vendor".to_owned(), "legacy".to_owned()];
+        let prefixes: Vec<_> = prefixes.iter().collect();
+        let names = strip_prefixes(Default::default(), &function_names, &prefixes);
+        // `vendor_foo` would clash with `foo`, `vendor_bar` and `legacy_bar`
+        // with each other
+        assert_eq!(names, [("vendor_baz", "baz")].into_iter().collect());
+    }
+
+    fn bool_getter(name: &str) -> library::Function {
+        let ret = library::Parameter {
+            name: String::new(),
+            typ: library::TypeId::tid_bool(),
+            c_type: "gboolean".into(),
+            instance_parameter: false,
+            direction: ParameterDirection::Return,
+            transfer: library::Transfer::None,
+            caller_allocates: false,
+            nullable: library::Nullable(false),
+            array_length: None,
+            is_error: false,
+            doc: None,
+            scope: ParameterScope::None,
+            closure: None,
+            destroy: None,
+        };
+        library::Function {
+            name: name.into(),
+            c_identifier: Some(format!("test_widget_{name}")),
+            kind: library::FunctionKind::Method,
+            parameters: Vec::new(),
+            ret,
+            throws: false,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Vec::new(),
+            shadows: None,
+            shadowed_by: None,
+            finish_func: None,
+            sync_func: None,
+            async_func: None,
+            invoker: None,
+        }
+    }
 }
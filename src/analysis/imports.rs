@@ -356,9 +356,10 @@ impl ImportConditions {
             return;
         }
         // Otherwise, we just check if the constraint
-        // is already present or not before adding it.
-        if !self.constraints.iter().any(|x| x == &constraint) {
-            self.constraints.push(constraint);
+        // is already present or not before adding it. The list is kept sorted
+        // so the generated `cfg(any(...))` doesn't depend on the analysis order.
+        if let Err(pos) = self.constraints.binary_search(&constraint) {
+            self.constraints.insert(pos, constraint);
         }
     }
 
@@ -376,8 +377,8 @@ impl ImportConditions {
             // Otherwise, we just check if the constraint
             // is already present or not before adding it.
             for constraint in constraints {
-                if !self.constraints.iter().any(|x| x == constraint) {
-                    self.constraints.push(constraint.clone());
+                if let Err(pos) = self.constraints.binary_search(constraint) {
+                    self.constraints.insert(pos, constraint.clone());
                 }
             }
         }
@@ -32,6 +32,16 @@ impl InfoBase {
             .collect()
     }
 
+    /// Methods that are generated into the extension trait, if there is one
+    pub fn trait_methods(&self) -> Vec<&functions::Info> {
+        self.functions
+            .iter()
+            .filter(|f| {
+                f.status.need_generate() && f.kind == library::FunctionKind::Method && !f.inherent
+            })
+            .collect()
+    }
+
     pub fn functions(&self) -> Vec<&functions::Info> {
         self.functions
             .iter()
@@ -44,6 +44,7 @@ pub mod trampoline_parameters;
 pub mod trampolines;
 pub mod try_from_glib;
 pub mod types;
+pub mod vfuncs;
 
 #[derive(Debug, Default)]
 pub struct Analysis {
@@ -282,6 +283,9 @@ fn analyze_global_functions(env: &mut Env) {
 
     let mut imports = imports::Imports::new(&env.library);
     imports.add("glib::translate::*");
+    for import in &obj.extra_imports {
+        imports.add(import);
+    }
 
     let functions = functions::analyze(
         env,
@@ -39,7 +39,9 @@ impl Index<NsId> for Info {
     }
 }
 
-pub fn run(gir: &library::Library) -> Info {
+/// Analyzes the namespaces of `gir`. The sys crate of the main namespace is
+/// referenced as `ffi` unless `main_sys_crate_path` is given.
+pub fn run(gir: &library::Library, main_sys_crate_path: Option<&str>) -> Info {
     let mut namespaces = Vec::with_capacity(gir.namespaces.len());
     let mut is_glib_crate = false;
     let mut glib_ns_id = None;
@@ -51,6 +53,10 @@ pub fn run(gir: &library::Library) -> Info {
             "gobject" => ("gobject_ffi".to_owned(), "glib".to_owned()),
             _ => ("ffi".to_owned(), crate_name.clone()),
         };
+        let sys_crate_name = match main_sys_crate_path {
+            Some(path) if ns_id == MAIN => path.to_owned(),
+            _ => sys_crate_name,
+        };
         namespaces.push(Namespace {
             name: ns.name.clone(),
             crate_name,
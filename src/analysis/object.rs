@@ -41,6 +41,8 @@ pub struct Info {
     pub has_constructors: bool,
     pub has_functions: bool,
     pub virtual_methods: Vec<functions::Info>,
+    /// Fields of the class or interface struct holding the virtual methods
+    pub vfuncs: Vec<vfuncs::Info>,
     pub signals: Vec<signals::Info>,
     pub notify_signals: Vec<signals::Info>,
     pub properties: Vec<properties::Property>,
@@ -70,7 +72,7 @@ impl Info {
         self.has_constructors
             || has_builder_properties(&self.builder_properties)
             || !(self.need_generate_trait()
-                && self.methods().is_empty()
+                && self.trait_methods().is_empty()
                 && self.properties.is_empty()
                 && self.child_properties.is_empty()
                 && self.signals.is_empty())
@@ -81,6 +83,7 @@ impl Info {
         self.has_constructors
             || self.has_functions
             || !self.need_generate_trait()
+            || self.methods().len() != self.trait_methods().len()
             || has_builder_properties(&self.builder_properties)
     }
 
@@ -102,6 +105,7 @@ impl Info {
             LocationInObject::VirtualExt
         } else if self.final_type
             || self.is_fundamental
+            || fn_info.inherent
             || matches!(
                 fn_info.kind,
                 FunctionKind::Constructor | FunctionKind::Function
@@ -186,6 +190,9 @@ pub fn class(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<Info>
     let deprecated_version = klass.deprecated_version;
 
     let mut imports = Imports::with_defined(&env.library, &name);
+    for import in &obj.extra_imports {
+        imports.add(import);
+    }
     if obj.generate_display_trait {
         imports.add("std::fmt");
     }
@@ -250,6 +257,7 @@ pub fn class(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<Info>
         specials.traits_mut().remove(t);
     }
     special_functions::analyze_imports(&specials, &mut imports);
+    let vfuncs = vfuncs::analyze(&klass.virtual_methods, &functions);
 
     let signals = signals::analyze(
         env,
@@ -279,9 +287,9 @@ pub fn class(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<Info>
     let child_properties =
         child_properties::analyze(env, obj.child_properties.as_ref(), class_tid, &mut imports);
 
-    let has_methods = functions
-        .iter()
-        .any(|f| f.kind == library::FunctionKind::Method && f.status.need_generate());
+    let has_methods = functions.iter().any(|f| {
+        f.kind == library::FunctionKind::Method && f.status.need_generate() && !f.inherent
+    });
     let has_signals = signals.iter().any(|s| s.trampoline.is_ok())
         || notify_signals.iter().any(|s| s.trampoline.is_ok());
     // There's no point in generating a trait if there are no signals, methods,
@@ -322,12 +330,19 @@ pub fn class(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<Info>
     // patch up trait methods in the symbol table
     if generate_trait {
         let mut symbols = env.symbols.borrow_mut();
-        for func in base.methods() {
+        for func in base.trait_methods() {
             if let Some(symbol) = symbols.by_c_name_mut(&func.glib_name) {
                 symbol.make_trait_method(&trait_name);
             }
         }
     }
+    if let Some(ref module_path) = obj.module_path {
+        env.symbols.borrow_mut().make_in_module(
+            &klass.c_type,
+            base.functions.iter().map(|f| f.glib_name.as_str()),
+            module_path,
+        );
+    }
 
     let has_constructors = !base.constructors().is_empty();
     let has_functions = !base.functions().is_empty();
@@ -346,6 +361,7 @@ pub fn class(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<Info>
         has_constructors,
         has_functions,
         virtual_methods,
+        vfuncs,
         signals,
         notify_signals,
         properties,
@@ -377,6 +393,9 @@ pub fn interface(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<I
 
     let mut imports = Imports::with_defined(&env.library, &name);
     imports.add("glib::prelude::*");
+    for import in &obj.extra_imports {
+        imports.add(import);
+    }
     if obj.generate_display_trait {
         imports.add("std::fmt");
     }
@@ -411,6 +430,7 @@ pub fn interface(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<I
         Some(&mut signatures),
         Some(deps),
     );
+    let vfuncs = vfuncs::analyze(&iface.virtual_methods, &functions);
 
     let signals = signals::analyze(
         env,
@@ -448,6 +468,14 @@ pub fn interface(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<I
         visibility: obj.visibility,
     };
 
+    if let Some(ref module_path) = obj.module_path {
+        env.symbols.borrow_mut().make_in_module(
+            &iface.c_type,
+            base.functions.iter().map(|f| f.glib_name.as_str()),
+            module_path,
+        );
+    }
+
     let has_functions = !base.functions().is_empty();
 
     let info = Info {
@@ -461,6 +489,7 @@ pub fn interface(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<I
         generate_trait: true,
         trait_name,
         has_functions,
+        vfuncs,
         signals,
         notify_signals,
         properties,
@@ -38,6 +38,8 @@ pub enum Mode {
 pub struct Info {
     pub mode: Mode,
     pub params: Vec<analysis::Parameter>,
+    /// The return value comes after the out parameters in the returned tuple
+    pub ret_last: bool,
 }
 
 impl Info {
@@ -120,7 +122,15 @@ pub fn analyze(
         if let Some(val) = nullable_override {
             ret.lib_par.nullable = val;
         }
-        info.params.insert(0, ret);
+        info.ret_last = configured_functions
+            .iter()
+            .find_map(|f| f.ret.after_outs)
+            .unwrap_or(false);
+        if info.ret_last {
+            info.params.push(ret);
+        } else {
+            info.params.insert(0, ret);
+        }
     }
 
     (info, unsupported_outs)
@@ -26,12 +26,17 @@ pub struct Property {
     pub func_name: String,
     pub func_name_alias: Option<String>,
     pub nullable: library::Nullable,
+    /// Message of the panic of a getter on NULL, which then returns `T`
+    /// instead of `Option<T>`
+    pub null_panic_message: Option<String>,
     pub get_out_ref_mode: RefMode,
     pub set_in_ref_mode: RefMode,
     pub bounds: Bounds,
     pub set_bound: Option<PropertyBound>,
     pub version: Option<Version>,
     pub deprecated_version: Option<Version>,
+    /// Version removing the type of the property from the API, if any
+    pub not_version: Option<Version>,
 }
 
 pub fn analyze(
@@ -58,7 +63,13 @@ pub fn analyze(
             continue;
         }
 
-        if env.is_totally_deprecated(Some(type_tid.ns_id), prop.deprecated_version) {
+        let deprecated_version = configured_properties
+            .iter()
+            .find_map(|f| f.deprecated_version)
+            .or(prop.deprecated_version);
+        if env.is_totally_deprecated(Some(type_tid.ns_id), deprecated_version)
+            || env.uses_removed_type([prop.typ])
+        {
             continue;
         }
 
@@ -118,6 +129,11 @@ fn analyze_property(
         .min()
         .or(prop.version)
         .or(Some(env.config.min_cfg_version));
+    let prop_deprecated_version = configured_properties
+        .iter()
+        .find_map(|f| f.deprecated_version)
+        .or(prop.deprecated_version);
+    let not_version = env.types_version_end([prop.typ]);
     let generate = configured_properties.iter().find_map(|f| f.generate);
     let generate_set = generate.is_some();
     let generate = generate.unwrap_or_else(PropertyGenerateFlags::all);
@@ -157,10 +173,17 @@ fn analyze_property(
         );
     }
     if generate_set && generate.contains(PropertyGenerateFlags::SET) && !writable {
-        warn!(
-            "Attempt to generate setter for nonwritable property \"{}.{}\"",
-            type_name, name
-        );
+        if prop.construct_only {
+            warn!(
+                "Attempt to generate setter for construct-only property \"{}.{}\", it can only be set with the builder",
+                type_name, name
+            );
+        } else {
+            warn!(
+                "Attempt to generate setter for nonwritable property \"{}.{}\"",
+                type_name, name
+            );
+        }
     }
     readable &= generate.contains(PropertyGenerateFlags::GET);
     writable &= generate.contains(PropertyGenerateFlags::SET);
@@ -230,6 +253,17 @@ fn analyze_property(
             imports.add("glib::prelude::*");
         }
 
+        let null_panic_message = if *nullable {
+            getter_null_panic_message(
+                configured_properties,
+                env.config.panic_on_null_properties,
+                &name,
+                &type_name,
+            )
+        } else {
+            None
+        };
+
         Some(Property {
             name: name.clone(),
             var_name: nameutil::mangle_keywords(&*name_for_func).into_owned(),
@@ -237,13 +271,15 @@ fn analyze_property(
             is_get: true,
             func_name: get_func_name,
             func_name_alias: get_prop_name,
-            nullable,
+            nullable: library::Nullable(*nullable && null_panic_message.is_none()),
+            null_panic_message,
             get_out_ref_mode,
             set_in_ref_mode,
             set_bound: None,
             bounds: Bounds::default(),
             version: prop_version,
-            deprecated_version: prop.deprecated_version,
+            deprecated_version: prop_deprecated_version,
+            not_version,
         })
     } else {
         None
@@ -279,12 +315,14 @@ fn analyze_property(
             func_name: set_func_name,
             func_name_alias: set_prop_name,
             nullable,
+            null_panic_message: None,
             get_out_ref_mode,
             set_in_ref_mode,
             set_bound,
             bounds: Bounds::default(),
             version: prop_version,
-            deprecated_version: prop.deprecated_version,
+            deprecated_version: prop_deprecated_version,
+            not_version,
         })
     } else {
         None
@@ -324,7 +362,7 @@ fn analyze_property(
                 is_detailed: false, /* well, technically this *is* an instance of a detailed
                                      * signal, but we "pre-detailed" it */
                 version: prop_version,
-                deprecated_version: prop.deprecated_version,
+                deprecated_version: prop_deprecated_version,
                 doc: None,
                 doc_deprecated: None,
             },
@@ -346,16 +384,20 @@ fn analyze_property(
             imports.add("std::mem::transmute");
             imports.add("std::boxed::Box as Box_");
 
+            let generate_local = signals::needs_local_connect(obj, &trampoline);
             Some(signals::Info {
                 connect_name: format!("connect_{name_for_func}_notify"),
                 signal_name: format!("notify::{name}"),
                 trampoline,
                 action_emit_name: None,
                 version: prop_version,
-                deprecated_version: prop.deprecated_version,
+                deprecated_version: prop_deprecated_version,
+                not_version,
                 doc_hidden: false,
                 is_detailed: false, // see above comment
                 generate_doc: obj.generate_doc,
+                generate_local,
+                generate_stream: false,
             })
         } else {
             None
@@ -368,7 +410,7 @@ fn analyze_property(
 }
 
 /// Returns (the list of get functions to check, the desired get function name).
-fn get_func_name(prop_name: &str, is_bool_getter: bool) -> (Vec<String>, String) {
+pub fn get_func_name(prop_name: &str, is_bool_getter: bool) -> (Vec<String>, String) {
     let get_rename_res = getter_rules::try_rename_getter_suffix(prop_name, is_bool_getter);
     match get_rename_res {
         Ok(new_name) => {
@@ -414,3 +456,80 @@ pub fn get_property_ref_modes(
     let nullable = library::Nullable(set_in_ref_mode.is_ref());
     (get_out_ref_mode, set_in_ref_mode, nullable)
 }
+
+/// Returns the message of the panic of the getter of a nullable property on
+/// NULL, or `None` if the getter returns an `Option`.
+fn getter_null_panic_message(
+    configured_properties: &[&config::properties::Property],
+    panic_on_null_properties: bool,
+    name: &str,
+    type_name: &str,
+) -> Option<String> {
+    let null_panic_message = configured_properties
+        .iter()
+        .find_map(|f| f.null_panic_message.as_ref());
+    let panics = configured_properties
+        .iter()
+        .find_map(|f| f.nullable)
+        .map_or(
+            null_panic_message.is_some() || panic_on_null_properties,
+            |nullable| !nullable,
+        );
+    panics.then(|| {
+        null_panic_message
+            .cloned()
+            .unwrap_or_else(|| format!("Property `{name}` of `{type_name}` is NULL"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parsable::Parse;
+
+    fn property(toml: &str) -> config::properties::Property {
+        config::properties::Property::parse(&toml.parse().unwrap(), "Gtk.Label").unwrap()
+    }
+
+    #[test]
+    fn test_getter_null_panic_message() {
+        let message = |props: &[&config::properties::Property], panic_on_null_properties| {
+            getter_null_panic_message(props, panic_on_null_properties, "label", "Gtk.Label")
+        };
+        assert_eq!(message(&[], false), None);
+        assert_eq!(
+            message(&[], true).as_deref(),
+            Some("Property `label` of `Gtk.Label` is NULL")
+        );
+
+        let custom = property(
+            r#"
+name = "label"
+null_panic_message = "label isn't set"
+"#,
+        );
+        assert_eq!(
+            message(&[&custom], false).as_deref(),
+            Some("label isn't set")
+        );
+
+        let nullable = property(
+            r#"
+name = "label"
+nullable = true
+"#,
+        );
+        assert_eq!(message(&[&nullable], true), None);
+
+        let non_nullable = property(
+            r#"
+name = "label"
+nullable = false
+"#,
+        );
+        assert_eq!(
+            message(&[&non_nullable], false).as_deref(),
+            Some("Property `label` of `Gtk.Label` is NULL")
+        );
+    }
+}
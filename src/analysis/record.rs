@@ -1,8 +1,11 @@
 use std::ops::Deref;
 
-use log::info;
+use log::{error, info};
 
-use super::{imports::Imports, info_base::InfoBase, record_type::RecordType, *};
+use super::{
+    conversion_type::ConversionType, imports::Imports, info_base::InfoBase,
+    record_type::RecordType, types::IsVolatile, *,
+};
 use crate::{
     config::{
         derives::{Derive, Derives},
@@ -25,6 +28,12 @@ pub struct Info {
     pub init_function_expression: Option<String>,
     pub copy_into_function_expression: Option<String>,
     pub clear_function_expression: Option<String>,
+    /// Names of the fields printed by the generated `Debug` implementation,
+    /// and whether they are `gboolean`s
+    pub debug_fields: Option<Vec<(String, bool)>>,
+    /// Names and Rust types of the fields of a record generated as a plain
+    /// `Copy` struct
+    pub plain_fields: Option<Vec<(String, String)>>,
 }
 
 impl Deref for Info {
@@ -46,6 +55,44 @@ impl Info {
     }
 }
 
+/// Whether the record is generated as a wrapper that can only free its values,
+/// as it has a `free` function but can't be copied or referenced. Such a
+/// wrapper can't take `transfer none` pointers or be cloned.
+pub fn is_free_only(record: &library::Record, obj: &GObject) -> bool {
+    obj.status.need_generate()
+        && obj.wrapper.is_none()
+        && !obj.plain_struct
+        && !obj.boxed_inline
+        && record.glib_get_type.is_none()
+        && record.has_free()
+        && RecordType::of(record) == RecordType::AutoBoxed
+}
+
+/// Whether `tid` is a record for which `is_free_only` holds.
+pub fn is_free_only_type(env: &Env, tid: library::TypeId) -> bool {
+    match (
+        env.library.type_(tid),
+        env.config.objects.get(&tid.full_name(&env.library)),
+    ) {
+        (library::Type::Record(record), Some(obj)) => is_free_only(record, obj),
+        _ => false,
+    }
+}
+
+/// Removes `Clone` from the derives of a record that can only be freed, the
+/// clones would free the same value twice.
+fn free_only_derives(derives: Derives, full_name: &str) -> Derives {
+    if derives.iter().any(|d| d.names.iter().any(|n| n == "Clone")) {
+        error!(
+            "`Clone` can't be derived for {}, which can only be freed",
+            full_name
+        );
+        filter_derives(&derives, &["Clone"])
+    } else {
+        derives
+    }
+}
+
 fn filter_derives(derives: &[Derive], names: &[&str]) -> Derives {
     derives
         .iter()
@@ -73,7 +120,7 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
     info!("Analyzing record {}", obj.name);
     let full_name = obj.name.clone();
 
-    let record_tid = env.library.find_type(0, &full_name)?;
+    let record_tid = env.library.find_type(library::MAIN_NAMESPACE, &full_name)?;
 
     let type_ = env.type_(record_tid);
 
@@ -88,6 +135,9 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
     let boxed_inline = obj.boxed_inline;
 
     let mut imports = Imports::with_defined(&env.library, &name);
+    for import in &obj.extra_imports {
+        imports.add(import);
+    }
 
     let mut functions = functions::analyze(
         env,
@@ -122,7 +172,7 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
             panic!("Can't automatically derive traits other than `Debug` for BoxedInline records");
         }
         derives.clone()
-    } else if !boxed_inline {
+    } else if !boxed_inline && obj.wrapper.is_none() {
         let derives = vec![Derive {
             names: vec![
                 "Debug".into(),
@@ -137,7 +187,7 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
 
         derives
     } else {
-        // boxed_inline
+        // boxed_inline or custom wrapper, which defines its own impls
         vec![]
     };
 
@@ -155,6 +205,59 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
             _ => (),
         }
     }
+    if is_free_only(record, obj) {
+        derives = free_only_derives(derives, &full_name);
+    }
+
+    let debug_fields = if obj.debug_fields {
+        derives = filter_derives(&derives, &["Debug"]);
+        imports.add("glib::translate::*");
+        Some(
+            record
+                .fields
+                .iter()
+                .filter(|field| !field.private && field.bits.is_none() && !field.is_volatile())
+                .filter_map(|field| match env.library.type_(field.typ) {
+                    library::Type::Basic(library::Basic::Boolean) => {
+                        Some((field.name.clone(), true))
+                    }
+                    library::Type::Basic(_)
+                        if ConversionType::of(env, field.typ) == ConversionType::Direct =>
+                    {
+                        Some((field.name.clone(), false))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    let plain_fields = if !obj.plain_struct {
+        None
+    } else if record.glib_get_type.is_some()
+        || specials.has_trait(special_functions::Type::Copy)
+        || specials.has_trait(special_functions::Type::Free)
+    {
+        log::warn!(
+            "Record {} can't be a plain struct: it has a GType or copy or free functions",
+            full_name
+        );
+        None
+    } else {
+        let fields = plain_struct_fields(env, record);
+        if fields.is_some() {
+            derives = filter_derives(&derives, &["Clone", "Copy", "Eq", "Ord", "Hash"]);
+            imports.add("glib::translate::*");
+        } else {
+            log::warn!(
+                "Record {} can't be a plain struct: all fields have to be of plain scalar types",
+                full_name
+            );
+        }
+        fields
+    };
 
     special_functions::analyze_imports(&specials, &mut imports);
 
@@ -172,8 +275,11 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
     };
 
     // Check if we have to make use of the GType and the generic
-    // boxed functions.
-    if !is_shared
+    // boxed functions. Custom wrappers take care of the memory management
+    // themselves.
+    if obj.wrapper.is_none()
+        && plain_fields.is_none()
+        && !is_shared
         && (!specials.has_trait(special_functions::Type::Copy)
             || !specials.has_trait(special_functions::Type::Free))
     {
@@ -190,7 +296,9 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
                 get_type_version <= version,
                 "Have to use get_type function for {full_name} but version is higher than for the type ({get_type_version:?} > {version:?})"
             );
-        } else {
+        } else if !specials.has_trait(special_functions::Type::Free) && !boxed_inline {
+            // Records with only a free function can't be cloned but are still generated,
+            // inline allocated ones are copied bitwise by default
             error!("Missing memory management functions for {}", full_name);
         }
     }
@@ -208,6 +316,13 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
         concurrency: obj.concurrency,
         visibility: obj.visibility,
     };
+    if let Some(ref module_path) = obj.module_path {
+        env.symbols.borrow_mut().make_in_module(
+            &record.c_type,
+            base.functions.iter().map(|f| f.glib_name.as_str()),
+            module_path,
+        );
+    }
 
     let info = Info {
         base,
@@ -218,7 +333,136 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
         init_function_expression: obj.init_function_expression.clone(),
         copy_into_function_expression: obj.copy_into_function_expression.clone(),
         clear_function_expression: obj.clear_function_expression.clone(),
+        debug_fields,
+        plain_fields,
     };
 
     Some(info)
 }
+
+fn plain_struct_fields(env: &Env, record: &library::Record) -> Option<Vec<(String, String)>> {
+    if record.fields.is_empty() {
+        return None;
+    }
+    record
+        .fields
+        .iter()
+        .map(|field| {
+            if field.bits.is_some() || field.is_volatile() {
+                return None;
+            }
+            match env.library.type_(field.typ) {
+                library::Type::Basic(library::Basic::Boolean) => None,
+                library::Type::Basic(_)
+                    if ConversionType::of(env, field.typ) == ConversionType::Direct =>
+                {
+                    let typ = rust_type::RustType::try_new(env, field.typ).ok()?;
+                    Some((
+                        mangle_keywords(&*field.name).into_owned(),
+                        typ.into_string(),
+                    ))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::gobjects::GStatus,
+        library::{
+            Function, FunctionKind, Nullable, Parameter, ParameterDirection, ParameterScope,
+            Record, Transfer, TypeId,
+        },
+    };
+
+    fn function(name: &str) -> Function {
+        Function {
+            name: name.into(),
+            c_identifier: Some(format!("test_record_{name}")),
+            kind: FunctionKind::Method,
+            parameters: Vec::new(),
+            ret: Parameter {
+                name: String::new(),
+                typ: TypeId::tid_none(),
+                c_type: "void".into(),
+                instance_parameter: false,
+                direction: ParameterDirection::Return,
+                transfer: Transfer::None,
+                caller_allocates: false,
+                nullable: Nullable(false),
+                array_length: None,
+                is_error: false,
+                doc: None,
+                scope: ParameterScope::None,
+                closure: None,
+                destroy: None,
+            },
+            throws: false,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Vec::new(),
+            shadows: None,
+            shadowed_by: None,
+            finish_func: None,
+            sync_func: None,
+            async_func: None,
+            invoker: None,
+        }
+    }
+
+    fn record(functions: &[&str]) -> Record {
+        Record {
+            name: "Record".into(),
+            c_type: "TestRecord".into(),
+            functions: functions.iter().map(|name| function(name)).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_free_only() {
+        let obj = GObject {
+            status: GStatus::Generate,
+            ..Default::default()
+        };
+        assert!(is_free_only(&record(&["free"]), &obj));
+        assert!(!is_free_only(&record(&["copy", "free"]), &obj));
+        assert!(!is_free_only(&record(&["ref", "unref", "free"]), &obj));
+
+        let boxed = Record {
+            glib_get_type: Some("test_record_get_type".into()),
+            ..record(&["free"])
+        };
+        assert!(!is_free_only(&boxed, &obj));
+
+        let boxed_inline = GObject {
+            boxed_inline: true,
+            ..obj.clone()
+        };
+        assert!(!is_free_only(&record(&["free"]), &boxed_inline));
+    }
+
+    #[test]
+    fn test_free_only_derives() {
+        let derives = vec![
+            Derive {
+                names: vec!["Debug".into(), "Clone".into()],
+                cfg_condition: None,
+            },
+            Derive {
+                names: vec!["Clone".into()],
+                cfg_condition: Some("unix".into()),
+            },
+        ];
+        let derives = free_only_derives(derives, "Test.Record");
+        assert_eq!(derives.len(), 1);
+        assert_eq!(derives[0].names, ["Debug"]);
+        assert_eq!(derives[0].cfg_condition, None);
+    }
+}
@@ -57,6 +57,7 @@ impl RefMode {
             | Interface(..)
             | List(..)
             | SList(..)
+            | Array(..)
             | PtrArray(..)
             | CArray(..) => {
                 if direction == library::ParameterDirection::In {
@@ -1,11 +1,14 @@
-use log::error;
+use log::{error, warn};
 
 use crate::{
     analysis::{
-        self, imports::Imports, namespaces, override_string_type::override_string_type_return,
-        rust_type::RustType,
+        self, function_parameters::is_pointer_c_type, imports::Imports, namespaces,
+        override_string_type::override_string_type_return, rust_type::RustType,
+    },
+    config::{
+        self,
+        attributes::{self, AttributeBehavior},
     },
-    config,
     env::Env,
     library::{self, Nullable, TypeId},
 };
@@ -17,6 +20,21 @@ pub struct Info {
     pub commented: bool,
     pub bool_return_is_error: Option<String>,
     pub nullable_return_is_error: Option<String>,
+    /// Message of the panic on a NULL return value, which is then returned
+    /// as `T` instead of `Option<T>`
+    pub null_panic_message: Option<String>,
+    /// Whether a `GList`/`GSList` is returned as `glib::List`/`glib::SList`,
+    /// which converts the elements only when iterating
+    pub lazy_list: bool,
+    /// Whether a `gunichar` is returned as `Option<char>` instead of panicking
+    /// on invalid code points
+    pub invalid_char_is_none: bool,
+    /// Whether a `transfer none` return value is wrapped in
+    /// `glib::translate::Borrowed` instead of being copied or referenced
+    pub borrowed: bool,
+    /// Whether a pointer return value is cast to the configured integer type,
+    /// for integer handles declared as `gpointer`
+    pub integer_from_pointer: bool,
 }
 
 pub fn analyze(
@@ -28,16 +46,23 @@ pub fn analyze(
     used_types: &mut Vec<String>,
     imports: &mut Imports,
 ) -> Info {
-    let typ = configured_functions
+    let type_override = configured_functions
         .iter()
         .find_map(|f| f.ret.type_name.as_ref())
-        .and_then(|typ| env.library.find_type(0, typ))
+        .and_then(|typ| env.library.find_type(0, typ));
+    let typ = type_override
         .unwrap_or_else(|| override_string_type_return(env, func.ret.typ, configured_functions));
+    let integer_from_pointer =
+        type_override.is_some() && is_pointer_c_type(&func.ret.c_type) && is_integer(env, typ);
     let mut parameter = if typ == Default::default() {
         None
     } else {
         let mut nullable = func.ret.nullable;
-        if !obj.trust_return_value_nullability {
+        let trust_return_value_nullability = configured_functions
+            .iter()
+            .find_map(|f| f.trust_return_value_nullability)
+            .unwrap_or(obj.trust_return_value_nullability);
+        if !trust_return_value_nullability {
             // Since GIRs are bad at specifying return value nullability, assume
             // any returned pointer is nullable unless overridden by the config.
             if !*nullable && can_be_nullable_return(env, typ) {
@@ -45,7 +70,7 @@ pub fn analyze(
             }
         }
 
-        let nullable_override = configured_functions.iter().find_map(|f| f.ret.nullable);
+        let nullable_override = configured_nullable(env, func, configured_functions);
         if let Some(val) = nullable_override {
             nullable = val;
         }
@@ -68,6 +93,12 @@ pub fn analyze(
                 func.name
             );
             None
+        } else if func.throws {
+            error!(
+                "Ignoring bool_return_is_error configuration for throwing function {}",
+                func.name
+            );
+            None
         } else {
             let ns = if env.namespaces.glib_ns_id == namespaces::MAIN {
                 "error"
@@ -84,7 +115,15 @@ pub fn analyze(
         .iter()
         .find_map(|f| f.ret.nullable_return_is_error.as_ref());
     let nullable_return_error_message = nullable_return_is_error.and_then(|m| {
-        if let Some(library::Parameter { nullable: Nullable(false), ..}) = parameter {
+        // Constructors are converted as nullable below
+        let non_nullable = matches!(
+            parameter,
+            Some(library::Parameter {
+                nullable: Nullable(false),
+                ..
+            })
+        );
+        if non_nullable && func.kind != library::FunctionKind::Constructor {
             error!(
                 "Ignoring nullable_return_is_error configuration for non-none returning function {}",
                 func.name
@@ -102,11 +141,112 @@ pub fn analyze(
         }
     });
 
+    let null_panic_message = configured_functions
+        .iter()
+        .find_map(|f| f.ret.null_panic_message.as_ref());
+    let null_panic_message = null_panic_message.and_then(|m| {
+        if nullable_return_error_message.is_some()
+            || func.throws
+            || func
+                .parameters
+                .iter()
+                .any(|p| p.direction == library::ParameterDirection::Out)
+        {
+            error!(
+                "Ignoring null_panic_message configuration for function {} not returning only a \
+                 value or returning a Result",
+                func.name
+            );
+            None
+        } else {
+            Some(m.clone())
+        }
+    });
+
+    let lazy_list = configured_functions
+        .iter()
+        .find_map(|f| f.ret.lazy_list)
+        .unwrap_or(obj.lazy_lists)
+        && func.kind != library::FunctionKind::Constructor
+        && matches!(
+            env.library.type_(typ),
+            library::Type::List(_) | library::Type::SList(_)
+        );
+    let lazy_list = if lazy_list && (func.throws || !is_lazy_list(env, typ)) {
+        error!(
+            "Ignoring lazy_list configuration for function {} that throws or doesn't return a list of strings, objects or boxed records",
+            func.name
+        );
+        false
+    } else {
+        lazy_list
+    };
+
+    let invalid_char_is_none = configured_functions
+        .iter()
+        .find_map(|f| f.ret.invalid_char_is_none)
+        .unwrap_or(false);
+    let invalid_char_is_none = if invalid_char_is_none
+        && (!matches!(
+            env.library.type_(typ),
+            library::Type::Basic(library::Basic::UniChar)
+        ) || func
+            .parameters
+            .iter()
+            .any(|p| p.direction == library::ParameterDirection::Out))
+    {
+        error!(
+            "Ignoring invalid_char_is_none configuration for function {} not returning only a gunichar",
+            func.name
+        );
+        false
+    } else {
+        invalid_char_is_none
+    };
+
+    let borrowed = configured_functions
+        .iter()
+        .find_map(|f| f.ret.borrowed)
+        .unwrap_or(false);
+    let borrowed = if borrowed
+        && (func.ret.transfer != library::Transfer::None
+            || func.throws
+            || func.kind == library::FunctionKind::Constructor
+            || !matches!(
+                env.library.type_(typ),
+                library::Type::Record(_) | library::Type::Class(_) | library::Type::Interface(_)
+            )
+            || func
+                .parameters
+                .iter()
+                .any(|p| p.direction == library::ParameterDirection::Out))
+    {
+        error!(
+            "Ignoring borrowed configuration for function {} not returning only a transfer none boxed or object value",
+            func.name
+        );
+        false
+    } else {
+        borrowed
+    };
+
+    // Records that can only be freed can't be taken from a pointer the caller
+    // doesn't own
+    let unowned_free_only = !borrowed
+        && func.ret.transfer == library::Transfer::None
+        && analysis::record::is_free_only_type(env, typ);
+    if unowned_free_only {
+        warn!(
+            "Function {} returns a record that can only be freed with transfer none",
+            func.name
+        );
+    }
+
     let mut base_tid = None;
 
     if func.kind == library::FunctionKind::Constructor {
         if let Some(par) = parameter {
-            let nullable_override = configured_functions.iter().find_map(|f| f.ret.nullable);
+            let nullable_override = configured_nullable(env, func, configured_functions);
             if par.typ != type_tid {
                 base_tid = Some(par.typ);
             }
@@ -118,6 +258,14 @@ pub fn analyze(
         }
     }
 
+    if null_panic_message.is_some() || nullable_return_error_message.is_some() {
+        // Converted as `Option<T>`, then unwrapped or turned into a `Result`,
+        // also for constructors that aren't annotated as nullable
+        if let Some(ref mut par) = parameter {
+            par.nullable = Nullable(true);
+        }
+    }
+
     let parameter = parameter.as_ref().map(|lib_par| {
         let par = analysis::Parameter::from_return_value(env, lib_par, configured_functions);
         if let Ok(rust_type) = RustType::builder(env, typ)
@@ -128,11 +276,12 @@ pub fn analyze(
             used_types.extend(rust_type.into_used_types());
         }
 
-        commented = RustType::builder(env, typ)
-            .direction(func.ret.direction)
-            .try_from_glib(&par.try_from_glib)
-            .try_build_param()
-            .is_err();
+        commented = unowned_free_only
+            || RustType::builder(env, typ)
+                .direction(func.ret.direction)
+                .try_from_glib(&par.try_from_glib)
+                .try_build_param()
+                .is_err();
 
         par
     });
@@ -143,9 +292,88 @@ pub fn analyze(
         commented,
         bool_return_is_error: bool_return_error_message,
         nullable_return_is_error: nullable_return_error_message,
+        null_panic_message,
+        lazy_list,
+        invalid_char_is_none,
+        borrowed,
+        integer_from_pointer,
+    }
+}
+
+/// Whether the elements of the list can be held by a `glib::List` or
+/// `glib::SList`, which only holds pointers of strings and wrapper types
+fn is_lazy_list(env: &Env, type_id: library::TypeId) -> bool {
+    use crate::library::{Basic::*, Type::*};
+    let element = match env.library.type_(type_id) {
+        List(element) | SList(element) => *element,
+        _ => return false,
+    };
+    match env.library.type_(element) {
+        Basic(Utf8 | Filename | OsString) | Class(_) | Interface(_) => true,
+        Record(_) => env
+            .config
+            .objects
+            .get(&element.full_name(&env.library))
+            .map_or(false, |obj| {
+                !obj.boxed_inline && !analysis::record::is_free_only_type(env, element)
+            }),
+        _ => false,
+    }
+}
+
+fn is_integer(env: &Env, type_id: library::TypeId) -> bool {
+    use crate::library::{Basic::*, Type::*};
+    matches!(
+        env.library.type_(type_id),
+        Basic(
+            Int8 | UInt8
+                | Int16
+                | UInt16
+                | Int32
+                | UInt32
+                | Int64
+                | UInt64
+                | Short
+                | UShort
+                | Int
+                | UInt
+                | Long
+                | ULong
+                | Size
+                | SSize
+                | IntPtr
+                | UIntPtr
+        )
+    )
+}
+
+impl Info {
+    /// Whether the Rust return value is neither an `Option` nor a `Result`
+    pub fn is_non_nullable(&self) -> bool {
+        self.parameter.as_ref().map_or(false, |p| {
+            !*p.lib_par.nullable || self.null_panic_message.is_some()
+        })
     }
 }
 
+/// Nullability of the return value from the configuration or the GIR
+/// `<attribute>` annotations.
+fn configured_nullable(
+    env: &Env,
+    func: &library::Function,
+    configured_functions: &[&config::functions::Function],
+) -> Option<Nullable> {
+    configured_functions
+        .iter()
+        .find_map(|f| f.ret.nullable)
+        .or_else(|| {
+            env.config
+                .attribute_value(&func.attributes, AttributeBehavior::Nullable)
+                .and_then(attributes::parse_bool)
+                .map(Nullable)
+        })
+}
+
 fn can_be_nullable_return(env: &Env, type_id: library::TypeId) -> bool {
     use crate::library::{Basic::*, Type::*};
     match env.library.type_(type_id) {
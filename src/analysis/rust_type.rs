@@ -57,6 +57,26 @@ impl RustType {
     ) -> result::Result<String, TypeError> {
         let mut type_name = type_name.to_string();
 
+        // Manual types may live at an arbitrary path, which is then used as is.
+        if let Some(rust_path) = env
+            .config
+            .objects
+            .get(&type_id.full_name(&env.library))
+            .and_then(|obj| obj.rust_path.as_ref())
+        {
+            return Ok(rust_path.clone());
+        }
+        // So are generated types placed in a submodule.
+        if let Some(module_path) = env
+            .config
+            .objects
+            .get(&type_id.full_name(&env.library))
+            .filter(|obj| obj.status.need_generate())
+            .and_then(|obj| obj.module_path.as_ref())
+        {
+            return Ok(format!("crate::{module_path}::{type_name}"));
+        }
+
         if type_id.ns_id != library::MAIN_NAMESPACE
             && type_id.ns_id != library::INTERNAL_NAMESPACE
             && type_id.full_name(&env.library) != "GLib.DestroyNotify"
@@ -378,6 +398,24 @@ impl<'env> RustTypeBuilder<'env> {
                         })
                     })
             }
+            Array(inner_tid)
+                if ConversionType::of(self.env, inner_tid) == ConversionType::Direct =>
+            {
+                // Elements of a `GArray` are stored inline, so only types with the
+                // same layout in Rust and C can be copied into or out of it
+                skip_option = true;
+                RustType::builder(self.env, inner_tid)
+                    .try_build()
+                    .map_any(|rust_type| {
+                        rust_type.alter_type(|typ| {
+                            if self.ref_mode.is_ref() {
+                                format!("[{typ}]")
+                            } else {
+                                format!("Vec<{typ}>")
+                            }
+                        })
+                    })
+            }
             CArray(inner_tid)
                 if ConversionType::of(self.env, inner_tid) == ConversionType::Direct =>
             {
@@ -672,7 +710,7 @@ impl<'env> RustTypeBuilder<'env> {
                 ParameterDirection::In | ParameterDirection::Return => rust_type,
                 _ => Err(TypeError::Unimplemented(into_inner(rust_type))),
             },
-            CArray(..) | PtrArray(..) => match self.direction {
+            Array(..) | CArray(..) | PtrArray(..) => match self.direction {
                 ParameterDirection::In | ParameterDirection::Out | ParameterDirection::Return => {
                     rust_type
                 }
@@ -16,9 +16,17 @@ pub struct Info {
     pub trampoline: Result<Trampoline, Vec<String>>,
     pub version: Option<Version>,
     pub deprecated_version: Option<Version>,
+    /// Version removing one of the types of the signal from the API, if any
+    pub not_version: Option<Version>,
     pub doc_hidden: bool,
     pub is_detailed: bool,
     pub generate_doc: bool,
+    /// Whether a `_local` variant of the connector accepting non-`Send` closures
+    /// is generated
+    pub generate_local: bool,
+    /// Whether a `_stream` function returning the emissions as a `Stream` is
+    /// generated
+    pub generate_stream: bool,
 }
 
 pub fn analyze(
@@ -37,7 +45,13 @@ pub fn analyze(
         if !configured_signals.iter().all(|f| f.status.need_generate()) {
             continue;
         }
-        if env.is_totally_deprecated(Some(type_tid.ns_id), signal.deprecated_version) {
+        let deprecated_version = configured_signals
+            .iter()
+            .find_map(|f| f.deprecated_version)
+            .or(signal.deprecated_version);
+        if env.is_totally_deprecated(Some(type_tid.ns_id), deprecated_version)
+            || env.uses_removed_type(signal_types(signal))
+        {
             continue;
         }
 
@@ -57,6 +71,14 @@ pub fn analyze(
     sns
 }
 
+fn signal_types(signal: &library::Signal) -> impl Iterator<Item = library::TypeId> + '_ {
+    signal
+        .parameters
+        .iter()
+        .chain(Some(&signal.ret))
+        .map(|par| par.typ)
+}
+
 fn analyze_signal(
     env: &Env,
     signal: &library::Signal,
@@ -73,7 +95,11 @@ fn analyze_signal(
         .filter_map(|f| f.version)
         .min()
         .or(signal.version);
-    let deprecated_version = signal.deprecated_version;
+    let deprecated_version = configured_signals
+        .iter()
+        .find_map(|f| f.deprecated_version)
+        .or(signal.deprecated_version);
+    let not_version = env.types_version_end(signal_types(signal));
     let doc_hidden = configured_signals.iter().any(|f| f.doc_hidden);
 
     let imports = &mut imports.with_defaults(version, &None);
@@ -107,6 +133,13 @@ fn analyze_signal(
         imports.add("std::boxed::Box as Box_");
     }
     let generate_doc = configured_signals.iter().all(|f| f.generate_doc);
+    let generate_local = needs_local_connect(obj, &trampoline);
+    // The emissions can only be forwarded if the handler doesn't have to
+    // return anything
+    let generate_stream = obj.signal_streams
+        && trampoline.as_ref().map_or(false, |t| {
+            t.ret.typ == Default::default() && !t.inhibit && t.bounds.is_empty()
+        });
 
     Info {
         connect_name,
@@ -115,8 +148,20 @@ fn analyze_signal(
         action_emit_name,
         version,
         deprecated_version,
+        not_version,
         doc_hidden,
         is_detailed: signal.is_detailed,
         generate_doc,
+        generate_local,
+        generate_stream,
     }
 }
+
+/// The closures of objects without concurrency guarantees don't need to be
+/// `Send` anyway, so there is nothing to add for them.
+pub fn needs_local_connect(obj: &GObject, trampoline: &Result<Trampoline, Vec<String>>) -> bool {
+    obj.local_signals
+        && trampoline
+            .as_ref()
+            .map_or(false, |t| t.concurrency != library::Concurrency::None)
+}
@@ -79,11 +79,22 @@ impl Symbol {
     }
 }
 
+/// Where a function is declared in the library
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FunctionLocation {
+    pub ns_id: NsId,
+    /// Type the function belongs to, `None` for global functions
+    pub owner: Option<TypeId>,
+    /// Position in the functions of the owner or of the namespace
+    pub index: usize,
+}
+
 #[derive(Debug)]
 pub struct Info {
     symbols: Vec<Symbol>,
     c_name_index: HashMap<String, u32>,
     tid_index: HashMap<Option<TypeId>, u32>,
+    function_index: HashMap<String, FunctionLocation>,
 }
 
 pub fn run(library: &Library, namespaces: &namespaces::Info) -> Info {
@@ -91,6 +102,7 @@ pub fn run(library: &Library, namespaces: &namespaces::Info) -> Info {
         symbols: Vec::new(),
         c_name_index: HashMap::new(),
         tid_index: HashMap::new(),
+        function_index: HashMap::new(),
     };
 
     info.insert(
@@ -133,6 +145,8 @@ pub fn run(library: &Library, namespaces: &namespaces::Info) -> Info {
             Some(&namespaces[ns_id].crate_name)
         };
 
+        info.insert_functions(ns_id, None, &ns.functions);
+
         for (pos, typ) in ns.types.iter().map(|t| t.as_ref().unwrap()).enumerate() {
             let symbol = Symbol {
                 crate_name: crate_name.cloned(),
@@ -143,6 +157,7 @@ pub fn run(library: &Library, namespaces: &namespaces::Info) -> Info {
                 ns_id,
                 id: pos as u32,
             };
+            info.insert_functions(ns_id, Some(tid), typ.functions());
 
             match typ {
                 Type::Alias(Alias { c_identifier, .. }) => {
@@ -234,12 +249,47 @@ impl Info {
         }
     }
 
+    /// Returns where the function `c_identifier` is declared.
+    pub fn function_location(&self, c_identifier: &str) -> Option<FunctionLocation> {
+        self.function_index.get(c_identifier).copied()
+    }
+
     pub fn by_tid(&self, tid: TypeId) -> Option<&Symbol> {
         self.tid_index
             .get(&Some(tid))
             .map(|&id| &self.symbols[id as usize])
     }
 
+    /// Places the type `c_type` and those of its `functions` that aren't
+    /// trait methods in the submodule `module` of the crate.
+    pub fn make_in_module<'a>(
+        &mut self,
+        c_type: &str,
+        functions: impl IntoIterator<Item = &'a str>,
+        module: &str,
+    ) {
+        for name in std::iter::once(c_type).chain(functions) {
+            if let Some(symbol) = self.by_c_name_mut(name) {
+                if symbol.module_name.is_none() {
+                    symbol.module_name = Some(module.to_owned());
+                }
+            }
+        }
+    }
+
+    fn insert_functions(&mut self, ns_id: NsId, owner: Option<TypeId>, functions: &[Function]) {
+        for (index, func) in functions.iter().enumerate() {
+            if let Some(ref c_identifier) = func.c_identifier {
+                let location = FunctionLocation {
+                    ns_id,
+                    owner,
+                    index,
+                };
+                self.function_index.insert(c_identifier.clone(), location);
+            }
+        }
+    }
+
     fn insert(&mut self, name: &str, symbol: Symbol, tid: Option<TypeId>) {
         let id = self.symbols.len();
         self.symbols.push(symbol);
@@ -249,3 +299,106 @@ impl Info {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_in_module() {
+        let mut info = Info {
+            symbols: Vec::new(),
+            c_name_index: HashMap::new(),
+            tid_index: HashMap::new(),
+            function_index: HashMap::new(),
+        };
+        let symbol = |owner_name: Option<&str>, name: &str| Symbol {
+            owner_name: owner_name.map(ToOwned::to_owned),
+            name: name.into(),
+            ..Default::default()
+        };
+        info.insert("GtkDialog", symbol(None, "Dialog"), None);
+        info.insert("gtk_dialog_new", symbol(Some("Dialog"), "new"), None);
+        info.insert("gtk_dialog_run", symbol(Some("Dialog"), "run"), None);
+        info.by_c_name_mut("gtk_dialog_run")
+            .unwrap()
+            .make_trait_method("DialogExt");
+
+        info.make_in_module("GtkDialog", ["gtk_dialog_new", "gtk_dialog_run"], "dialogs");
+        let full_rust_name = |c_name| info.by_c_name(c_name).unwrap().full_rust_name();
+        assert_eq!(full_rust_name("GtkDialog"), "dialogs::Dialog");
+        assert_eq!(full_rust_name("gtk_dialog_new"), "dialogs::Dialog::new");
+        assert_eq!(full_rust_name("gtk_dialog_run"), "prelude::DialogExt::run");
+    }
+
+    #[test]
+    fn test_function_location() {
+        let function = |name: &str, c_identifier: Option<&str>| Function {
+            name: name.into(),
+            c_identifier: c_identifier.map(ToOwned::to_owned),
+            kind: FunctionKind::Function,
+            parameters: Vec::new(),
+            ret: Parameter {
+                name: String::new(),
+                typ: TypeId::tid_none(),
+                c_type: "void".into(),
+                instance_parameter: false,
+                direction: ParameterDirection::Return,
+                transfer: Transfer::None,
+                caller_allocates: false,
+                nullable: Nullable(false),
+                array_length: None,
+                is_error: false,
+                doc: None,
+                scope: ParameterScope::None,
+                closure: None,
+                destroy: None,
+            },
+            throws: false,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Vec::new(),
+            shadows: None,
+            shadowed_by: None,
+            finish_func: None,
+            sync_func: None,
+            async_func: None,
+            invoker: None,
+        };
+        let mut info = Info {
+            symbols: Vec::new(),
+            c_name_index: HashMap::new(),
+            tid_index: HashMap::new(),
+            function_index: HashMap::new(),
+        };
+        let owner = TypeId { ns_id: 1, id: 3 };
+        info.insert_functions(
+            1,
+            Some(owner),
+            &[
+                function("new", None),
+                function("run", Some("gtk_dialog_run")),
+            ],
+        );
+        info.insert_functions(1, None, &[function("init", Some("gtk_init"))]);
+        assert_eq!(
+            info.function_location("gtk_dialog_run"),
+            Some(FunctionLocation {
+                ns_id: 1,
+                owner: Some(owner),
+                index: 1,
+            })
+        );
+        assert_eq!(
+            info.function_location("gtk_init"),
+            Some(FunctionLocation {
+                ns_id: 1,
+                owner: None,
+                index: 0,
+            })
+        );
+        assert_eq!(info.function_location("gtk_main"), None);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::library::*;
+use crate::{library::*, version::Version};
 
 /// Array size limit above which Rust no longer automatically derives traits.
 const RUST_DERIVE_ARRAY_SIZE_LIMIT: u16 = 32;
@@ -45,6 +45,98 @@ impl IsVolatile for Field {
     }
 }
 
+/// Returns the size in bits of the integer field that stores consecutive
+/// bitfields of the given C type, if it's the same on all platforms.
+pub fn bitfield_unit_bits(c_type: &str) -> Option<u8> {
+    match c_type {
+        "gint8" | "guint8" | "gchar" | "guchar" | "char" | "unsigned char" => Some(8),
+        "gint16" | "guint16" | "gshort" | "gushort" | "short" | "unsigned short" => Some(16),
+        "gint" | "guint" | "gint32" | "guint32" | "gboolean" | "int" | "unsigned int"
+        | "unsigned" => Some(32),
+        "gint64" | "guint64" => Some(64),
+        _ => None,
+    }
+}
+
+/// Checks if the bitfield `field` directly following the bitfield `prev` can
+/// be stored in the same integer fields.
+pub fn bitfields_collapsible(prev: &Field, field: &Field) -> bool {
+    match (&prev.c_type, &field.c_type) {
+        (Some(prev), Some(c_type)) => prev == c_type && bitfield_unit_bits(c_type).is_some(),
+        _ => false,
+    }
+}
+
+/// Packs the consecutive collapsible bitfields `run` into storage units of
+/// `storage_bits` like C compilers do on little endian targets.
+///
+/// Returns the index of the unit and the offset in it of every bitfield,
+/// `None` for zero width bitfields, and whether the last unit is full.
+pub fn pack_bitfields(run: &[&Field], storage_bits: u8) -> (Vec<Option<(usize, u8)>>, bool) {
+    let mut units = 0;
+    // Start a new unit for the first bitfield
+    let mut offset = storage_bits;
+    let positions = run
+        .iter()
+        .map(|field| {
+            let bits = field.bits.unwrap_or(0);
+            if bits == 0 {
+                // Unnamed zero width bitfields only align the next bitfield
+                offset = storage_bits;
+                return None;
+            }
+            if offset + bits > storage_bits {
+                units += 1;
+                offset = 0;
+            }
+            let position = (units - 1, offset);
+            offset += bits;
+            Some(position)
+        })
+        .collect();
+    (positions, offset == storage_bits)
+}
+
+/// Checks if the layout of the collapsed bitfields `run` doesn't depend on the
+/// compiler. That's not the case if the fields before or after them may be
+/// packed into the same storage unit: their sizes have to be multiples of
+/// the unit size unless the last unit is full.
+pub fn bitfield_run_is_unambiguous(
+    lib: &Library,
+    prev: Option<&Field>,
+    run: &[&Field],
+    next: Option<&Field>,
+) -> bool {
+    let storage_bits = match run.first().and_then(|f| f.c_type.as_deref()) {
+        Some(c_type) => bitfield_unit_bits(c_type),
+        None => None,
+    };
+    let storage_bits = match storage_bits {
+        Some(storage_bits) => storage_bits,
+        None => return false,
+    };
+    let is_aligned = |field: &Field| {
+        let bits = match field.c_type.as_deref() {
+            _ if field.bits.is_some() => None,
+            _ if matches!(
+                *lib.type_(field.typ),
+                Type::Enumeration(_) | Type::Bitfield(_)
+            ) =>
+            {
+                Some(32)
+            }
+            // The smallest pointer size of all platforms
+            Some("gpointer" | "gconstpointer") => Some(32),
+            Some(_) if field.is_ptr() => Some(32),
+            Some(c_type) => bitfield_unit_bits(c_type),
+            None => None,
+        };
+        bits.map_or(false, |bits| bits % storage_bits == 0)
+    };
+    let (_, last_unit_full) = pack_bitfields(run, storage_bits);
+    prev.map_or(true, is_aligned) && (last_unit_full || next.map_or(true, is_aligned))
+}
+
 /// Checks if given type is incomplete, i.e., its size is unknown.
 pub trait IsIncomplete {
     fn is_incomplete(&self, lib: &Library) -> bool;
@@ -71,6 +163,10 @@ impl IsIncomplete for Field {
         if self.is_ptr() {
             // Pointers are always complete.
             false
+        } else if self.c_type.is_none() && matches!(lib.type_(self.typ), Type::Function(..)) {
+            // So are the function pointers of inline callbacks, like the virtual
+            // methods of class structs, whatever their parameters are.
+            false
         } else {
             lib.type_(self.typ).is_incomplete(lib)
         }
@@ -79,25 +175,49 @@ impl IsIncomplete for Field {
 
 impl<'a> IsIncomplete for &'a [Field] {
     fn is_incomplete(&self, lib: &Library) -> bool {
-        if self.is_empty() {
-            return true;
-        }
+        fields_are_incomplete(self, lib, true)
+    }
+}
 
-        let mut is_bitfield = false;
-        for field in self.iter() {
-            if field.is_incomplete(lib) {
-                return true;
-            }
-            // Two consequitive bitfields are unrepresentable in Rust,
-            // so from our perspective they are incomplete.
-            if is_bitfield && field.bits.is_some() {
+fn fields_are_incomplete(fields: &[Field], lib: &Library, collapse_bitfields: bool) -> bool {
+    if fields.is_empty() {
+        return true;
+    }
+
+    if fields.iter().any(|field| field.is_incomplete(lib)) {
+        return true;
+    }
+
+    let mut i = 0;
+    while i < fields.len() {
+        let start = i;
+        i += 1;
+        if fields[start].bits.is_none() {
+            continue;
+        }
+        while i < fields.len() && fields[i].bits.is_some() {
+            // Two consequitive bitfields are unrepresentable in Rust unless
+            // they can be collapsed into integer fields, so from our
+            // perspective they are incomplete.
+            if !collapse_bitfields || !bitfields_collapsible(&fields[i - 1], &fields[i]) {
                 return true;
             }
-            is_bitfield = field.bits.is_some();
+            i += 1;
+        }
+        let run: Vec<_> = fields[start..i].iter().collect();
+        if run.len() > 1
+            && !bitfield_run_is_unambiguous(
+                lib,
+                start.checked_sub(1).map(|prev| &fields[prev]),
+                &run,
+                fields.get(i),
+            )
+        {
+            return true;
         }
-
-        false
     }
+
+    false
 }
 
 impl IsIncomplete for Class {
@@ -119,7 +239,9 @@ impl IsIncomplete for Record {
 
 impl IsIncomplete for Union {
     fn is_incomplete(&self, lib: &Library) -> bool {
-        self.fields.as_slice().is_incomplete(lib)
+        // Bitfields of unions all start at the same offset, so they can't be
+        // collapsed.
+        fields_are_incomplete(&self.fields, lib, false)
     }
 }
 
@@ -250,3 +372,179 @@ impl ImplementsDebug for Field {
         }
     }
 }
+
+/// Returns the earliest `version_end` among `types` and the types they
+/// contain, looked up by their full name
+pub fn version_end(
+    lib: &Library,
+    types: impl IntoIterator<Item = TypeId>,
+    version_end_of: &dyn Fn(&str) -> Option<Version>,
+) -> Option<Version> {
+    types
+        .into_iter()
+        .filter_map(|tid| {
+            let inner = match *lib.type_(tid) {
+                Type::Array(t)
+                | Type::CArray(t)
+                | Type::FixedArray(t, ..)
+                | Type::PtrArray(t)
+                | Type::List(t)
+                | Type::SList(t) => version_end(lib, [t], version_end_of),
+                Type::HashTable(k, v) => version_end(lib, [k, v], version_end_of),
+                _ => None,
+            };
+            version_end_of(&tid.full_name(lib))
+                .into_iter()
+                .chain(inner)
+                .min()
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(c_type: &str, bits: Option<u8>) -> Field {
+        Field {
+            typ: TypeId::tid_uint32(),
+            c_type: Some(c_type.into()),
+            bits,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pack_bitfields() {
+        let fields = [
+            field("guint", Some(20)),
+            field("guint", Some(10)),
+            field("guint", Some(4)),
+            field("guint", Some(0)),
+            field("guint", Some(1)),
+        ];
+        let run: Vec<_> = fields.iter().collect();
+        assert_eq!(
+            pack_bitfields(&run, 32),
+            (
+                vec![
+                    Some((0, 0)),
+                    Some((0, 20)),
+                    Some((1, 0)),
+                    None,
+                    Some((2, 0))
+                ],
+                false
+            )
+        );
+        assert_eq!(
+            pack_bitfields(&run[..2], 16),
+            (vec![Some((0, 0)), Some((1, 0))], false)
+        );
+        let full = [field("guint8", Some(4)), field("guint8", Some(4))];
+        let run: Vec<_> = full.iter().collect();
+        assert_eq!(
+            pack_bitfields(&run, 8),
+            (vec![Some((0, 0)), Some((0, 4))], true)
+        );
+    }
+
+    #[test]
+    fn test_bitfield_run_is_unambiguous() {
+        let lib = Library::new("Test");
+        let bitfields = [field("guint", Some(1)), field("guint", Some(1))];
+        let run: Vec<_> = bitfields.iter().collect();
+        let pointer = field("gpointer", None);
+        let int = field("gint", None);
+        let char_ = field("gchar", None);
+
+        assert!(bitfield_run_is_unambiguous(&lib, None, &run, None));
+        assert!(bitfield_run_is_unambiguous(
+            &lib,
+            Some(&pointer),
+            &run,
+            Some(&int)
+        ));
+        assert!(bitfield_run_is_unambiguous(
+            &lib,
+            Some(&field("GtkWidget*", None)),
+            &run,
+            None
+        ));
+        // The bitfields may be packed after the char
+        assert!(!bitfield_run_is_unambiguous(&lib, Some(&char_), &run, None));
+        // The char may be packed into the unit of the bitfields
+        assert!(!bitfield_run_is_unambiguous(&lib, None, &run, Some(&char_)));
+        // Unless it's full
+        let full = [field("guint8", Some(4)), field("guint8", Some(4))];
+        let run: Vec<_> = full.iter().collect();
+        assert!(bitfield_run_is_unambiguous(
+            &lib,
+            Some(&char_),
+            &run,
+            Some(&char_)
+        ));
+        // Or pointers are smaller than the unit on some platforms
+        let long = [field("guint64", Some(4)), field("guint64", Some(8))];
+        let run: Vec<_> = long.iter().collect();
+        assert!(!bitfield_run_is_unambiguous(
+            &lib,
+            Some(&pointer),
+            &run,
+            None
+        ));
+    }
+
+    #[test]
+    fn test_fields_are_incomplete() {
+        let lib = Library::new("Test");
+        let fields = [
+            field("gpointer", None),
+            field("guint", Some(1)),
+            field("guint", Some(1)),
+        ];
+        assert!(!fields_are_incomplete(&fields, &lib, true));
+        assert!(fields_are_incomplete(&fields, &lib, false));
+
+        let fields = [
+            field("gchar", None),
+            field("guint", Some(1)),
+            field("guint", Some(1)),
+        ];
+        assert!(fields_are_incomplete(&fields, &lib, true));
+
+        let fields = [field("guint", Some(1)), field("guint8", Some(1))];
+        assert!(fields_are_incomplete(&fields, &lib, true));
+    }
+
+    #[test]
+    fn test_version_end() {
+        let mut lib = Library::new("Test");
+        let old = lib.add_type(MAIN_NAMESPACE, "Old", Type::Record(Record::default()));
+        let older = lib.add_type(MAIN_NAMESPACE, "Older", Type::Record(Record::default()));
+        let list = lib.add_type(INTERNAL_NAMESPACE, "List(#Old)", Type::List(old));
+        let table = lib.add_type(
+            INTERNAL_NAMESPACE,
+            "HashTable(#utf8, #Older)",
+            Type::HashTable(TypeId::tid_utf8(), older),
+        );
+        let version_end_of = |name: &str| match name {
+            "Test.Old" => Some(Version(3, 20, 0)),
+            "Test.Older" => Some(Version(3, 10, 0)),
+            _ => None,
+        };
+
+        assert_eq!(
+            version_end(&lib, [TypeId::tid_utf8()], &version_end_of),
+            None
+        );
+        assert_eq!(
+            version_end(&lib, [TypeId::tid_utf8(), list], &version_end_of),
+            Some(Version(3, 20, 0))
+        );
+        assert_eq!(
+            version_end(&lib, [list, table], &version_end_of),
+            Some(Version(3, 10, 0))
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use super::{functions, signatures::Signature};
+use crate::{library, version::Version};
+
+/// A virtual method of a class or interface, as needed for subclassing
+#[derive(Debug)]
+pub struct Info {
+    /// Name of the field of the class or interface struct
+    pub name: String,
+    pub signature: Signature,
+    /// Rust name of the method calling the virtual method, if it's generated
+    pub invoker: Option<String>,
+    pub version: Option<Version>,
+}
+
+pub fn analyze(vfuncs: &[library::Function], functions: &[functions::Info]) -> Vec<Info> {
+    // Rust names of the generated methods by their name in the GIR
+    let invokers = functions
+        .iter()
+        .filter(|f| f.status.need_generate() && !f.commented)
+        .map(|f| (f.func_name.as_str(), f.codegen_name()))
+        .collect::<HashMap<_, _>>();
+    analyze_with_invokers(vfuncs, &invokers)
+}
+
+fn analyze_with_invokers(
+    vfuncs: &[library::Function],
+    invokers: &HashMap<&str, &str>,
+) -> Vec<Info> {
+    vfuncs
+        .iter()
+        .map(|vfunc| Info {
+            name: vfunc.name.clone(),
+            signature: Signature::new(vfunc),
+            invoker: vfunc
+                .invoker
+                .as_deref()
+                .and_then(|invoker| invokers.get(invoker))
+                .map(|&name| name.to_owned()),
+            version: vfunc.version,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::{
+        FunctionKind, Nullable, Parameter, ParameterDirection, ParameterScope, Transfer, TypeId,
+    };
+
+    fn vfunc(name: &str, invoker: Option<&str>) -> library::Function {
+        library::Function {
+            name: name.into(),
+            c_identifier: None,
+            kind: FunctionKind::VirtualMethod,
+            parameters: Vec::new(),
+            ret: Parameter {
+                name: String::new(),
+                typ: TypeId::tid_none(),
+                c_type: "void".into(),
+                instance_parameter: false,
+                direction: ParameterDirection::Return,
+                transfer: Transfer::None,
+                caller_allocates: false,
+                nullable: Nullable(false),
+                array_length: None,
+                is_error: false,
+                doc: None,
+                scope: ParameterScope::None,
+                closure: None,
+                destroy: None,
+            },
+            throws: false,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Vec::new(),
+            shadows: None,
+            shadowed_by: None,
+            finish_func: None,
+            sync_func: None,
+            async_func: None,
+            invoker: invoker.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn test_invokers() {
+        let vfuncs = [
+            vfunc("get_name", Some("get_name")),
+            vfunc("activate", Some("activate")),
+            vfunc("changed", None),
+        ];
+        // `activate` isn't generated
+        let invokers = HashMap::from([("get_name", "name")]);
+        let infos = analyze_with_invokers(&vfuncs, &invokers);
+        let names = infos
+            .iter()
+            .map(|info| (info.name.as_str(), info.invoker.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                ("get_name", Some("name")),
+                ("activate", None),
+                ("changed", None)
+            ]
+        );
+    }
+}
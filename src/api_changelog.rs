@@ -0,0 +1,446 @@
+//! Compares the public API of the generated code with the one of the previous
+//! generation run
+//!
+//! The generated files are only scanned line by line: they have a regular
+//! layout, and declarations are compared with normalized whitespace so that
+//! lines wrapped differently are the same item.
+
+use std::{collections::BTreeMap, fs, mem, path::Path};
+
+/// Kind of the block the scanned code is in
+#[derive(Clone)]
+enum Scope {
+    /// Module level, including `extern` blocks and item macros
+    Module,
+    Impl(String),
+    Trait(String),
+    Enum(String),
+    /// Struct inside `bitflags!`
+    Flags(String),
+    /// Blocks whose content isn't part of the API, like function bodies
+    Other,
+}
+
+/// Returns the declarations of the public items of the Rust files in `path`
+/// (recursively) by item, e.g. `fn button::Button::new`. A missing directory
+/// has no items.
+pub fn collect_api(path: &Path) -> BTreeMap<String, String> {
+    let mut items = BTreeMap::new();
+    collect_dir(path, path, &mut items);
+    items
+}
+
+/// Writes the items that were added (`+`), removed (`-`) or changed (`~`)
+/// since `previous` to `path`, one per line as tab separated sign, item and
+/// declarations.
+pub fn write_api_changelog(
+    path: &Path,
+    previous: &BTreeMap<String, String>,
+    auto_path: &Path,
+) -> Result<(), String> {
+    let mut content = api_changes(previous, &collect_api(auto_path)).join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to write API changelog {}: {e}", path.display()))
+}
+
+/// Returns the changes since `previous` that break code using the generated
+/// crate: removed and changed items, formatted like the changelog.
+pub fn breaking_api_changes(previous: &BTreeMap<String, String>, auto_path: &Path) -> Vec<String> {
+    api_changes(previous, &collect_api(auto_path))
+        .into_iter()
+        .filter(|line| !line.starts_with('+'))
+        .collect()
+}
+
+fn api_changes(
+    previous: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (key, old) in previous {
+        match current.get(key) {
+            None => lines.push(format!("-\t{key}\t{old}")),
+            Some(new) if new != old => lines.push(format!("~\t{key}\t{old}\t{new}")),
+            Some(_) => (),
+        }
+    }
+    for (key, new) in current {
+        if !previous.contains_key(key) {
+            lines.push(format!("+\t{key}\t{new}"));
+        }
+    }
+    lines.sort_by(|a, b| a[2..].cmp(&b[2..]));
+    lines
+}
+
+fn collect_dir(root: &Path, dir: &Path, items: &mut BTreeMap<String, String>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(root, &path, items);
+        } else if path.extension().map_or(false, |ext| ext == "rs") {
+            if let Ok(content) = fs::read_to_string(&path) {
+                collect_file(&module_path(root, &path), &content, items);
+            }
+        }
+    }
+}
+
+/// `button` for `button.rs`, empty for `mod.rs` and `lib.rs` of the root
+fn module_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path).with_extension("");
+    let mut parts = relative
+        .iter()
+        .map(|part| part.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    if matches!(parts.last().map(String::as_str), Some("mod" | "lib")) {
+        parts.pop();
+    }
+    parts.join("::")
+}
+
+fn collect_file(module: &str, content: &str, items: &mut BTreeMap<String, String>) {
+    let mut scopes = vec![Scope::Module];
+    let mut cfgs = Vec::new();
+    let mut attribute = String::new();
+    let mut declaration = String::new();
+
+    for line in content.lines().map(str::trim) {
+        if !attribute.is_empty() || (declaration.is_empty() && line.starts_with("#[")) {
+            push_part(&mut attribute, line);
+            if count(&attribute, '[') == count(&attribute, ']') {
+                let attribute = mem::take(&mut attribute);
+                if attribute.starts_with("#[cfg(") {
+                    cfgs.push(attribute);
+                }
+            }
+            continue;
+        }
+        if declaration.is_empty() && (line.is_empty() || line.starts_with("//")) {
+            continue;
+        }
+        push_part(&mut declaration, line);
+
+        let scope = scopes.last().cloned().unwrap_or(Scope::Module);
+        let code = strip_strings(&declaration);
+        let complete = count(&code, '(') == count(&code, ')')
+            && (code.ends_with(['{', ';', '}'])
+                || (code.ends_with(',') && matches!(scope, Scope::Enum(_))));
+        if !complete {
+            continue;
+        }
+
+        let declaration = mem::take(&mut declaration);
+        let header = declaration
+            .trim_end_matches(['{', '}', ';', ','])
+            .trim_end();
+        let (item, new_scope) = read_item(&scope, module, header);
+        if let Some(item) = item {
+            let mut signature = mem::take(&mut cfgs);
+            signature.push(header.to_owned());
+            items.insert(item, signature.join(" "));
+        }
+        cfgs.clear();
+
+        let opens = count(&code, '{');
+        let closes = count(&code, '}');
+        if opens > closes {
+            scopes.push(new_scope);
+            for _ in 1..opens - closes {
+                scopes.push(Scope::Other);
+            }
+        } else {
+            for _ in opens..closes {
+                if scopes.len() > 1 {
+                    scopes.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Returns the item declared by `header`, if it's public, and the scope of its
+/// body
+fn read_item(scope: &Scope, module: &str, header: &str) -> (Option<String>, Scope) {
+    let path = |parent: Option<&str>, name: &str| {
+        [module, parent.unwrap_or_default(), name]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("::")
+    };
+
+    match scope {
+        Scope::Module => {
+            if let Some(rest) = header.strip_prefix("impl") {
+                let rest = skip_generics(rest);
+                let rest = rest.split(" where ").next().unwrap_or(rest).trim();
+                return match rest.split_once(" for ") {
+                    Some((trait_, typ)) => (
+                        Some(format!("impl {} for {}", trait_, path(None, typ))),
+                        Scope::Other,
+                    ),
+                    None => (None, Scope::Impl(identifier(rest).to_owned())),
+                };
+            }
+            if header.starts_with("extern ") || header.ends_with('!') {
+                return (None, Scope::Module);
+            }
+            let rest = match header.strip_prefix("pub ") {
+                Some(rest) => rest,
+                None => return (None, Scope::Other),
+            };
+            let (kind, rest) = match rest.split_once(' ') {
+                Some(("unsafe", rest)) => ("fn", rest.trim_start_matches("fn ")),
+                Some(split) => split,
+                None => (rest, ""),
+            };
+            let name = identifier(rest);
+            let item = Some(format!("{kind} {}", path(None, name)));
+            match kind {
+                "struct" if header.contains(':') && !header.contains('(') => {
+                    (item, Scope::Flags(name.to_owned()))
+                }
+                "enum" => (item, Scope::Enum(name.to_owned())),
+                "trait" => (item, Scope::Trait(name.to_owned())),
+                "mod" => (item, Scope::Module),
+                "use" => (Some(format!("use {}", path(None, rest))), Scope::Other),
+                "struct" | "fn" | "const" | "static" | "type" => (item, Scope::Other),
+                _ => (None, Scope::Other),
+            }
+        }
+        Scope::Impl(typ) | Scope::Trait(typ) | Scope::Flags(typ) => {
+            let rest = match scope {
+                Scope::Impl(_) => header.strip_prefix("pub "),
+                _ => Some(header),
+            };
+            let (kind, rest) = match rest.and_then(|rest| rest.split_once(' ')) {
+                Some(split) => split,
+                None => return (None, Scope::Other),
+            };
+            let kind = if kind == "unsafe" { "fn" } else { kind };
+            match kind {
+                "fn" | "const" | "type" => (
+                    Some(format!(
+                        "{kind} {}",
+                        path(
+                            Some(typ.as_str()),
+                            identifier(rest.trim_start_matches("fn "))
+                        )
+                    )),
+                    Scope::Other,
+                ),
+                _ => (None, Scope::Other),
+            }
+        }
+        Scope::Enum(typ) => {
+            let name = identifier(header);
+            if name.is_empty() || name.starts_with("__") {
+                (None, Scope::Other)
+            } else {
+                (
+                    Some(format!("variant {}", path(Some(typ.as_str()), name))),
+                    Scope::Other,
+                )
+            }
+        }
+        Scope::Other => (None, Scope::Other),
+    }
+}
+
+/// Appends a line to a declaration, so that wrapped and unwrapped
+/// declarations are the same
+fn push_part(declaration: &mut String, line: &str) {
+    if !declaration.is_empty()
+        && !declaration.ends_with(['(', '<', '['])
+        && !line.starts_with([')', '>', ']'])
+    {
+        declaration.push(' ');
+    }
+    if line.starts_with([')', '>', ']']) && declaration.ends_with(',') {
+        declaration.pop();
+    }
+    declaration.push_str(line);
+}
+
+fn identifier(s: &str) -> &str {
+    let end = s
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+/// Skips the generic parameters of an `impl`
+fn skip_generics(s: &str) -> &str {
+    if !s.starts_with('<') {
+        return s;
+    }
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &s[i + 1..];
+                }
+            }
+            _ => (),
+        }
+    }
+    s
+}
+
+fn count(s: &str, c: char) -> usize {
+    s.matches(c).count()
+}
+
+/// Removes the content of string literals, which may contain braces
+fn strip_strings(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in s.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => continue,
+            }
+            if !in_string {
+                result.push(c);
+            }
+        } else {
+            if c == '"' {
+                in_string = true;
+            }
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(content: &str) -> BTreeMap<String, String> {
+        let mut items = BTreeMap::new();
+        collect_file("button", content, &mut items);
+        items
+    }
+
+    #[test]
+    fn test_collect_file() {
+        let items = collect(
+            r#"
+glib::wrapper! {
+    #[doc(alias = "GtkButton")]
+    pub struct Button(Object<ffi::GtkButton, ffi::GtkButtonClass>) @extends Widget;
+
+    match fn {
+        type_ => || ffi::gtk_button_get_type(),
+    }
+}
+
+impl Button {
+    pub const NONE: Option<&'static Button> = None;
+
+    #[doc(alias = "gtk_button_new")]
+    pub fn new() -> Button {
+        assert_initialized_main_thread!();
+        unsafe { from_glib_none(ffi::gtk_button_new()) }
+    }
+
+    #[cfg(feature = "v3_10")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "v3_10")))]
+    pub fn with_label(
+        label: &str,
+    ) -> Button {
+        let s = "}";
+        todo!()
+    }
+
+    fn private() {}
+}
+
+impl Default for Button {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub trait ButtonExt: 'static {
+    fn connect_clicked<F: Fn(&Self) + 'static>(&self, f: F) -> SignalHandlerId {
+        todo!()
+    }
+}
+
+pub enum Relief {
+    Normal,
+    None = 2,
+    #[doc(hidden)]
+    __Unknown(i32),
+}
+"#,
+        );
+        let keys = items.keys().map(String::as_str).collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            [
+                "const button::Button::NONE",
+                "enum button::Relief",
+                "fn button::Button::new",
+                "fn button::Button::with_label",
+                "fn button::ButtonExt::connect_clicked",
+                "impl Default for button::Button",
+                "struct button::Button",
+                "trait button::ButtonExt",
+                "variant button::Relief::None",
+                "variant button::Relief::Normal",
+            ]
+        );
+        assert_eq!(
+            items["fn button::Button::with_label"],
+            "#[cfg(feature = \"v3_10\")] pub fn with_label(label: &str) -> Button"
+        );
+        assert_eq!(items["variant button::Relief::None"], "None = 2");
+    }
+
+    #[test]
+    fn test_api_changes() {
+        let previous = collect("pub fn kept() {}\npub fn removed() {}\npub fn changed(a: i32) {}");
+        let current = collect("pub fn kept() {}\npub fn changed(a: u32) {}\npub fn added() {}");
+        assert_eq!(
+            api_changes(&previous, &current),
+            [
+                "+\tfn button::added\tpub fn added()",
+                "~\tfn button::changed\tpub fn changed(a: i32)\tpub fn changed(a: u32)",
+                "-\tfn button::removed\tpub fn removed()",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_module_path() {
+        let root = Path::new("src/auto");
+        assert_eq!(module_path(root, Path::new("src/auto/button.rs")), "button");
+        assert_eq!(module_path(root, Path::new("src/auto/mod.rs")), "");
+        assert_eq!(
+            module_path(root, Path::new("src/auto/subclass/widget.rs")),
+            "subclass::widget"
+        );
+    }
+}
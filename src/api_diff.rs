@@ -0,0 +1,335 @@
+//! Compares the API of the main namespaces of two versions of a library
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{
+    analysis::{functions::auto_rename, properties::get_func_name},
+    library::{Function, FunctionKind, Library, Parameter, Property, Type, TypeId, MAIN_NAMESPACE},
+    nameutil,
+};
+
+/// An item of the API with the Rust name generated for it with the default
+/// naming rules, and a description of its signature, which is only used to
+/// detect changes
+struct Item {
+    rust_name: String,
+    signature: String,
+}
+
+/// Returns a line for every type, function, signal, property and enum or flags
+/// member of the main namespace that was added, removed or changed between
+/// `old` and `new`, sorted by item.
+pub fn api_diff(old: &Library, new: &Library) -> Vec<String> {
+    let old = collect_items(old);
+    let new = collect_items(new);
+    let mut lines = Vec::new();
+
+    for (key, old_item) in &old {
+        match new.get(key) {
+            None => lines.push(format!("- {key} ({})", old_item.rust_name)),
+            Some(new_item) if new_item.signature != old_item.signature => lines.push(format!(
+                "~ {key} ({}): {} => {}",
+                new_item.rust_name, old_item.signature, new_item.signature
+            )),
+            Some(_) => (),
+        }
+    }
+    for (key, new_item) in &new {
+        if !old.contains_key(key) {
+            lines.push(format!("+ {key} ({})", new_item.rust_name));
+        }
+    }
+    lines.sort_by(|a, b| a[2..].cmp(&b[2..]));
+    lines
+}
+
+fn collect_items(library: &Library) -> BTreeMap<String, Item> {
+    let mut items = BTreeMap::new();
+    let namespace = library.namespace(MAIN_NAMESPACE);
+
+    let names = function_names(&namespace.functions);
+    for func in &namespace.functions {
+        add_function(library, &mut items, None, func, &names);
+    }
+
+    for typ in namespace.types.iter().flatten() {
+        let (name, functions, signals, properties) = match typ {
+            Type::Class(class) => (
+                &class.name,
+                class.functions.as_slice(),
+                class.signals.as_slice(),
+                class.properties.as_slice(),
+            ),
+            Type::Interface(interface) => (
+                &interface.name,
+                interface.functions.as_slice(),
+                interface.signals.as_slice(),
+                interface.properties.as_slice(),
+            ),
+            Type::Record(record) => (&record.name, record.functions.as_slice(), &[][..], &[][..]),
+            Type::Union(union) => (&union.name, union.functions.as_slice(), &[][..], &[][..]),
+            Type::Enumeration(enum_) => {
+                for member in &enum_.members {
+                    items.insert(
+                        format!("member {}", member.c_identifier),
+                        Item {
+                            rust_name: format!(
+                                "{}::{}",
+                                enum_.name,
+                                nameutil::enum_member_name(&member.name)
+                            ),
+                            signature: member.value.clone(),
+                        },
+                    );
+                }
+                (&enum_.name, enum_.functions.as_slice(), &[][..], &[][..])
+            }
+            Type::Bitfield(bitfield) => {
+                for member in &bitfield.members {
+                    items.insert(
+                        format!("member {}", member.c_identifier),
+                        Item {
+                            rust_name: format!(
+                                "{}::{}",
+                                bitfield.name,
+                                nameutil::bitfield_member_name(&member.name)
+                            ),
+                            signature: member.value.clone(),
+                        },
+                    );
+                }
+                (
+                    &bitfield.name,
+                    bitfield.functions.as_slice(),
+                    &[][..],
+                    &[][..],
+                )
+            }
+            _ => continue,
+        };
+
+        items.insert(
+            format!("type {name}"),
+            Item {
+                rust_name: name.clone(),
+                signature: typ.to_string(),
+            },
+        );
+        let names = function_names(functions);
+        for func in functions {
+            add_function(library, &mut items, Some(name), func, &names);
+        }
+        for signal in signals {
+            items.insert(
+                format!("signal {name}::{}", signal.name),
+                Item {
+                    rust_name: format!(
+                        "{name}::connect_{}",
+                        nameutil::signal_to_snake(&signal.name)
+                    ),
+                    signature: signature(library, &signal.parameters, &signal.ret, false),
+                },
+            );
+        }
+        for property in properties {
+            items.insert(
+                format!("property {name}:{}", property.name),
+                Item {
+                    rust_name: format!("{name}::{}", property_name(property)),
+                    signature: format!(
+                        "{}{}{}{}",
+                        property.typ.full_name(library),
+                        if property.readable { " readable" } else { "" },
+                        if property.writable { " writable" } else { "" },
+                        if property.construct_only {
+                            " construct-only"
+                        } else {
+                            ""
+                        },
+                    ),
+                },
+            );
+        }
+    }
+
+    items
+}
+
+fn function_names(functions: &[Function]) -> HashSet<&str> {
+    functions.iter().map(|func| func.name.as_str()).collect()
+}
+
+/// Returns the name of the getter of a readable property, or of its setter
+fn property_name(property: &Property) -> String {
+    let name = nameutil::signal_to_snake(&property.name);
+    if property.readable {
+        get_func_name(&name, property.typ == TypeId::tid_bool()).1
+    } else {
+        format!("set_{name}")
+    }
+}
+
+/// Returns the name of the function after the automatic renaming of the
+/// analysis, which unlike a configuration can't rename it to any name
+fn function_name(func: &Function, function_names: &HashSet<&str>) -> String {
+    let name = nameutil::mangle_keywords(&*func.name).into_owned();
+    let shadowed_name = func
+        .shadows
+        .as_ref()
+        .map(|shadows| nameutil::mangle_keywords(shadows.as_str()).into_owned());
+    let base_name = shadowed_name.as_deref().unwrap_or(&name);
+    let is_constructor = func.kind == FunctionKind::Constructor;
+    auto_rename(func, base_name, is_constructor, true, true)
+        .filter(|new_name| is_constructor || !function_names.contains(new_name.as_str()))
+        .or(shadowed_name)
+        .unwrap_or(name)
+}
+
+fn add_function(
+    library: &Library,
+    items: &mut BTreeMap<String, Item>,
+    type_name: Option<&String>,
+    func: &Function,
+    function_names: &HashSet<&str>,
+) {
+    let name = function_name(func, function_names);
+    let rust_name = match type_name {
+        Some(type_name) => format!("{type_name}::{name}"),
+        None => name,
+    };
+    items.insert(
+        format!(
+            "function {}",
+            func.c_identifier.as_ref().unwrap_or(&func.name)
+        ),
+        Item {
+            rust_name,
+            signature: signature(library, &func.parameters, &func.ret, func.throws),
+        },
+    );
+}
+
+fn signature(library: &Library, parameters: &[Parameter], ret: &Parameter, throws: bool) -> String {
+    let parameters = parameters
+        .iter()
+        .map(|par| par.typ.full_name(library))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "({parameters}) -> {}{}",
+        ret.typ.full_name(library),
+        if throws { " throws" } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::{Class, Nullable, ParameterDirection, ParameterScope, Transfer};
+
+    fn parameter(typ: TypeId, direction: ParameterDirection) -> Parameter {
+        Parameter {
+            name: "par".into(),
+            typ,
+            c_type: String::new(),
+            instance_parameter: false,
+            direction,
+            transfer: Transfer::None,
+            caller_allocates: false,
+            nullable: Nullable(false),
+            array_length: None,
+            is_error: false,
+            doc: None,
+            scope: ParameterScope::None,
+            closure: None,
+            destroy: None,
+        }
+    }
+
+    fn function(name: &str, parameters: &[TypeId], ret: TypeId) -> Function {
+        Function {
+            name: name.into(),
+            c_identifier: Some(format!("test_widget_{name}")),
+            kind: FunctionKind::Method,
+            parameters: parameters
+                .iter()
+                .map(|&typ| parameter(typ, ParameterDirection::In))
+                .collect(),
+            ret: parameter(ret, ParameterDirection::Return),
+            throws: false,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Vec::new(),
+            shadows: None,
+            shadowed_by: None,
+            finish_func: None,
+            sync_func: None,
+            async_func: None,
+            invoker: None,
+        }
+    }
+
+    fn library(functions: Vec<Function>) -> Library {
+        let mut library = Library::new("Test");
+        library.add_type(
+            MAIN_NAMESPACE,
+            "Widget",
+            Type::Class(Class {
+                name: "Widget".into(),
+                functions,
+                ..Default::default()
+            }),
+        );
+        library
+    }
+
+    #[test]
+    fn test_api_diff() {
+        let old = library(vec![
+            function("get_name", &[], TypeId::tid_utf8()),
+            function("get_visible", &[], TypeId::tid_bool()),
+            function("show", &[], TypeId::tid_none()),
+        ]);
+        let new = library(vec![
+            function("get_name", &[TypeId::tid_bool()], TypeId::tid_utf8()),
+            function("get_visible", &[], TypeId::tid_bool()),
+            function("hide", &[], TypeId::tid_none()),
+        ]);
+        assert_eq!(
+            api_diff(&old, &new),
+            [
+                "~ function test_widget_get_name (Widget::name): () -> *.Utf8 => \
+                 (*.Boolean) -> *.Utf8",
+                "+ function test_widget_hide (Widget::hide)",
+                "- function test_widget_show (Widget::show)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_name() {
+        let get_name = function("get_name", &[], TypeId::tid_utf8());
+        let get_visible = function("get_visible", &[], TypeId::tid_bool());
+        assert_eq!(function_name(&get_name, &HashSet::new()), "name");
+        assert_eq!(function_name(&get_visible, &HashSet::new()), "is_visible");
+        // Not renamed if it clashes with another function
+        assert_eq!(
+            function_name(&get_name, &["name"].into_iter().collect()),
+            "get_name"
+        );
+
+        let constructor = Function {
+            kind: FunctionKind::Constructor,
+            ..function("new_from_file", &[], TypeId::tid_none())
+        };
+        assert_eq!(function_name(&constructor, &HashSet::new()), "from_file");
+
+        let shadowing = Function {
+            shadows: Some("get_label".into()),
+            ..function("get_label_full", &[], TypeId::tid_utf8())
+        };
+        assert_eq!(function_name(&shadowing, &HashSet::new()), "label");
+    }
+}
@@ -52,6 +52,10 @@ pub enum Chunk {
         condition: String,
         value: Box<Chunk>,
     },
+    ResultMapReturn {
+        result: String,
+        value: Box<Chunk>,
+    },
     AssertErrorSanity,
     ErrorResultReturn {
         ret: Option<Box<Chunk>>,
@@ -80,6 +84,11 @@ pub enum Chunk {
         func_name: String,
         arguments: Vec<Chunk>,
     },
+    // Runs `body` in `catch_unwind`, evaluating `on_panic` if it panics
+    CatchUnwind {
+        body: Vec<Chunk>,
+        on_panic: String,
+    },
 }
 
 impl Chunk {
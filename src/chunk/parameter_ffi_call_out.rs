@@ -9,6 +9,7 @@ pub struct Parameter {
     pub typ: library::TypeId,
     pub transfer: library::Transfer,
     pub caller_allocates: bool,
+    pub nullable: library::Nullable,
     pub is_error: bool,
     pub is_uninitialized: bool,
     pub try_from_glib: TryFromGlib,
@@ -21,6 +22,7 @@ impl Parameter {
             typ: orig.typ,
             transfer: orig.transfer,
             caller_allocates: orig.caller_allocates,
+            nullable: orig.nullable,
             is_error: orig.is_error,
             is_uninitialized,
             try_from_glib: orig.try_from_glib.clone(),
@@ -35,6 +37,7 @@ impl From<&analysis::Parameter> for Parameter {
             typ: orig.lib_par.typ,
             transfer: orig.lib_par.transfer,
             caller_allocates: orig.lib_par.caller_allocates,
+            nullable: orig.lib_par.nullable,
             is_error: orig.lib_par.is_error,
             is_uninitialized: false,
             try_from_glib: orig.try_from_glib.clone(),
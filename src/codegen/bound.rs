@@ -30,13 +30,15 @@ impl Bound {
                 let trait_bound = self.trait_bound(r#async);
                 let trait_bound = format!("impl {trait_bound}");
 
-                // Combining a ref mode and lifetime requires parentheses for disambiguation
+                // Combining a ref mode and lifetime or several bounds requires
+                // parentheses for disambiguation
                 match self.bound_type {
                     BoundType::IsA(lifetime) => {
                         // TODO: This is fragile
                         let has_lifetime = r#async || lifetime.is_some();
+                        let has_several = !self.additional_type_strs.is_empty();
 
-                        if !ref_str.is_empty() && has_lifetime {
+                        if !ref_str.is_empty() && (has_lifetime || has_several) {
                             format!("({trait_bound})")
                         } else {
                             trait_bound
@@ -75,7 +77,10 @@ impl Bound {
                 if r#async {
                     assert!(lifetime.is_none(), "Async overwrites lifetime");
                 }
-                let is_a = format!("IsA<{}>", self.type_str);
+                let mut is_a = format!("IsA<{}>", self.type_str);
+                for type_str in &self.additional_type_strs {
+                    is_a.push_str(&format!(" + IsA<{type_str}>"));
+                }
 
                 let lifetime = r#async
                     .then(|| " + Clone + 'static".to_string())
@@ -89,3 +94,41 @@ impl Bound {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bound(additional_type_strs: &[&str]) -> Bound {
+        Bound {
+            bound_type: BoundType::IsA(None),
+            parameter_name: "widget".into(),
+            alias: None,
+            type_str: "Widget".into(),
+            additional_type_strs: additional_type_strs.iter().map(|&s| s.into()).collect(),
+            callback_modified: false,
+        }
+    }
+
+    #[test]
+    fn test_full_type_parameter_reference() {
+        let single = bound(&[]);
+        assert_eq!(
+            single.full_type_parameter_reference(RefMode::ByRef, Nullable(false), false),
+            "&impl IsA<Widget>"
+        );
+        let several = bound(&["Scrollable"]);
+        assert_eq!(
+            several.full_type_parameter_reference(RefMode::ByRef, Nullable(false), false),
+            "&(impl IsA<Widget> + IsA<Scrollable>)"
+        );
+        assert_eq!(
+            several.full_type_parameter_reference(RefMode::ByRef, Nullable(true), false),
+            "Option<&(impl IsA<Widget> + IsA<Scrollable>)>"
+        );
+        assert_eq!(
+            several.full_type_parameter_reference(RefMode::None, Nullable(false), false),
+            "impl IsA<Widget> + IsA<Scrollable>"
+        );
+    }
+}
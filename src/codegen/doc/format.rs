@@ -8,8 +8,10 @@ use regex::{Captures, Regex};
 use super::{gi_docgen, LocationInObject};
 use crate::{
     analysis::functions::Info,
-    library::{FunctionKind, TypeId},
-    nameutil, Env,
+    library::{FunctionKind, Type, TypeId},
+    nameutil,
+    traits::*,
+    Env,
 };
 
 const LANGUAGE_SEP_BEGIN: &str = "<!-- language=\"";
@@ -188,7 +190,8 @@ fn replace_c_types(
             if !IGNORE_C_WARNING_FUNCS.contains(&name) {
                 info!("No function found for `{}()`", name);
             }
-            format!("`{}{}()`", caps.get(2).map_or("", |m| m.as_str()), name)
+            let text = format!("`{}{}()`", caps.get(2).map_or("", |m| m.as_str()), name);
+            with_doc_url(text, c_function_doc_url(env, name))
         })
     });
 
@@ -217,7 +220,7 @@ fn replace_c_types(
                     constant_or_variant
                 } else {
                     info!("Type `#{}` not found", symbol_name);
-                    format!("`{symbol_name}`")
+                    with_doc_url(format!("`{symbol_name}`"), c_type_doc_url(env, symbol_name))
                 }
             }
             "@" => {
@@ -277,10 +280,10 @@ fn find_member(
     if !is_signal && !is_property {
         find_method_or_function_by_ctype(Some(type_), method_name, env, in_type)
     } else {
+        let type_id = env.library.find_type_by_glib_name(type_)?;
         env.analysis
             .objects
-            .values()
-            .find(|o| o.c_type == type_)
+            .get(&type_id.full_name(&env.library))
             .map(|info| {
                 let sym = symbols.by_tid(info.type_id).unwrap(); // we are sure the object exists
                 let name = method_name.trim_start_matches(':');
@@ -293,6 +296,16 @@ fn find_member(
     }
 }
 
+/// Returns the configured Rust name of an enum or flags member
+fn member_name(env: &Env, full_name: &str, member: &str) -> Option<String> {
+    env.config.objects.get(full_name).and_then(|c| {
+        c.members
+            .matched(member)
+            .into_iter()
+            .find_map(|m| m.rename.clone())
+    })
+}
+
 fn find_constant_or_variant(
     symbol: &str,
     env: &Env,
@@ -307,7 +320,8 @@ fn find_constant_or_variant(
     }) {
         Some(gen_member_doc_link(
             flag_info.type_id,
-            &nameutil::bitfield_member_name(&member_info.name),
+            &member_name(env, &flag_info.full_name, &member_info.name)
+                .unwrap_or_else(|| nameutil::bitfield_member_name(&member_info.name)),
             env,
             in_type,
         ))
@@ -320,7 +334,8 @@ fn find_constant_or_variant(
     }) {
         Some(gen_member_doc_link(
             enum_info.type_id,
-            &nameutil::enum_member_name(&member_info.name),
+            &member_name(env, &enum_info.full_name, &member_info.name)
+                .unwrap_or_else(|| nameutil::enum_member_name(&member_info.name)),
             env,
             in_type,
         ))
@@ -351,34 +366,18 @@ fn find_type(type_: &str, env: &Env) -> Option<String> {
         return None;
     }
 
-    let type_id = if let Some(obj) = env.analysis.objects.values().find(|o| o.c_type == type_) {
-        Some(obj.type_id)
-    } else if let Some(record) = env
-        .analysis
-        .records
-        .values()
-        .find(|r| r.type_(&env.library).c_type == type_)
-    {
-        Some(record.type_id)
-    } else if let Some(enum_) = env
-        .analysis
-        .enumerations
-        .iter()
-        .find(|e| e.type_(&env.library).c_type == type_)
-    {
-        Some(enum_.type_id)
-    } else if let Some(flag) = env
-        .analysis
-        .flags
-        .iter()
-        .find(|f| f.type_(&env.library).c_type == type_)
-    {
-        Some(flag.type_id)
-    } else {
-        None
-    };
+    let type_id = env.library.find_type_by_glib_name(type_)?;
+    let full_name = type_id.full_name(&env.library);
+    let is_generated = env.analysis.objects.contains_key(&full_name)
+        || env.analysis.records.contains_key(&full_name)
+        || env
+            .analysis
+            .enumerations
+            .iter()
+            .any(|e| e.type_id == type_id)
+        || env.analysis.flags.iter().any(|f| f.type_id == type_id);
 
-    type_id.map(|ty| gen_symbol_doc_link(ty, env))
+    is_generated.then(|| gen_symbol_doc_link(type_id, env))
 }
 
 fn find_method_or_function_by_ctype(
@@ -582,7 +581,7 @@ pub(crate) fn gen_vfunc_doc_link(symbol: &str, vfunc: &str) -> String {
 }
 
 pub(crate) fn gen_callback_doc_link(callback: &str) -> String {
-    format!("`callback::{callback}")
+    format!("`callback::{callback}`")
 }
 
 pub(crate) fn gen_alias_doc_link(alias: &str) -> String {
@@ -599,3 +598,72 @@ pub(crate) fn gen_symbol_doc_link(type_id: TypeId, env: &Env) -> String {
         format!("[`{n}`][crate::{n}]", n = sym.full_rust_name())
     }
 }
+
+/// Turns the code `text` for an item that isn't generated into a link to its
+/// C documentation, if there's any.
+pub(crate) fn with_doc_url(text: String, url: Option<String>) -> String {
+    match url {
+        Some(url) => format!("[{text}]({url})"),
+        None => text,
+    }
+}
+
+/// Returns the URL of the gi-docgen page `path`, like `class.Widget.html`, in
+/// the C documentation of the namespace `ns_id`.
+pub(crate) fn gen_c_doc_url(env: &Env, ns_id: u16, path: &str) -> Option<String> {
+    env.config
+        .doc_base_url(env, ns_id)
+        .map(|base| format!("{}/{path}", base.trim_end_matches('/')))
+}
+
+/// Returns the URL of the C documentation of the function `c_identifier`.
+pub(crate) fn c_function_doc_url(env: &Env, c_identifier: &str) -> Option<String> {
+    let location = env.symbols.borrow().function_location(c_identifier)?;
+    let path = match location.owner {
+        None => {
+            let func = &env.library.namespace(location.ns_id).functions[location.index];
+            format!("func.{}.html", func.name)
+        }
+        Some(tid) => {
+            let typ = env.library.type_(tid);
+            let func = &typ.functions()[location.index];
+            let kind = match func.kind {
+                FunctionKind::Constructor => "ctor",
+                FunctionKind::Method => "method",
+                FunctionKind::ClassMethod => "class_method",
+                _ => "type_func",
+            };
+            format!("{kind}.{}.{}.html", typ.get_name(), func.name)
+        }
+    };
+    gen_c_doc_url(env, location.ns_id, &path)
+}
+
+/// Returns the URL of the C documentation of the type `c_type`.
+pub(crate) fn c_type_doc_url(env: &Env, c_type: &str) -> Option<String> {
+    for (ns_id, namespace) in env.library.namespaces.iter().enumerate() {
+        let ns_id = ns_id as u16;
+        let typ = match namespace
+            .glib_name_index
+            .get(c_type)
+            .and_then(|&id| namespace.types[id as usize].as_ref())
+        {
+            Some(typ) => typ,
+            None => continue,
+        };
+        let kind = match typ {
+            Type::Class(_) => "class",
+            Type::Interface(_) => "iface",
+            Type::Record(_) => "struct",
+            Type::Union(_) => "union",
+            Type::Enumeration(enum_) if enum_.error_domain.is_some() => "error",
+            Type::Enumeration(_) => "enum",
+            Type::Bitfield(_) => "flags",
+            Type::Alias(_) => "alias",
+            Type::Function(_) => "callback",
+            _ => continue,
+        };
+        return gen_c_doc_url(env, ns_id, &format!("{kind}.{}.html", typ.get_name()));
+    }
+    None
+}
@@ -10,8 +10,9 @@ use super::format::find_method_or_function;
 use crate::{
     analysis::object::LocationInObject,
     codegen::doc::format::{
-        gen_alias_doc_link, gen_callback_doc_link, gen_const_doc_link, gen_object_fn_doc_link,
-        gen_property_doc_link, gen_signal_doc_link, gen_symbol_doc_link, gen_vfunc_doc_link,
+        c_function_doc_url, c_type_doc_url, gen_alias_doc_link, gen_c_doc_url,
+        gen_callback_doc_link, gen_const_doc_link, gen_object_fn_doc_link, gen_property_doc_link,
+        gen_signal_doc_link, gen_symbol_doc_link, gen_vfunc_doc_link, with_doc_url,
     },
     library::{TypeId, MAIN_NAMESPACE},
     nameutil::mangle_keywords,
@@ -96,23 +97,48 @@ static GI_DOCGEN_SYMBOLS: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"\[(callback|id|alias|class|const|ctor|enum|error|flags|func|iface|method|property|signal|struct|vfunc)[@](\w+\b)([:.]+[\w-]+\b)?([:.]+[\w-]+\b)?\]?").unwrap()
 });
 
+static GI_DOCGEN_INCLUDES: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*(.*?)\s*\}\}").unwrap());
+
 pub(crate) fn replace_c_types(
     entry: &str,
     env: &Env,
     in_type: Option<(&TypeId, Option<LocationInObject>)>,
 ) -> String {
+    let entry = replace_includes(entry);
     GI_DOCGEN_SYMBOLS
-        .replace_all(entry, |caps: &Captures<'_>| {
+        .replace_all(&entry, |caps: &Captures<'_>| {
             if let Ok(gi_type) = GiDocgen::from_str(&caps[0]) {
-                gi_type.rust_link(env, in_type)
+                let link = gi_type.rust_link(env, in_type);
+                // Items that aren't generated are only given as code
+                if link.starts_with('`') && link.ends_with('`') {
+                    with_doc_url(link, gi_type.c_doc_url(env))
+                } else {
+                    link
+                }
             } else {
-                // otherwise fallback to the original string
-                caps[0].to_string()
+                // otherwise fallback to the linked item as code
+                broken_link_text(&caps[0])
             }
         })
         .to_string()
 }
 
+/// Replaces `{{ value }}` template includes by their plain value.
+fn replace_includes(entry: &str) -> std::borrow::Cow<'_, str> {
+    GI_DOCGEN_INCLUDES.replace_all(entry, "$1")
+}
+
+/// Returns the name of the item a link that can't be parsed points to,
+/// e.g. `` `Gsk.RenderNode.test` `` for `[class@Gsk.RenderNode.test]`.
+fn broken_link_text(link: &str) -> String {
+    let item = link
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split_once('@')
+        .map_or(link, |(_, item)| item);
+    format!("`{item}`")
+}
+
 /// A representation of the various ways to link items using GI-docgen
 ///
 /// See <https://gnome.pages.gitlab.gnome.org/gi-docgen/linking.html> for details.
@@ -203,20 +229,14 @@ fn find_virtual_method_by_name(
     env: &Env,
     in_type: Option<(&TypeId, Option<LocationInObject>)>,
 ) -> Option<String> {
+    let ns_id = namespace_id(env, namespace);
+    let type_id = type_.map(|t| find_type_id(env, namespace, t));
+    let is_type = |tid: TypeId| type_id.map_or(true, |t| t == Some(tid));
     find_method_or_function(
         env,
         in_type,
-        |f| {
-            f.name == mangle_keywords(name)
-                && namespace.as_ref().map_or(f.ns_id == MAIN_NAMESPACE, |n| {
-                    &env.library.namespaces[f.ns_id as usize].name == n
-                })
-        },
-        |o| {
-            type_.map_or(true, |t| {
-                o.name == t && is_same_namespace(env, namespace, o.type_id)
-            })
-        },
+        |f| f.name == mangle_keywords(name) && Some(f.ns_id) == ns_id,
+        |o| is_type(o.type_id),
         |_| false,
         |_| false,
         |_| false,
@@ -233,35 +253,17 @@ fn find_method_or_function_by_name(
     in_type: Option<(&TypeId, Option<LocationInObject>)>,
     is_class_method: bool,
 ) -> Option<String> {
+    let ns_id = namespace_id(env, namespace);
+    let type_id = type_.map(|t| find_type_id(env, namespace, t));
+    let is_type = |tid: TypeId| type_id.map_or(true, |t| t == Some(tid));
     find_method_or_function(
         env,
         in_type,
-        |f| {
-            f.name == mangle_keywords(name)
-                && namespace.as_ref().map_or(f.ns_id == MAIN_NAMESPACE, |n| {
-                    &env.library.namespaces[f.ns_id as usize].name == n
-                })
-        },
-        |o| {
-            type_.map_or(true, |t| {
-                o.name == t && is_same_namespace(env, namespace, o.type_id)
-            })
-        },
-        |r| {
-            type_.map_or(true, |t| {
-                r.name == t && is_same_namespace(env, namespace, r.type_id)
-            })
-        },
-        |e| {
-            type_.map_or(true, |t| {
-                e.name == t && is_same_namespace(env, namespace, e.type_id)
-            })
-        },
-        |f| {
-            type_.map_or(true, |t| {
-                f.name == t && is_same_namespace(env, namespace, f.type_id)
-            })
-        },
+        |f| f.name == mangle_keywords(name) && Some(f.ns_id) == ns_id,
+        |o| is_type(o.type_id),
+        |r| is_type(r.type_id),
+        |e| is_type(e.type_id),
+        |f| is_type(f.type_id),
         is_class_method,
         false,
     )
@@ -275,7 +277,94 @@ fn is_same_namespace(env: &Env, namespace: Option<&str>, type_id: TypeId) -> boo
         })
 }
 
+/// Id of `namespace`, or of the main namespace
+fn namespace_id(env: &Env, namespace: Option<&str>) -> Option<u16> {
+    namespace.map_or(Some(MAIN_NAMESPACE), |n| env.library.find_namespace(n))
+}
+
+/// Id of the type `type_` in `namespace`, or in the main namespace
+fn find_type_id(env: &Env, namespace: Option<&str>, type_: &str) -> Option<TypeId> {
+    env.library.find_type(namespace_id(env, namespace)?, type_)
+}
+
+/// Full name of the type `type_` in `namespace`, or in the main namespace
+fn full_name(env: &Env, namespace: Option<&str>, type_: &str) -> String {
+    let namespace = namespace.unwrap_or(&env.library.namespace(MAIN_NAMESPACE).name);
+    format!("{namespace}.{type_}")
+}
+
 impl GiDocgen {
+    /// Returns the URL of the item in the C documentation of its namespace.
+    pub fn c_doc_url(&self, env: &Env) -> Option<String> {
+        let (namespace, path) = match self {
+            GiDocgen::Id(c_name) => {
+                return c_function_doc_url(env, c_name).or_else(|| c_type_doc_url(env, c_name))
+            }
+            GiDocgen::Alias(alias) => (&None, format!("alias.{alias}.html")),
+            GiDocgen::Class { namespace, type_ } => (namespace, format!("class.{type_}.html")),
+            GiDocgen::Const { namespace, type_ } => (namespace, format!("const.{type_}.html")),
+            GiDocgen::Constructor {
+                namespace,
+                type_,
+                name,
+            } => (namespace, format!("ctor.{type_}.{name}.html")),
+            GiDocgen::Callback { namespace, name } => (namespace, format!("callback.{name}.html")),
+            GiDocgen::Enum { namespace, type_ } => (namespace, format!("enum.{type_}.html")),
+            GiDocgen::Error { namespace, type_ } => (namespace, format!("error.{type_}.html")),
+            GiDocgen::Flag { namespace, type_ } => (namespace, format!("flags.{type_}.html")),
+            GiDocgen::Func {
+                namespace,
+                type_: Some(type_),
+                name,
+            } => (namespace, format!("type_func.{type_}.{name}.html")),
+            GiDocgen::Func {
+                namespace,
+                type_: None,
+                name,
+            } => (namespace, format!("func.{name}.html")),
+            GiDocgen::Interface { namespace, type_ } => (namespace, format!("iface.{type_}.html")),
+            GiDocgen::Method {
+                namespace,
+                type_,
+                name,
+                is_class_method: true,
+            } => (
+                namespace,
+                format!(
+                    "class_method.{}.{name}.html",
+                    type_.strip_suffix("Class").unwrap_or(type_)
+                ),
+            ),
+            GiDocgen::Method {
+                namespace,
+                type_,
+                name,
+                is_class_method: false,
+            } => (namespace, format!("method.{type_}.{name}.html")),
+            GiDocgen::Property {
+                namespace,
+                type_,
+                name,
+            } => (namespace, format!("property.{type_}.{name}.html")),
+            GiDocgen::Signal {
+                namespace,
+                type_,
+                name,
+            } => (namespace, format!("signal.{type_}.{name}.html")),
+            GiDocgen::Struct { namespace, type_ } => (namespace, format!("struct.{type_}.html")),
+            GiDocgen::VFunc {
+                namespace,
+                type_,
+                name,
+            } => (namespace, format!("vfunc.{type_}.{name}.html")),
+        };
+        let ns_id = match namespace {
+            Some(namespace) => env.library.find_namespace(namespace)?,
+            None => MAIN_NAMESPACE,
+        };
+        gen_c_doc_url(env, ns_id, &path)
+    }
+
     pub fn rust_link(
         &self,
         env: &Env,
@@ -295,25 +384,22 @@ impl GiDocgen {
             GiDocgen::Class { type_, namespace } | GiDocgen::Interface { type_, namespace } => env
                 .analysis
                 .objects
-                .values()
-                .find(|o| {
-                    &o.name == type_ && is_same_namespace(env, namespace.as_deref(), o.type_id)
-                })
-                .map_or_else(
-                    || format!("`{}`", ns_type_to_doc(namespace, type_)),
-                    |info| gen_symbol_doc_link(info.type_id, env),
-                ),
-            GiDocgen::Flag { type_, namespace } => env
-                .analysis
-                .flags
-                .iter()
-                .find(|e| {
-                    &e.name == type_ && is_same_namespace(env, namespace.as_deref(), e.type_id)
-                })
+                .get(&full_name(env, namespace.as_deref(), type_))
                 .map_or_else(
                     || format!("`{}`", ns_type_to_doc(namespace, type_)),
                     |info| gen_symbol_doc_link(info.type_id, env),
                 ),
+            GiDocgen::Flag { type_, namespace } => {
+                let type_id = find_type_id(env, namespace.as_deref(), type_);
+                env.analysis
+                    .flags
+                    .iter()
+                    .find(|e| Some(e.type_id) == type_id)
+                    .map_or_else(
+                        || format!("`{}`", ns_type_to_doc(namespace, type_)),
+                        |info| gen_symbol_doc_link(info.type_id, env),
+                    )
+            }
             GiDocgen::Const { type_, namespace } => env
                 .analysis
                 .constants
@@ -330,10 +416,7 @@ impl GiDocgen {
             } => env
                 .analysis
                 .objects
-                .values()
-                .find(|o| {
-                    &o.name == type_ && is_same_namespace(env, namespace.as_deref(), o.type_id)
-                })
+                .get(&full_name(env, namespace.as_deref(), type_))
                 .map_or_else(
                     || gen_property_doc_link(&ns_type_to_doc(namespace, type_), name),
                     |info| {
@@ -348,10 +431,7 @@ impl GiDocgen {
             } => env
                 .analysis
                 .objects
-                .values()
-                .find(|o| {
-                    &o.name == type_ && is_same_namespace(env, namespace.as_deref(), o.type_id)
-                })
+                .get(&full_name(env, namespace.as_deref(), type_))
                 .map_or_else(
                     || gen_signal_doc_link(&ns_type_to_doc(namespace, type_), name),
                     |info| {
@@ -366,10 +446,7 @@ impl GiDocgen {
             GiDocgen::Struct { namespace, type_ } => env
                 .analysis
                 .records
-                .values()
-                .find(|r| {
-                    &r.name == type_ && is_same_namespace(env, namespace.as_deref(), r.type_id)
-                })
+                .get(&full_name(env, namespace.as_deref(), type_))
                 .map_or_else(
                     || format!("`{}`", ns_type_to_doc(namespace, type_)),
                     |info| gen_symbol_doc_link(info.type_id, env),
@@ -378,19 +455,21 @@ impl GiDocgen {
                 namespace,
                 type_,
                 name,
-            } => env
-                .analysis
-                .find_object_by_function(
-                    env,
-                    |o| &o.name == type_ && is_same_namespace(env, namespace.as_deref(), o.type_id),
-                    |f| f.name == mangle_keywords(name),
-                )
-                .map_or_else(
-                    || format!("`{}::{}()`", ns_type_to_doc(namespace, type_), name),
-                    |(obj_info, fn_info)| {
-                        gen_object_fn_doc_link(obj_info, fn_info, env, in_type, type_)
-                    },
-                ),
+            } => {
+                let type_id = find_type_id(env, namespace.as_deref(), type_);
+                env.analysis
+                    .find_object_by_function(
+                        env,
+                        |o| Some(o.type_id) == type_id,
+                        |f| f.name == mangle_keywords(name),
+                    )
+                    .map_or_else(
+                        || format!("`{}::{}()`", ns_type_to_doc(namespace, type_), name),
+                        |(obj_info, fn_info)| {
+                            gen_object_fn_doc_link(obj_info, fn_info, env, in_type, type_)
+                        },
+                    )
+            }
             GiDocgen::Func {
                 namespace,
                 type_,
@@ -557,6 +636,19 @@ impl FromStr for GiDocgen {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_includes_and_broken_links() {
+        assert_eq!(
+            replace_includes("Defaults to {{ default_value }}."),
+            "Defaults to default_value."
+        );
+        assert_eq!(replace_includes("No {includes} here"), "No {includes} here");
+        assert_eq!(
+            broken_link_text("[class@Gsk.RenderNode.test]"),
+            "`Gsk.RenderNode.test`"
+        );
+    }
     #[test]
     fn test_link_alias() {
         assert_eq!(
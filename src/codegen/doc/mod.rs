@@ -12,6 +12,7 @@ use stripper_lib::{write_file_name, write_item_doc, Type as SType, TypeStruct};
 use self::format::reformat_doc;
 use crate::{
     analysis::{self, namespaces::MAIN, object::LocationInObject},
+    codegen::general::version_condition_string,
     config::gobjects::GObject,
     env::Env,
     file_saver::save_to_file,
@@ -65,10 +66,17 @@ trait FunctionLikeType {
     fn parameters(&self) -> &[Parameter];
     fn version(&self) -> &Option<Version>;
     fn deprecated_version(&self) -> &Option<Version>;
+    /// Documentation linking the synchronous and asynchronous variants
+    fn async_variant_doc(&self) -> Option<String> {
+        None
+    }
 }
 
 macro_rules! impl_function_like_type {
     ($ty:ident) => {
+        impl_function_like_type!($ty, {});
+    };
+    ($ty:ident, { $($extra:tt)* }) => {
         impl FunctionLikeType for $ty {
             fn doc(&self) -> &Option<String> {
                 &self.doc
@@ -88,6 +96,7 @@ macro_rules! impl_function_like_type {
             fn deprecated_version(&self) -> &Option<Version> {
                 &self.deprecated_version
             }
+            $($extra)*
         }
     };
 }
@@ -99,7 +108,23 @@ impl_to_stripper_type!(Class, Struct);
 impl_to_stripper_type!(Function, Fn);
 impl_to_stripper_type!(Signal, Fn, false);
 
-impl_function_like_type!(Function);
+impl_function_like_type!(Function, {
+    fn async_variant_doc(&self) -> Option<String> {
+        let is_finish = self.parameters.iter().any(|p| p.c_type == "GAsyncResult*");
+        if let Some(ref sync_func) = self.sync_func {
+            let sync_func = analysis::functions::sibling_c_identifier(self, sync_func)?;
+            Some(format!(
+                "This is the asynchronous version of {sync_func}()."
+            ))
+        } else if !is_finish {
+            let async_func = self.async_func.as_ref()?;
+            let async_func = analysis::functions::sibling_c_identifier(self, async_func)?;
+            Some(format!("See {async_func}() for the asynchronous version."))
+        } else {
+            None
+        }
+    }
+});
 impl_function_like_type!(Signal);
 
 pub fn generate(env: &Env) {
@@ -317,6 +342,7 @@ fn create_object_doc(w: &mut dyn Write, env: &Env, info: &analysis::object::Info
         } else {
             writeln!(w)?;
         }
+        write_version_requirement(w, env, info.version)?;
         if is_abstract {
             writeln!(
                 w,
@@ -435,6 +461,15 @@ fn create_object_doc(w: &mut dyn Write, env: &Env, info: &analysis::object::Info
                         )
                     )?;
                 }
+                if property.construct_only {
+                    if property.doc.is_some() || property.doc_deprecated.is_some() {
+                        writeln!(w)?;
+                    }
+                    writeln!(
+                        w,
+                        "This property is construct-only: it can't be changed once the object is built."
+                    )?;
+                }
                 Ok(())
             })?;
         }
@@ -470,6 +505,12 @@ fn create_object_doc(w: &mut dyn Write, env: &Env, info: &analysis::object::Info
     for function in functions {
         let configured_functions = obj.functions.matched(&function.name);
         let is_manual = configured_functions.iter().any(|f| f.status.manual());
+        let f_info = function
+            .c_identifier
+            .as_ref()
+            .and_then(|c_identifier| info.functions.iter().find(|f| &f.glib_name == c_identifier));
+        // Generated on the type even though it has a trait
+        let inherent = f_info.map_or(false, |f| f.inherent);
         let (ty, object_location) = if (has_trait || is_manual)
             && function.parameters.iter().any(|p| p.instance_parameter)
             && !info.final_type
@@ -497,6 +538,8 @@ fn create_object_doc(w: &mut dyn Write, env: &Env, info: &analysis::object::Info
                     // the `{}Manual` trait, which would be ObjectLocation::ExtManual.
                     None,
                 )
+            } else if inherent {
+                (ty.clone(), Some(LocationInObject::Impl))
             } else if is_manual {
                 (
                     TypeStruct::new(SType::Trait, &format!("{}ExtManual", info.name)),
@@ -508,8 +551,7 @@ fn create_object_doc(w: &mut dyn Write, env: &Env, info: &analysis::object::Info
         } else {
             (ty.clone(), Some(LocationInObject::Impl))
         };
-        if let Some(c_identifier) = &function.c_identifier {
-            let f_info = info.functions.iter().find(|f| &f.glib_name == c_identifier);
+        if function.c_identifier.is_some() {
             let should_be_documented = f_info.map_or(false, |f| f.should_docs_be_generated(env));
 
             if !should_be_documented {
@@ -662,6 +704,7 @@ fn create_record_doc(w: &mut dyn Write, env: &Env, info: &analysis::record::Info
             if let Some(ref doc) = record.doc {
                 writeln!(w, "{}", reformat_doc(doc, env, Some((&info.type_id, None))))?;
             }
+            write_version_requirement(w, env, info.version)?;
             if let Some(ver) = info.deprecated_version {
                 writeln!(w, "\n# Deprecated since {ver}\n")?;
             } else if record.doc_deprecated.is_some() {
@@ -717,6 +760,7 @@ fn create_enum_doc(w: &mut dyn Write, env: &Env, enum_: &Enumeration, tid: TypeI
             if let Some(ref doc) = enum_.doc {
                 writeln!(w, "{}", reformat_doc(doc, env, Some((&tid, None))))?;
             }
+            write_version_requirement(w, env, enum_.version)?;
             if let Some(ver) = enum_.deprecated_version {
                 writeln!(w, "\n# Deprecated since {ver}\n")?;
             } else if enum_.doc_deprecated.is_some() {
@@ -739,28 +783,75 @@ fn create_enum_doc(w: &mut dyn Write, env: &Env, enum_: &Enumeration, tid: TypeI
             })
             .unwrap_or(true);
 
-        if generate_doc && member.doc.is_some() {
+        if generate_doc && member_has_doc(env, member) {
+            let rename = config.and_then(|c| {
+                c.members
+                    .matched(&member.name)
+                    .into_iter()
+                    .find_map(|m| m.rename.clone())
+            });
             let sub_ty = TypeStruct {
-                name: nameutil::enum_member_name(&member.name),
+                name: rename.unwrap_or_else(|| nameutil::enum_member_name(&member.name)),
                 parent: Some(Box::new(ty.clone())),
                 ty: SType::Variant,
                 args: Vec::new(),
             };
-            write_item_doc(w, &sub_ty, |w| {
-                if let Some(ref doc) = member.doc {
-                    writeln!(w, "{}", reformat_doc(doc, env, Some((&tid, None))))?;
-                }
-                if let Some(ref doc) = member.doc_deprecated {
-                    writeln!(w, "{}", reformat_doc(doc, env, Some((&tid, None))))?;
-                }
-                Ok(())
-            })?;
+            create_member_doc(w, env, &sub_ty, member, tid)?;
         }
     }
 
     Ok(())
 }
 
+fn member_has_doc(env: &Env, member: &Member) -> bool {
+    member.doc.is_some()
+        || member.doc_deprecated.is_some()
+        || member.deprecated_version.is_some()
+        || version_condition_string(env, None, member.version, false, 0).is_some()
+}
+
+/// Documents an enum variant or a flags constant with the documentation of
+/// its member.
+fn create_member_doc(
+    w: &mut dyn Write,
+    env: &Env,
+    ty: &TypeStruct,
+    member: &Member,
+    tid: TypeId,
+) -> Result<()> {
+    write_item_doc(w, ty, |w| {
+        if let Some(ref doc) = member.doc {
+            writeln!(w, "{}", reformat_doc(doc, env, Some((&tid, None))))?;
+        }
+        write_version_requirement(w, env, member.version)?;
+        if let Some(ver) = member.deprecated_version {
+            writeln!(w, "\n# Deprecated since {ver}\n")?;
+        } else if member.doc_deprecated.is_some() {
+            writeln!(w, "\n# Deprecated\n")?;
+        }
+        if let Some(ref doc) = member.doc_deprecated {
+            writeln!(w, "{}", reformat_doc(doc, env, Some((&tid, None))))?;
+        }
+        Ok(())
+    })
+}
+
+/// Tells which version of the library an item requires and which feature
+/// enables it, if it's behind a version condition.
+fn write_version_requirement(w: &mut dyn Write, env: &Env, version: Option<Version>) -> Result<()> {
+    if version_condition_string(env, None, version, false, 0).is_some() {
+        if let Some(version) = version {
+            writeln!(
+                w,
+                "\nRequires {} {version} or newer, enabled with the `{}` feature.",
+                env.config.library_name,
+                version.to_feature()
+            )?;
+        }
+    }
+    Ok(())
+}
+
 fn create_bitfield_doc(
     w: &mut dyn Write,
     env: &Env,
@@ -776,6 +867,7 @@ fn create_bitfield_doc(
                 writeln!(w, "{}", reformat_doc(doc, env, Some((&tid, None))))?;
             }
         }
+        write_version_requirement(w, env, bitfield.version)?;
         if let Some(ver) = bitfield.deprecated_version {
             writeln!(w, "\n# Deprecated since {ver}\n")?;
         } else if bitfield.doc_deprecated.is_some() {
@@ -797,22 +889,20 @@ fn create_bitfield_doc(
             })
             .unwrap_or(true);
 
-        if generate_doc && member.doc.is_some() {
+        if generate_doc && member_has_doc(env, member) {
+            let rename = config.and_then(|c| {
+                c.members
+                    .matched(&member.name)
+                    .into_iter()
+                    .find_map(|m| m.rename.clone())
+            });
             let sub_ty = TypeStruct {
-                name: nameutil::bitfield_member_name(&member.name),
+                name: rename.unwrap_or_else(|| nameutil::bitfield_member_name(&member.name)),
                 parent: Some(Box::new(ty.clone())),
                 ty: SType::Const,
                 args: Vec::new(),
             };
-            write_item_doc(w, &sub_ty, |w| {
-                if let Some(ref doc) = member.doc {
-                    writeln!(w, "{}", reformat_doc(doc, env, Some((&tid, None))))?;
-                }
-                if let Some(ref doc) = member.doc_deprecated {
-                    writeln!(w, "{}", reformat_doc(doc, env, Some((&tid, None))))?;
-                }
-                Ok(())
-            })?;
+            create_member_doc(w, env, &sub_ty, member, tid)?;
         }
     }
 
@@ -855,6 +945,8 @@ where
         && fn_.doc_deprecated().is_none()
         && fn_.ret().doc.is_none()
         && fn_.parameters().iter().all(|p| p.doc.is_none())
+        && fn_.async_variant_doc().is_none()
+        && version_condition_string(env, None, *fn_.version(), false, 0).is_none()
     {
         return Ok(());
     }
@@ -878,6 +970,10 @@ where
                 reformat_doc(&fix_param_names(doc, &self_name), env, in_type)
             )?;
         }
+        if let Some(doc) = fn_.async_variant_doc() {
+            writeln!(w, "\n{}", reformat_doc(&doc, env, in_type))?;
+        }
+        write_version_requirement(w, env, *fn_.version())?;
         if let Some(ver) = fn_.deprecated_version() {
             writeln!(w, "\n# Deprecated since {ver}\n")?;
         } else if fn_.doc_deprecated().is_some() {
@@ -1003,6 +1099,7 @@ fn create_property_doc(
     }
     if property.doc.is_none()
         && property.doc_deprecated.is_none()
+        && version_condition_string(env, None, property.version, false, 0).is_none()
         && (property.readable || property.writable)
     {
         return Ok(());
@@ -1018,23 +1115,52 @@ fn create_property_doc(
         f.func_name == setter_name || f.new_name.as_ref().map_or(false, |n| n == &setter_name)
     });
 
-    let mut v = Vec::with_capacity(2);
+    let notify_name = format!("connect_{name_for_func}_notify");
+    let has_notify_signal = obj_info
+        .notify_signals
+        .iter()
+        .any(|s| s.connect_name == notify_name && s.trampoline.is_ok());
+
+    // Documented items and whether they connect to changes of the property
+    let mut v = Vec::with_capacity(3);
 
     if property.readable && !has_getter_method {
-        v.push(TypeStruct {
-            parent: parent.clone(),
-            ..TypeStruct::new(SType::Fn, &getter_name)
-        });
+        v.push((
+            TypeStruct {
+                parent: parent.clone(),
+                ..TypeStruct::new(SType::Fn, &getter_name)
+            },
+            false,
+        ));
     }
     if property.writable && !property.construct_only && !has_setter_method {
-        v.push(TypeStruct {
-            parent,
-            ..TypeStruct::new(SType::Fn, &setter_name)
-        });
+        v.push((
+            TypeStruct {
+                parent: parent.clone(),
+                ..TypeStruct::new(SType::Fn, &setter_name)
+            },
+            false,
+        ));
+    }
+    if has_notify_signal && property.doc.is_some() {
+        v.push((
+            TypeStruct {
+                parent,
+                ..TypeStruct::new(SType::Fn, &notify_name)
+            },
+            true,
+        ));
     }
 
-    for item in &v {
+    for (item, is_notify) in &v {
         write_item_doc(w, item, |w| {
+            if *is_notify {
+                writeln!(
+                    w,
+                    "Connects to changes of the `{}` property.\n",
+                    property.name
+                )?;
+            }
             if let Some(ref doc) = property.doc {
                 writeln!(
                     w,
@@ -1042,6 +1168,7 @@ fn create_property_doc(
                     reformat_doc(&fix_param_names(doc, &None), env, Some(in_type))
                 )?;
             }
+            write_version_requirement(w, env, property.version)?;
             if let Some(ver) = property.deprecated_version {
                 writeln!(w, "\n# Deprecated since {ver}\n")?;
             } else if property.doc_deprecated.is_some() {
@@ -10,8 +10,8 @@ use crate::{
     codegen::{
         general::{
             self, allow_deprecated, cfg_condition, cfg_condition_no_doc, cfg_condition_string,
-            cfg_deprecated, derives, doc_alias, version_condition, version_condition_no_doc,
-            version_condition_string,
+            cfg_deprecated, derives, doc_alias, object_cfg_condition, version_condition,
+            version_condition_no_doc, version_condition_string,
         },
         generate_default_impl,
     },
@@ -19,7 +19,7 @@ use crate::{
     env::Env,
     file_saver,
     library::*,
-    nameutil::{enum_member_name, use_glib_if_needed, use_glib_type},
+    nameutil::{enum_member_name, module_name, use_glib_if_needed, use_glib_type},
     traits::*,
     version::Version,
 };
@@ -43,7 +43,9 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
         mod_rs.push("\nmod enums;".into());
         for enum_analysis in &env.analysis.enumerations {
             let config = &env.config.objects[&enum_analysis.full_name];
-            if !config.status.need_generate() {
+            if !config.status.need_generate()
+                || matches!(config.version_end, Some(v) if v <= env.config.min_cfg_version)
+            {
                 continue;
             }
 
@@ -52,7 +54,8 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
             if let Some(cfg) = version_condition_string(env, None, enum_.version, false, 0) {
                 mod_rs.push(cfg);
             }
-            if let Some(cfg) = cfg_condition_string(config.cfg_condition.as_ref(), false, 0) {
+            let type_cfg_condition = object_cfg_condition(config);
+            if let Some(cfg) = cfg_condition_string(type_cfg_condition.as_ref(), false, 0) {
                 mod_rs.push(cfg);
             }
             mod_rs.push(format!(
@@ -79,12 +82,18 @@ fn generate_enum(
     config: &GObject,
     analysis: &Info,
 ) -> Result<()> {
+    let type_cfg_condition = object_cfg_condition(config);
+
     struct Member<'a> {
         name: String,
         c_name: String,
         version: Option<Version>,
         deprecated_version: Option<Version>,
         cfg_condition: Option<&'a String>,
+        // Generated name of a renamed member
+        old_name: Option<String>,
+        // Description used by `Display` for error domains
+        message: String,
     }
 
     let mut members: Vec<Member<'_>> = Vec::new();
@@ -107,12 +116,20 @@ fn generate_enum(
             .find_map(|m| m.version)
             .or(member.version);
         let cfg_condition = member_config.iter().find_map(|m| m.cfg_condition.as_ref());
+        let generated_name = enum_member_name(&member.name);
+        let (name, old_name) = match member_config.iter().find_map(|m| m.rename.as_ref()) {
+            Some(rename) => (rename.clone(), Some(generated_name)),
+            None => (generated_name, None),
+        };
+        let message = error_message(member.nick.as_deref(), &name);
         members.push(Member {
-            name: enum_member_name(&member.name),
+            name,
             c_name: member.c_identifier.clone(),
             version,
             deprecated_version,
             cfg_condition,
+            old_name,
+            message,
         });
     }
 
@@ -125,7 +142,7 @@ fn generate_enum(
         0,
     )?;
     version_condition(w, env, None, enum_.version, false, 0)?;
-    cfg_condition(w, config.cfg_condition.as_ref(), false, 0)?;
+    cfg_condition(w, type_cfg_condition.as_ref(), false, 0)?;
     if config.must_use {
         writeln!(w, "#[must_use]")?;
     }
@@ -185,6 +202,29 @@ fn generate_enum(
         .deprecated_version
         .or_else(|| members.iter().find_map(|m| m.deprecated_version));
 
+    if members.iter().any(|m| m.old_name.is_some()) {
+        writeln!(w)?;
+        version_condition(w, env, None, enum_.version, false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+        allow_deprecated(w, any_deprecated_version, false, 0)?;
+        writeln!(w, "impl {} {{", enum_.name)?;
+        for (member, old_name) in members
+            .iter()
+            .filter_map(|m| m.old_name.as_ref().map(|old_name| (m, old_name)))
+        {
+            version_condition(w, env, None, member.version, false, 1)?;
+            cfg_condition(w, member.cfg_condition.as_ref(), false, 1)?;
+            writeln!(w, "\t#[deprecated = \"Renamed to `{}`\"]", member.name)?;
+            writeln!(w, "\t#[allow(non_upper_case_globals)]")?;
+            writeln!(
+                w,
+                "\t{} const {}: Self = Self::{};",
+                analysis.visibility, old_name, member.name
+            )?;
+        }
+        writeln!(w, "}}")?;
+    }
+
     let functions = analysis
         .functions
         .iter()
@@ -194,7 +234,7 @@ fn generate_enum(
     if !functions.is_empty() {
         writeln!(w)?;
         version_condition(w, env, None, enum_.version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
         allow_deprecated(w, enum_.deprecated_version, false, 0)?;
         write!(w, "impl {} {{", analysis.name)?;
         for func_analysis in functions {
@@ -221,15 +261,39 @@ fn generate_enum(
         &analysis.specials,
         None,
         None,
-        config.cfg_condition.as_deref(),
+        type_cfg_condition.as_deref(),
     )?;
 
     writeln!(w)?;
 
-    if config.generate_display_trait && !analysis.specials.has_trait(Type::Display) {
+    let generate_display =
+        config.generate_display_trait && !analysis.specials.has_trait(Type::Display);
+    let generate_error = config.generate_error_trait && enum_.error_domain.is_some();
+    if generate_display && generate_error {
+        // Generate Display trait implementation describing the errors.
+        version_condition(w, env, None, enum_.version, false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+        allow_deprecated(w, any_deprecated_version, false, 0)?;
+        writeln!(
+            w,
+            "impl fmt::Display for {} {{\n\
+             \tfn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{\n\
+             \t\tf.write_str(match *self {{",
+            enum_.name
+        )?;
+        for member in &members {
+            version_condition_no_doc(w, env, None, member.version, false, 3)?;
+            cfg_condition_no_doc(w, member.cfg_condition.as_ref(), false, 3)?;
+            writeln!(w, "\t\t\tSelf::{} => {:?},", member.name, member.message)?;
+        }
+        if !config.exhaustive {
+            writeln!(w, "\t\t\t_ => \"Unknown error\",")?;
+        }
+        writeln!(w, "\t\t}})\n\t}}\n}}\n")?;
+    } else if generate_display {
         // Generate Display trait implementation.
         version_condition(w, env, None, enum_.version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
         allow_deprecated(w, any_deprecated_version, false, 0)?;
         writeln!(
             w,
@@ -245,14 +309,26 @@ fn generate_enum(
         }
 
         if !config.exhaustive {
-            writeln!(
-                w,
-                "\t\t\t_ => \"Unknown\",\n\
-                 \t\t}})\n\
-                 \t}}\n\
-                 }}\n"
-            )?;
+            writeln!(w, "\t\t\t_ => \"Unknown\",")?;
         }
+        writeln!(w, "\t\t}})\n\t}}\n}}\n")?;
+    }
+
+    // Error domains can be used as errors themselves if configured, which
+    // needs `Debug` and `Display`
+    let has_debug = config.derives.as_ref().map_or(true, |derives| {
+        derives
+            .iter()
+            .any(|d| d.cfg_condition.is_none() && d.names.iter().any(|n| n == "Debug"))
+    });
+    if generate_error
+        && has_debug
+        && (generate_display || analysis.specials.has_trait(Type::Display))
+    {
+        version_condition(w, env, None, enum_.version, false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+        allow_deprecated(w, any_deprecated_version, false, 0)?;
+        writeln!(w, "impl std::error::Error for {} {{}}\n", enum_.name)?;
     }
 
     // Only inline from_glib / into_glib implementations if there are not many enums members
@@ -264,7 +340,7 @@ fn generate_enum(
 
     // Generate IntoGlib trait implementation.
     version_condition(w, env, None, enum_.version, false, 0)?;
-    cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+    cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
     allow_deprecated(w, any_deprecated_version, false, 0)?;
     writeln!(
         w,
@@ -321,7 +397,7 @@ impl IntoGlib for {name} {{
 
     // Generate FromGlib trait implementation.
     version_condition(w, env, None, enum_.version, false, 0)?;
-    cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+    cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
     allow_deprecated(w, any_deprecated_version, false, 0)?;
     writeln!(
         w,
@@ -375,7 +451,7 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
         let has_failed_member = members.iter().any(|m| m.name == "Failed");
 
         version_condition(w, env, None, enum_.version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
         allow_deprecated(w, any_deprecated_version, false, 0)?;
         writeln!(
             w,
@@ -399,7 +475,13 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
                     quark,
                 )?;
             }
-            ErrorDomain::Function(f) => {
+            ErrorDomain::Function { name: f, ns_id } => {
+                let sys_crate_name = if *ns_id == MAIN_NAMESPACE {
+                    sys_crate_name.to_owned()
+                } else {
+                    let ns = &env.namespaces[*ns_id];
+                    format!("{}::{}", ns.higher_crate_name, ns.sys_crate_name)
+                };
                 writeln!(w, "        unsafe {{ from_glib({sys_crate_name}::{f}()) }}")?;
             }
         }
@@ -436,35 +518,40 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
 
     // Generate StaticType trait implementation.
     if let Some(ref get_type) = enum_.glib_get_type {
+        let skip_value_trait =
+            |trait_name: &str| config.skip_value_traits.iter().any(|t| t == trait_name);
         let configured_functions = config.functions.matched("get_type");
         let version = std::iter::once(enum_.version)
             .chain(configured_functions.iter().map(|f| f.version))
             .max()
             .flatten();
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, enum_.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl StaticType for {name} {{
+        if !skip_value_trait("StaticType") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, enum_.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl StaticType for {name} {{
     #[inline]
     fn static_type() -> Type {{
         unsafe {{ from_glib({sys_crate_name}::{get_type}()) }}
     }}
 }}",
-            sys_crate_name = sys_crate_name,
-            name = enum_.name,
-            get_type = get_type
-        )?;
-        writeln!(w)?;
+                sys_crate_name = sys_crate_name,
+                name = enum_.name,
+                get_type = get_type
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, enum_.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl {has_param_spec} for {name} {{
+        if !skip_value_trait("HasParamSpec") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, enum_.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl {has_param_spec} for {name} {{
                 type ParamSpec = {param_spec_enum};
                 type SetValue = Self;
                 type BuilderFn = fn(&str, Self) -> {param_spec_builder}<Self>;
@@ -473,32 +560,36 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
                     |name, default_value| Self::ParamSpec::builder_with_default(name, default_value)
                 }}
 }}",
-            name = enum_.name,
-            has_param_spec = use_glib_type(env, "HasParamSpec"),
-            param_spec_enum = use_glib_type(env, "ParamSpecEnum"),
-            param_spec_builder = use_glib_type(env, "ParamSpecEnumBuilder"),
-        )?;
-        writeln!(w)?;
+                name = enum_.name,
+                has_param_spec = use_glib_type(env, "HasParamSpec"),
+                param_spec_enum = use_glib_type(env, "ParamSpecEnum"),
+                param_spec_builder = use_glib_type(env, "ParamSpecEnumBuilder"),
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, enum_.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl {valuetype} for {name} {{
+        if !skip_value_trait("ValueType") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, enum_.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl {valuetype} for {name} {{
     type Type = Self;
 }}",
-            name = enum_.name,
-            valuetype = use_glib_type(env, "value::ValueType"),
-        )?;
-        writeln!(w)?;
+                name = enum_.name,
+                valuetype = use_glib_type(env, "value::ValueType"),
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, enum_.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "unsafe impl<'a> FromValue<'a> for {name} {{
+        if !skip_value_trait("FromValue") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, enum_.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "unsafe impl<'a> FromValue<'a> for {name} {{
     type Checker = {genericwrongvaluetypechecker}<Self>;
 
     #[inline]
@@ -506,20 +597,22 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
         {assert}from_glib({glib}(value.to_glib_none().0))
     }}
 }}",
-            name = enum_.name,
-            glib = use_glib_type(env, "gobject_ffi::g_value_get_enum"),
-            gvalue = use_glib_type(env, "Value"),
-            genericwrongvaluetypechecker = use_glib_type(env, "value::GenericValueTypeChecker"),
-            assert = assert,
-        )?;
-        writeln!(w)?;
+                name = enum_.name,
+                glib = use_glib_type(env, "gobject_ffi::g_value_get_enum"),
+                gvalue = use_glib_type(env, "Value"),
+                genericwrongvaluetypechecker = use_glib_type(env, "value::GenericValueTypeChecker"),
+                assert = assert,
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, enum_.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl ToValue for {name} {{
+        if !skip_value_trait("ToValue") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, enum_.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl ToValue for {name} {{
     #[inline]
     fn to_value(&self) -> {gvalue} {{
         let mut value = {gvalue}::for_value_type::<Self>();
@@ -534,29 +627,32 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
         Self::static_type()
     }}
 }}",
-            name = enum_.name,
-            glib = use_glib_type(env, "gobject_ffi::g_value_set_enum"),
-            gvalue = use_glib_type(env, "Value"),
-            gtype = use_glib_type(env, "Type"),
-        )?;
-        writeln!(w)?;
+                name = enum_.name,
+                glib = use_glib_type(env, "gobject_ffi::g_value_set_enum"),
+                gvalue = use_glib_type(env, "Value"),
+                gtype = use_glib_type(env, "Type"),
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, enum_.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl From<{name}> for {gvalue} {{
+        if !skip_value_trait("From") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, enum_.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl From<{name}> for {gvalue} {{
     #[inline]
     fn from(v: {name}) -> Self {{
         {assert}ToValue::to_value(&v)
     }}
 }}",
-            name = enum_.name,
-            gvalue = use_glib_type(env, "Value"),
-            assert = assert,
-        )?;
-        writeln!(w)?;
+                name = enum_.name,
+                gvalue = use_glib_type(env, "Value"),
+                assert = assert,
+            )?;
+            writeln!(w)?;
+        }
     }
 
     generate_default_impl(
@@ -578,5 +674,63 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
         },
     )?;
 
+    if env.config.generate_tests && !members.is_empty() {
+        writeln!(w)?;
+        writeln!(w, "#[cfg(test)]")?;
+        version_condition_no_doc(w, env, None, enum_.version, false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+        writeln!(
+            w,
+            "mod {}_tests {{
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn round_trip() {{
+        let values: &[{}] = &[",
+            module_name(&enum_.name),
+            enum_.name,
+        )?;
+        for member in &members {
+            version_condition_no_doc(w, env, None, member.version, false, 3)?;
+            cfg_condition_no_doc(w, member.cfg_condition.as_ref(), false, 3)?;
+            writeln!(w, "\t\t\t{}::{},", enum_.name, member.name)?;
+        }
+        writeln!(
+            w,
+            "\
+        ];
+        for &value in values {{
+            assert_eq!(
+                unsafe {{ {}::from_glib(value.into_glib()) }}.into_glib(),
+                value.into_glib()
+            );
+        }}
+    }}
+}}",
+            enum_.name,
+        )?;
+    }
+
     Ok(())
 }
+
+/// Returns the message written by the `Display` implementation of an error
+/// domain member: its nick, which is free of doc markup, or its name
+fn error_message(nick: Option<&str>, name: &str) -> String {
+    nick.filter(|nick| !nick.is_empty())
+        .unwrap_or(name)
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(error_message(Some("not-found"), "NotFound"), "not-found");
+        assert_eq!(error_message(Some(""), "NotFound"), "NotFound");
+        assert_eq!(error_message(None, "NotFound"), "NotFound");
+    }
+}
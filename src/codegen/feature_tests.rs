@@ -0,0 +1,118 @@
+use std::{
+    collections::BTreeMap,
+    io::{prelude::*, Result},
+    path::Path,
+};
+
+use log::info;
+
+use super::general;
+use crate::{env::Env, file_saver::save_to_file, version::Version};
+
+/// Generates a test per version feature that uses all types introduced in that
+/// version, so that `cargo test --features vX_Y` catches broken cfg gating.
+pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
+    let mut versions: BTreeMap<Version, Vec<&str>> = BTreeMap::new();
+    let types = env
+        .analysis
+        .objects
+        .values()
+        .map(|info| (&info.full_name, &info.name, info.version))
+        .chain(
+            env.analysis
+                .records
+                .values()
+                .map(|info| (&info.full_name, &info.name, info.version)),
+        )
+        .chain(env.analysis.enumerations.iter().map(|info| {
+            let version = info.type_(&env.library).version;
+            (&info.full_name, &info.name, version)
+        }))
+        .chain(env.analysis.flags.iter().map(|info| {
+            let version = info.type_(&env.library).version;
+            (&info.full_name, &info.name, version)
+        }));
+    for (full_name, name, version) in types {
+        let config = &env.config.objects[full_name];
+        // Types with custom conditions can't be checked by version alone
+        if !config.status.need_generate()
+            || config.cfg_condition.is_some()
+            || config.version_end.is_some()
+        {
+            continue;
+        }
+        if let Some(version) = version {
+            if general::version_condition_string(env, None, Some(version), false, 0).is_some() {
+                versions.entry(version).or_default().push(name);
+            }
+        }
+    }
+    if versions.is_empty() {
+        return;
+    }
+
+    let path = root_path.join("feature_tests.rs");
+    info!("Generating file {:?}", path);
+    save_to_file(path, env.config.make_backup, |w| {
+        general::start_comments(w, &env.config)?;
+        generate_tests(w, &versions)
+    });
+
+    mod_rs.push("\n#[cfg(test)]\nmod feature_tests;".into());
+}
+
+/// Writes a test per version, which only compiles if the version's feature
+/// enables all of its types.
+fn generate_tests(w: &mut dyn Write, versions: &BTreeMap<Version, Vec<&str>>) -> Result<()> {
+    for (&version, names) in versions {
+        writeln!(w)?;
+        general::cfg_condition_no_doc(w, Some(&version.to_cfg(None)), false, 0)?;
+        writeln!(w, "#[test]")?;
+        writeln!(w, "#[allow(deprecated)]")?;
+        writeln!(w, "fn {}() {{", version.to_feature())?;
+        writeln!(w, "\tlet _ = [")?;
+        for name in names {
+            writeln!(w, "\t\tstd::any::type_name::<super::{name}>(),")?;
+        }
+        writeln!(w, "\t];")?;
+        writeln!(w, "}}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_tests() {
+        let mut versions = BTreeMap::new();
+        versions.insert(Version(2, 4, 0), vec!["Overlay"]);
+        versions.insert(Version(2, 2, 0), vec!["Button", "Label"]);
+        let mut w = Vec::new();
+        generate_tests(&mut w, &versions).unwrap();
+        assert_eq!(
+            String::from_utf8(w).unwrap(),
+            "
+#[cfg(feature = \"v2_2\")]
+#[test]
+#[allow(deprecated)]
+fn v2_2() {
+\tlet _ = [
+\t\tstd::any::type_name::<super::Button>(),
+\t\tstd::any::type_name::<super::Label>(),
+\t];
+}
+
+#[cfg(feature = \"v2_4\")]
+#[test]
+#[allow(deprecated)]
+fn v2_4() {
+\tlet _ = [
+\t\tstd::any::type_name::<super::Overlay>(),
+\t];
+}
+"
+        );
+    }
+}
@@ -9,8 +9,8 @@ use crate::{
     codegen::{
         general::{
             self, cfg_condition, cfg_condition_doc, cfg_condition_no_doc, cfg_condition_string,
-            cfg_deprecated, derives, doc_alias, version_condition, version_condition_doc,
-            version_condition_no_doc, version_condition_string,
+            cfg_deprecated, derives, doc_alias, object_cfg_condition, version_condition,
+            version_condition_doc, version_condition_no_doc, version_condition_string,
         },
         generate_default_impl,
     },
@@ -18,7 +18,7 @@ use crate::{
     env::Env,
     file_saver,
     library::*,
-    nameutil::{bitfield_member_name, use_glib_type},
+    nameutil::{bitfield_member_name, module_name, use_glib_type},
     traits::*,
 };
 
@@ -41,7 +41,9 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
         mod_rs.push("\nmod flags;".into());
         for flags_analysis in &env.analysis.flags {
             let config = &env.config.objects[&flags_analysis.full_name];
-            if !config.status.need_generate() {
+            if !config.status.need_generate()
+                || matches!(config.version_end, Some(v) if v <= env.config.min_cfg_version)
+            {
                 continue;
             }
             let flags = flags_analysis.type_(&env.library);
@@ -49,7 +51,8 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
             if let Some(cfg) = version_condition_string(env, None, flags.version, false, 0) {
                 mod_rs.push(cfg);
             }
-            if let Some(cfg) = cfg_condition_string(config.cfg_condition.as_ref(), false, 0) {
+            let type_cfg_condition = object_cfg_condition(config);
+            if let Some(cfg) = cfg_condition_string(type_cfg_condition.as_ref(), false, 0) {
                 mod_rs.push(cfg);
             }
             mod_rs.push(format!(
@@ -75,11 +78,12 @@ fn generate_flags(
     config: &GObject,
     analysis: &Info,
 ) -> Result<()> {
+    let type_cfg_condition = object_cfg_condition(config);
     let sys_crate_name = env.main_sys_crate_name();
-    cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+    cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
     version_condition_no_doc(w, env, None, flags.version, false, 0)?;
     writeln!(w, "bitflags! {{")?;
-    cfg_condition_doc(w, config.cfg_condition.as_ref(), false, 1)?;
+    cfg_condition_doc(w, type_cfg_condition.as_ref(), false, 1)?;
     version_condition_doc(w, env, flags.version, false, 1)?;
     cfg_deprecated(
         w,
@@ -103,13 +107,19 @@ fn generate_flags(
         "    {} struct {}: u32 {{",
         analysis.visibility, flags.name
     )?;
+    let mut test_members = Vec::new();
+    let mut renamed_members = Vec::new();
     for member in &flags.members {
         let member_config = config.members.matched(&member.name);
         if member.status.ignored() {
             continue;
         }
 
-        let name = bitfield_member_name(&member.name);
+        let generated_name = bitfield_member_name(&member.name);
+        let (name, old_name) = match member_config.iter().find_map(|m| m.rename.as_ref()) {
+            Some(rename) => (rename.clone(), Some(generated_name)),
+            None => (generated_name, None),
+        };
         let deprecated_version = member_config
             .iter()
             .find_map(|m| m.deprecated_version)
@@ -130,6 +140,10 @@ fn generate_flags(
             "\t\tconst {} = {}::{} as _;",
             name, sys_crate_name, member.c_identifier,
         )?;
+        if let Some(old_name) = old_name {
+            renamed_members.push((old_name, name.clone(), version, cfg_cond));
+        }
+        test_members.push((name, version, cfg_cond));
     }
 
     writeln!(
@@ -138,6 +152,25 @@ fn generate_flags(
 }}"
     )?;
 
+    if !renamed_members.is_empty() {
+        writeln!(w)?;
+        version_condition(w, env, None, flags.version, false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+        allow_deprecated(w, flags.deprecated_version, false, 0)?;
+        writeln!(w, "impl {} {{", flags.name)?;
+        for (old_name, name, version, cfg_cond) in renamed_members {
+            version_condition(w, env, None, version, false, 1)?;
+            cfg_condition(w, cfg_cond, false, 1)?;
+            writeln!(w, "\t#[deprecated = \"Renamed to `{name}`\"]")?;
+            writeln!(
+                w,
+                "\t{} const {old_name}: Self = Self::{name};",
+                analysis.visibility
+            )?;
+        }
+        writeln!(w, "}}")?;
+    }
+
     let functions = analysis
         .functions
         .iter()
@@ -147,7 +180,7 @@ fn generate_flags(
     if !functions.is_empty() {
         writeln!(w)?;
         version_condition(w, env, None, flags.version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
         allow_deprecated(w, flags.deprecated_version, false, 0)?;
         write!(w, "impl {} {{", analysis.name)?;
         for func_analysis in functions {
@@ -174,7 +207,7 @@ fn generate_flags(
         &analysis.specials,
         None,
         None,
-        config.cfg_condition.as_deref(),
+        type_cfg_condition.as_deref(),
     )?;
 
     writeln!(w)?;
@@ -182,7 +215,7 @@ fn generate_flags(
     if config.generate_display_trait && !analysis.specials.has_trait(Type::Display) {
         // Generate Display trait implementation.
         version_condition(w, env, None, flags.version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
         allow_deprecated(w, flags.deprecated_version, false, 0)?;
         writeln!(
             w,
@@ -211,12 +244,16 @@ fn generate_flags(
                 .find_map(|m| m.version)
                 .or(member.version);
             let cfg_cond = member_config.iter().find_map(|m| m.cfg_condition.as_ref());
-            Some((version, cfg_cond, bitfield_member_name(&member.name)))
+            let name = member_config
+                .iter()
+                .find_map(|m| m.rename.clone())
+                .unwrap_or_else(|| bitfield_member_name(&member.name));
+            Some((version, cfg_cond, name))
         },
     )?;
 
     version_condition(w, env, None, flags.version, false, 0)?;
-    cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+    cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
     allow_deprecated(w, flags.deprecated_version, false, 0)?;
     writeln!(
         w,
@@ -242,7 +279,7 @@ impl IntoGlib for {name} {{
     };
 
     version_condition(w, env, None, flags.version, false, 0)?;
-    cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
+    cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
     allow_deprecated(w, flags.deprecated_version, false, 0)?;
     writeln!(
         w,
@@ -261,35 +298,40 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
     )?;
 
     if let Some(ref get_type) = flags.glib_get_type {
+        let skip_value_trait =
+            |trait_name: &str| config.skip_value_traits.iter().any(|t| t == trait_name);
         let configured_functions = config.functions.matched("get_type");
         let version = std::iter::once(flags.version)
             .chain(configured_functions.iter().map(|f| f.version))
             .max()
             .flatten();
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, flags.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl StaticType for {name} {{
+        if !skip_value_trait("StaticType") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, flags.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl StaticType for {name} {{
     #[inline]
     fn static_type() -> Type {{
         unsafe {{ from_glib({sys_crate_name}::{get_type}()) }}
     }}
 }}",
-            sys_crate_name = sys_crate_name,
-            name = flags.name,
-            get_type = get_type
-        )?;
-        writeln!(w)?;
+                sys_crate_name = sys_crate_name,
+                name = flags.name,
+                get_type = get_type
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, flags.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl {has_param_spec} for {name} {{
+        if !skip_value_trait("HasParamSpec") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, flags.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl {has_param_spec} for {name} {{
                 type ParamSpec = {param_spec_flags};
                 type SetValue = Self;
                 type BuilderFn = fn(&str) -> {param_spec_builder}<Self>;
@@ -298,32 +340,36 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
                     |name| Self::ParamSpec::builder(name)
                 }}
 }}",
-            name = flags.name,
-            has_param_spec = use_glib_type(env, "HasParamSpec"),
-            param_spec_flags = use_glib_type(env, "ParamSpecFlags"),
-            param_spec_builder = use_glib_type(env, "ParamSpecFlagsBuilder"),
-        )?;
-        writeln!(w)?;
+                name = flags.name,
+                has_param_spec = use_glib_type(env, "HasParamSpec"),
+                param_spec_flags = use_glib_type(env, "ParamSpecFlags"),
+                param_spec_builder = use_glib_type(env, "ParamSpecFlagsBuilder"),
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, flags.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl {valuetype} for {name} {{
+        if !skip_value_trait("ValueType") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, flags.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl {valuetype} for {name} {{
     type Type = Self;
 }}",
-            name = flags.name,
-            valuetype = use_glib_type(env, "value::ValueType"),
-        )?;
-        writeln!(w)?;
+                name = flags.name,
+                valuetype = use_glib_type(env, "value::ValueType"),
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, flags.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "unsafe impl<'a> FromValue<'a> for {name} {{
+        if !skip_value_trait("FromValue") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, flags.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "unsafe impl<'a> FromValue<'a> for {name} {{
     type Checker = {genericwrongvaluetypechecker}<Self>;
 
     #[inline]
@@ -331,20 +377,22 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
         {assert}from_glib({glib}(value.to_glib_none().0))
     }}
 }}",
-            name = flags.name,
-            glib = use_glib_type(env, "gobject_ffi::g_value_get_flags"),
-            gvalue = use_glib_type(env, "Value"),
-            genericwrongvaluetypechecker = use_glib_type(env, "value::GenericValueTypeChecker"),
-            assert = assert,
-        )?;
-        writeln!(w)?;
+                name = flags.name,
+                glib = use_glib_type(env, "gobject_ffi::g_value_get_flags"),
+                gvalue = use_glib_type(env, "Value"),
+                genericwrongvaluetypechecker = use_glib_type(env, "value::GenericValueTypeChecker"),
+                assert = assert,
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, flags.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl ToValue for {name} {{
+        if !skip_value_trait("ToValue") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, flags.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl ToValue for {name} {{
     #[inline]
     fn to_value(&self) -> {gvalue} {{
         let mut value = {gvalue}::for_value_type::<Self>();
@@ -359,29 +407,81 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
         Self::static_type()
     }}
 }}",
-            name = flags.name,
-            glib = use_glib_type(env, "gobject_ffi::g_value_set_flags"),
-            gvalue = use_glib_type(env, "Value"),
-            gtype = use_glib_type(env, "Type"),
-        )?;
-        writeln!(w)?;
+                name = flags.name,
+                glib = use_glib_type(env, "gobject_ffi::g_value_set_flags"),
+                gvalue = use_glib_type(env, "Value"),
+                gtype = use_glib_type(env, "Type"),
+            )?;
+            writeln!(w)?;
+        }
 
-        version_condition(w, env, None, version, false, 0)?;
-        cfg_condition_no_doc(w, config.cfg_condition.as_ref(), false, 0)?;
-        allow_deprecated(w, flags.deprecated_version, false, 0)?;
-        writeln!(
-            w,
-            "impl From<{name}> for {gvalue} {{
+        if !skip_value_trait("From") {
+            version_condition(w, env, None, version, false, 0)?;
+            cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+            allow_deprecated(w, flags.deprecated_version, false, 0)?;
+            writeln!(
+                w,
+                "impl From<{name}> for {gvalue} {{
     #[inline]
     fn from(v: {name}) -> Self {{
         {assert}ToValue::to_value(&v)
     }}
+}}",
+                name = flags.name,
+                gvalue = use_glib_type(env, "Value"),
+                assert = assert,
+            )?;
+            writeln!(w)?;
+        }
+    }
+
+    if env.config.generate_tests && !test_members.is_empty() {
+        writeln!(w)?;
+        writeln!(w, "#[cfg(test)]")?;
+        version_condition_no_doc(w, env, None, flags.version, false, 0)?;
+        cfg_condition_no_doc(w, type_cfg_condition.as_ref(), false, 0)?;
+        writeln!(
+            w,
+            "mod {}_tests {{
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn round_trip() {{
+        let values: &[{}] = &[",
+            module_name(&flags.name),
+            flags.name,
+        )?;
+        for (name, version, cfg_cond) in &test_members {
+            version_condition_no_doc(w, env, None, *version, false, 3)?;
+            cfg_condition_no_doc(w, *cfg_cond, false, 3)?;
+            writeln!(w, "\t\t\t{}::{},", flags.name, name)?;
+        }
+        // Members with several bits are expected to be combinations of
+        // single bit members
+        writeln!(
+            w,
+            "\
+        ];
+        let single_bits = values
+            .iter()
+            .filter(|value| value.bits().count_ones() == 1)
+            .fold(0, |acc, value| acc | value.bits());
+        for &value in values {{
+            let bits = unsafe {{ {name}::from_glib(value.into_glib()) }}.bits();
+            assert_eq!(bits, value.bits());
+            if bits.count_ones() > 1 {{
+                assert_eq!(bits & !single_bits, 0, {message});
+            }}
+        }}
+    }}
 }}",
             name = flags.name,
-            gvalue = use_glib_type(env, "Value"),
-            assert = assert,
+            message = general::format_args(
+                "{bits:#x} isn't a combination of other members",
+                env.config.supports_rust(general::INLINE_FORMAT_ARGS),
+            ),
         )?;
-        writeln!(w)?;
     }
 
     Ok(())
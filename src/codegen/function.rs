@@ -15,6 +15,7 @@ use super::{
     parameter::ToParameter,
     return_value::{out_parameter_types, out_parameters_as_return, ToReturnValue},
     special_functions,
+    translate_to_glib::TranslateToGlib,
 };
 use crate::{
     analysis::{self, bounds::Bounds, try_from_glib::TryFromGlib},
@@ -30,11 +31,19 @@ use crate::{
 //
 // If `Self` is returned (so `-> Self`) in a method (whatever the form of the
 // `self`), then the `#[must_use]` attribute must be added.
+//
+// It is also added if the analysis (or the configuration) decided that the
+// return value must be checked. It isn't added for `Self` if the type itself is
+// `#[must_use]`, which would trigger the `double_must_use` clippy lint.
 pub fn get_must_use_if_needed(
+    env: &Env,
     parent_type_id: Option<TypeId>,
     analysis: &analysis::functions::Info,
     comment_prefix: &str,
 ) -> Option<String> {
+    if analysis.must_use {
+        return Some(format!("{comment_prefix}#[must_use]\n"));
+    }
     // If there is no parent, it means it's not a (trait) method so we're not
     // interested.
     if let Some(parent_type_id) = parent_type_id {
@@ -45,7 +54,13 @@ pub fn get_must_use_if_needed(
             let outs = out_parameter_types(analysis);
             // If there is only one type returned, we check if it's the same type as `self`
             // (stored in `parent_type_id`).
-            if [parent_type_id] == *outs.as_slice() {
+            if [parent_type_id] == *outs.as_slice()
+                && !env
+                    .config
+                    .objects
+                    .get(&parent_type_id.full_name(&env.library))
+                    .map_or(false, |o| o.must_use)
+            {
                 return Some(format!("{comment_prefix}#[must_use]\n"));
             }
         }
@@ -95,6 +110,11 @@ pub fn generate(
     let suffix = if only_declaration { ";" } else { " {" };
 
     writeln!(w)?;
+    if commented && env.config.generate_skip_reasons {
+        if let Some(ref reason) = analysis.commented_reason {
+            writeln!(w, "{}//TODO: not generated, {reason}", tabs(indent))?;
+        }
+    }
     cfg_deprecated(w, env, None, analysis.deprecated_version, commented, indent)?;
     cfg_condition(w, analysis.cfg_condition.as_ref(), commented, indent)?;
     let version = Version::if_stricter_than(analysis.version, scope_version);
@@ -128,7 +148,7 @@ pub fn generate(
         "{}{}{}{}{}{}{}{}{}",
         allow_should_implement_trait,
         dead_code_cfg,
-        get_must_use_if_needed(parent_type_id, analysis, comment_prefix).unwrap_or_default(),
+        get_must_use_if_needed(env, parent_type_id, analysis, comment_prefix).unwrap_or_default(),
         tabs(indent),
         comment_prefix,
         pub_prefix,
@@ -138,9 +158,20 @@ pub fn generate(
     )?;
 
     if !only_declaration {
-        let body = body_chunk(env, analysis).to_code(env);
-        for s in body {
-            writeln!(w, "{}{}", tabs(indent), s)?;
+        if let Some(ref custom_body) = analysis.custom_body {
+            for s in custom_body.trim_end().lines() {
+                if !s.is_empty() {
+                    writeln!(w, "{}{}{}", tabs(indent + 1), comment_prefix, s)?;
+                } else {
+                    writeln!(w)?;
+                }
+            }
+            writeln!(w, "{}{}}}", tabs(indent), comment_prefix)?;
+        } else {
+            let body = body_chunk(env, analysis).to_code(env);
+            for s in body {
+                writeln!(w, "{}{}", tabs(indent), s)?;
+            }
         }
     }
 
@@ -180,9 +211,122 @@ pub fn generate(
         }
     }
 
+    if let Some(ref raw) = analysis.raw {
+        generate_raw(
+            w,
+            env,
+            analysis,
+            raw,
+            version,
+            &pub_prefix,
+            only_declaration,
+            indent,
+        )?;
+    }
+
     Ok(())
 }
 
+/// Generates the unsafe `_raw` variant of a function that can't be generated,
+/// which only converts the instance parameter
+fn generate_raw(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::functions::Info,
+    raw: &analysis::functions::RawFunction,
+    version: Option<Version>,
+    pub_prefix: &str,
+    only_declaration: bool,
+    indent: usize,
+) -> Result<()> {
+    let instance_par = analysis
+        .parameters
+        .c_parameters
+        .iter()
+        .find(|par| par.instance_parameter)
+        .map(|par| par.to_parameter(env, &analysis.bounds, false));
+    let suffix = if only_declaration { ";" } else { " {" };
+
+    writeln!(w)?;
+    cfg_deprecated(w, env, None, analysis.deprecated_version, false, indent)?;
+    cfg_condition(w, analysis.cfg_condition.as_ref(), false, indent)?;
+    version_condition(w, env, None, version, false, indent)?;
+    not_version_condition(w, analysis.not_version, false, indent)?;
+    doc_hidden(w, analysis.doc_hidden, "", indent)?;
+    allow_deprecated(w, analysis.deprecated_version, false, indent)?;
+    doc_alias(w, &analysis.glib_name, "", indent)?;
+    writeln!(w, "{}#[allow(clippy::missing_safety_doc)]", tabs(indent))?;
+    writeln!(
+        w,
+        "{}{}{}{}",
+        tabs(indent),
+        pub_prefix,
+        raw_declaration(analysis.codegen_name(), instance_par.as_deref(), raw),
+        suffix
+    )?;
+    if only_declaration {
+        return Ok(());
+    }
+
+    if !analysis.assertion.is_none() {
+        writeln!(
+            w,
+            "{}{}",
+            tabs(indent + 1),
+            safety_assertion_mode_to_str(analysis.assertion)
+        )?;
+    }
+    writeln!(
+        w,
+        "{}{}",
+        tabs(indent + 1),
+        raw_call(env.main_sys_crate_name(), &analysis.glib_name, raw)
+    )?;
+    writeln!(w, "{}}}", tabs(indent))
+}
+
+fn raw_declaration(
+    name: &str,
+    instance_par: Option<&str>,
+    raw: &analysis::functions::RawFunction,
+) -> String {
+    let params = instance_par
+        .map(ToOwned::to_owned)
+        .into_iter()
+        .chain(
+            raw.parameters
+                .iter()
+                .map(|(name, typ)| format!("{name}: {typ}")),
+        )
+        .collect::<Vec<_>>();
+    let return_str = raw
+        .ret
+        .as_ref()
+        .map(|ret| format!(" -> {ret}"))
+        .unwrap_or_default();
+    format!(
+        "unsafe fn {}_raw({}){}",
+        name,
+        params.join(", "),
+        return_str
+    )
+}
+
+fn raw_call(
+    sys_crate_name: &str,
+    glib_name: &str,
+    raw: &analysis::functions::RawFunction,
+) -> String {
+    let args = raw
+        .instance
+        .as_ref()
+        .map(TranslateToGlib::translate_to_glib)
+        .into_iter()
+        .chain(raw.parameters.iter().map(|(name, _)| name.clone()))
+        .collect::<Vec<_>>();
+    format!("{}::{}({})", sys_crate_name, glib_name, args.join(", "))
+}
+
 pub fn declaration(env: &Env, analysis: &analysis::functions::Info) -> String {
     let outs_as_return = !analysis.outs.is_empty();
     let return_str = if outs_as_return {
@@ -351,7 +495,9 @@ pub fn body_chunk(env: &Env, analysis: &analysis::functions::Info) -> Chunk {
         .ret(&analysis.ret)
         .transformations(&analysis.parameters.transformations)
         .in_unsafe(analysis.unsafe_)
-        .outs_mode(analysis.outs.mode);
+        .trace_feature(env.config.trace_feature.as_deref())
+        .outs_mode(analysis.outs.mode)
+        .ret_last(analysis.outs.ret_last);
 
     if analysis.r#async {
         if let Some(ref trampoline) = analysis.trampoline {
@@ -423,7 +569,13 @@ pub fn body_chunk_futures(
         let type_ = env.type_(par.typ);
         let is_str = matches!(*type_, library::Type::Basic(library::Basic::Utf8));
 
-        if *c_par.nullable {
+        if c_par.to_value {
+            if *c_par.nullable {
+                writeln!(body, "let {0} = {0}.map(|v| v.to_value());", par.name)?;
+            } else {
+                writeln!(body, "let {0} = {0}.to_value();", par.name)?;
+            }
+        } else if *c_par.nullable {
             writeln!(
                 body,
                 "let {} = {}.map(ToOwned::to_owned);",
@@ -465,7 +617,13 @@ pub fn body_chunk_futures(
         } else {
             let c_par = &analysis.parameters.c_parameters[par.ind_c];
 
-            if *c_par.nullable {
+            if c_par.to_value && *c_par.nullable {
+                writeln!(
+                    body,
+                    "\t\t{}.as_ref().map(|v| v as &dyn ToValue),",
+                    par.name
+                )?;
+            } else if *c_par.nullable {
                 writeln!(
                     body,
                     "\t\t{}.as_ref().map(::std::borrow::Borrow::borrow),",
@@ -487,3 +645,54 @@ pub fn body_chunk_futures(
 
     Ok(body)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::{
+            function_parameters::TransformationType, functions::RawFunction, ref_mode::RefMode,
+        },
+        library::Transfer,
+    };
+
+    #[test]
+    fn test_raw_function() {
+        let raw = RawFunction {
+            instance: Some(TransformationType::ToGlibPointer {
+                name: "self".into(),
+                instance_parameter: true,
+                transfer: Transfer::None,
+                ref_mode: RefMode::ByRef,
+                to_glib_extra: ".as_ref()".into(),
+                explicit_target_type: String::new(),
+                pointer_cast: String::new(),
+                in_trait: true,
+                nullable: false,
+                move_: false,
+            }),
+            parameters: vec![
+                ("data".into(), "glib::ffi::gpointer".into()),
+                ("len".into(), "usize".into()),
+            ],
+            ret: Some("glib::ffi::gboolean".into()),
+        };
+        assert_eq!(
+            raw_declaration("write", Some("&self"), &raw),
+            "unsafe fn write_raw(&self, data: glib::ffi::gpointer, len: usize) -> \
+             glib::ffi::gboolean"
+        );
+        assert_eq!(
+            raw_call("ffi", "g_output_stream_write", &raw),
+            "ffi::g_output_stream_write(self.as_ref().to_glib_none().0, data, len)"
+        );
+
+        let raw = RawFunction {
+            instance: None,
+            parameters: Vec::new(),
+            ret: None,
+        };
+        assert_eq!(raw_declaration("init", None, &raw), "unsafe fn init_raw()");
+        assert_eq!(raw_call("ffi", "gst_init", &raw), "ffi::gst_init()");
+    }
+}
@@ -65,7 +65,9 @@ pub struct Builder {
     outs_as_return: bool,
     in_unsafe: bool,
     outs_mode: Mode,
+    ret_last: bool,
     assertion: SafetyAssertionMode,
+    trace_feature: Option<String>,
 }
 
 // Key: user data index
@@ -132,10 +134,18 @@ impl Builder {
         self.outs_mode = mode;
         self
     }
+    pub fn ret_last(&mut self, ret_last: bool) -> &mut Self {
+        self.ret_last = ret_last;
+        self
+    }
     pub fn in_unsafe(&mut self, in_unsafe: bool) -> &mut Self {
         self.in_unsafe = in_unsafe;
         self
     }
+    pub fn trace_feature(&mut self, feature: Option<&str>) -> &mut Self {
+        self.trace_feature = feature.map(ToOwned::to_owned);
+        self
+    }
     pub fn generate(&self, env: &Env, bounds: &str, bounds_names: &str) -> Chunk {
         let mut body = Vec::new();
 
@@ -208,15 +218,40 @@ impl Builder {
         let ret = self.generate_out_return(&mut uninitialized_vars);
         let (call, ret) = self.apply_outs_mode(call, ret, &mut uninitialized_vars);
 
-        body.push(call);
-        if let Some(chunk) = ret {
-            body.push(chunk);
+        let glib_type = |name: &str| use_glib_type(env, name);
+        self.add_inout_scalars(&mut body);
+        self.add_in_garrays(&mut body, &glib_type);
+        let write_backs = self.generate_inout_write_backs(&glib_type);
+        if write_backs.is_empty() {
+            body.push(call);
+            if let Some(chunk) = ret {
+                body.push(chunk);
+            }
+        } else {
+            let ret = match ret {
+                Some(chunk) => {
+                    body.push(call);
+                    chunk
+                }
+                None => {
+                    body.push(Chunk::Let {
+                        name: "ret".into(),
+                        is_mut: false,
+                        value: Box::new(call),
+                        type_: None,
+                    });
+                    Chunk::Custom("ret".into())
+                }
+            };
+            body.extend(write_backs);
+            body.push(ret);
         }
 
         let mut chunks = Vec::new();
 
         self.add_in_array_lengths(&mut chunks);
         self.add_assertion(&mut chunks);
+        self.add_trace(&mut chunks);
 
         if !self.callbacks.is_empty() || !self.destroys.is_empty() {
             // Key: user data index
@@ -317,6 +352,10 @@ impl Builder {
                         ))),
                         type_: Some(Box::new(Chunk::Custom(if calls[0].scope.is_call() {
                             format!("&{}", calls[0].bound_name)
+                        } else if self.is_async_with_destroy(calls[0].user_data_index)
+                            && !*calls[0].nullable
+                        {
+                            format!("Box_<Option<{}>>", calls[0].bound_name)
                         } else {
                             format!("Box_<{}>", calls[0].bound_name)
                         }))),
@@ -375,6 +414,19 @@ impl Builder {
             .map(|(_, need_from_glib)| *need_from_glib)
     }
 
+    /// Whether the user data is an async callback that is also freed by a
+    /// destroy notify. The closure is then taken out of an `Option` when it's
+    /// called and the box is only freed by the destroy notify.
+    fn is_async_with_destroy(&self, user_data_index: usize) -> bool {
+        self.callbacks
+            .iter()
+            .any(|c| c.user_data_index == user_data_index && c.scope.is_async())
+            && self
+                .destroys
+                .iter()
+                .any(|d| d.user_data_index == user_data_index)
+    }
+
     fn add_trampoline(
         &self,
         env: &Env,
@@ -386,8 +438,27 @@ impl Builder {
         bounds_names: &str,
         is_destroy: bool,
     ) {
+        let take_once =
+            full_type.is_none() && self.is_async_with_destroy(trampoline.user_data_index);
+        // Nullable callbacks are already stored in an `Option`
+        let once_type = if *trampoline.nullable {
+            trampoline.bound_name.clone()
+        } else {
+            format!("Option<{}>", trampoline.bound_name)
+        };
         if !is_destroy {
-            if full_type.is_none() {
+            if take_once {
+                chunks.push(Chunk::Custom(format!(
+                    "let {0}_data: Box_<{1}> = Box_::new({2});",
+                    trampoline.name,
+                    once_type,
+                    if *trampoline.nullable {
+                        trampoline.name.clone()
+                    } else {
+                        format!("Some({})", trampoline.name)
+                    }
+                )));
+            } else if full_type.is_none() {
                 if trampoline.scope.is_call() {
                     chunks.push(Chunk::Custom(format!(
                         "let {0}_data: {1} = {0};",
@@ -536,6 +607,26 @@ impl Builder {
                     )));
                 }
             }
+        } else if take_once {
+            body.push(Chunk::Let {
+                name: format!("{}callback", if is_destroy { "_" } else { "" }),
+                is_mut: false,
+                value: Box::new(Chunk::Custom(if is_destroy {
+                    format!("Box_::from_raw({func} as *mut _)")
+                } else {
+                    format!("&mut *({func} as *mut _)")
+                })),
+                type_: Some(Box::new(Chunk::Custom(if is_destroy {
+                    format!("Box_<{once_type}>")
+                } else {
+                    format!("&mut {once_type}")
+                }))),
+            });
+            if !is_destroy {
+                body.push(Chunk::Custom(
+                    "let callback = callback.take().expect(\"cannot get closure...\");".to_owned(),
+                ));
+            }
         } else {
             body.push(Chunk::Let {
                 name: format!("{}callback", if is_destroy { "_" } else { "" }),
@@ -588,7 +679,9 @@ impl Builder {
             use crate::writer::to_code::ToCode;
             body.push(Chunk::Custom(format!(
                 "{}({})",
-                if !*trampoline.nullable {
+                if take_once {
+                    "callback"
+                } else if !*trampoline.nullable {
                     "(*callback)"
                 } else if trampoline.scope.is_async() {
                     "callback"
@@ -613,6 +706,15 @@ impl Builder {
 
                 body.push(Chunk::Custom(trampoline.ret.trampoline_to_glib(env)));
             }
+            if let Some(policy) = env.config.trampoline_panic {
+                let on_panic = crate::codegen::trampoline::on_panic(
+                    env,
+                    policy,
+                    &trampoline.name,
+                    trampoline.ret.c_type != "void",
+                );
+                body = vec![Chunk::CatchUnwind { body, on_panic }];
+            }
         }
 
         let extern_func = Chunk::ExternCFunc {
@@ -772,7 +874,7 @@ impl Builder {
                 );
                 let value = self.generate_initialized_value(&out.lib_par.name, &uninitialized_vars);
                 if let OutMemMode::UninitializedNamed(_) = mem_mode {
-                    value
+                    caller_allocated_out(value, out.lib_par.nullable)
                 } else {
                     let array_length_name = self.array_length(out).cloned();
                     self.remove_extra_assume_init(&array_length_name, &mut uninitialized_vars);
@@ -896,6 +998,11 @@ impl Builder {
             func_name: "callback".to_string(),
             arguments: vec![Chunk::Name("result".to_string())],
         });
+        if let Some(policy) = env.config.trampoline_panic {
+            let on_panic =
+                crate::codegen::trampoline::on_panic(env, policy, &trampoline.name, false);
+            body = vec![Chunk::CatchUnwind { body, on_panic }];
+        }
 
         let parameters = vec![
             Param {
@@ -950,6 +1057,46 @@ impl Builder {
         }
     }
 
+    fn add_trace(&self, chunks: &mut Vec<Chunk>) {
+        let feature = match self.trace_feature {
+            Some(ref feature) => feature,
+            None => return,
+        };
+        let c_name = self.c_name();
+        // Only directly passed values are known to implement `Debug`, everything
+        // else is logged by name only.
+        let mut format_args = Vec::new();
+        let mut values = String::new();
+        for trans in &self.transformations {
+            match trans.transformation_type {
+                TransformationType::ToGlibDirect { ref name, .. } => {
+                    format_args.push(format!("{name}={{:?}}"));
+                    values.push_str(&format!(", {name}"));
+                }
+                TransformationType::ToGlibScalar { ref name, .. }
+                | TransformationType::ToGlibInOutScalar { ref name }
+                | TransformationType::ToGlibArray { ref name, .. }
+                | TransformationType::ToGlibPointer { ref name, .. }
+                | TransformationType::ToGlibUnknown { ref name } => {
+                    format_args.push(name.clone());
+                }
+                _ => (),
+            }
+        }
+        chunks.push(Chunk::Custom(format!("#[cfg(feature = \"{feature}\")]")));
+        chunks.push(Chunk::Custom(format!(
+            "log::trace!(\"{c_name}({})\"{values});",
+            format_args.join(", ")
+        )));
+    }
+
+    fn c_name(&self) -> &str {
+        self.glib_name
+            .rsplit("::")
+            .next()
+            .unwrap_or(&self.glib_name)
+    }
+
     fn add_in_array_lengths(&self, chunks: &mut Vec<Chunk>) {
         for trans in &self.transformations {
             if let TransformationType::Length {
@@ -971,11 +1118,78 @@ impl Builder {
         }
     }
 
+    fn add_inout_scalars(&self, body: &mut Vec<Chunk>) {
+        for trans in &self.transformations {
+            if let TransformationType::ToGlibInOutScalar { ref name } = trans.transformation_type {
+                body.push(Chunk::Let {
+                    name: format!("{name}_"),
+                    is_mut: true,
+                    value: Box::new(Chunk::Custom(format!("(*{name}).into_glib()"))),
+                    type_: None,
+                });
+            }
+        }
+    }
+
+    /// Copies the slices passed as `GArray` into temporary arrays.
+    fn add_in_garrays(&self, body: &mut Vec<Chunk>, glib_type: &dyn Fn(&str) -> String) {
+        for trans in &self.transformations {
+            if let TransformationType::ToGlibArray {
+                ref name,
+                ref element_type,
+                ..
+            } = trans.transformation_type
+            {
+                body.push(Chunk::Let {
+                    name: format!("{name}_"),
+                    is_mut: false,
+                    value: Box::new(Chunk::Custom(format!(
+                        "{}({false_}, {false_}, std::mem::size_of::<{element_type}>() as _, \
+                         {name}.len() as _)",
+                        glib_type("ffi::g_array_sized_new"),
+                        false_ = glib_type("ffi::GFALSE"),
+                    ))),
+                    type_: None,
+                });
+                body.push(Chunk::Custom(format!(
+                    "{}({name}_, {name}.as_ptr() as *const _, {name}.len() as _);",
+                    glib_type("ffi::g_array_append_vals"),
+                )));
+            }
+        }
+    }
+
+    fn generate_inout_write_backs(&self, glib_type: &dyn Fn(&str) -> String) -> Vec<Chunk> {
+        self.transformations
+            .iter()
+            .filter_map(|trans| match trans.transformation_type {
+                TransformationType::ToGlibInOutScalar { ref name } => {
+                    Some(Chunk::Custom(format!("*{name} = from_glib({name}_);")))
+                }
+                TransformationType::ToGlibArray {
+                    ref name,
+                    transfer: library::Transfer::None,
+                    ..
+                } => Some(Chunk::Custom(format!(
+                    "{}({name}_);",
+                    glib_type("ffi::g_array_unref")
+                ))),
+                _ => None,
+            })
+            .collect()
+    }
+
     fn generate_call(&self, calls: &FuncParameters<'_>) -> Chunk {
         let params = self.generate_func_parameters(calls);
-        Chunk::FfiCall {
+        let call = Chunk::FfiCall {
             name: self.glib_name.clone(),
             params,
+        };
+        match self.trace_feature {
+            Some(ref feature) if self.ret.ret.parameter.is_some() => {
+                trace_return(feature, self.c_name(), call)
+            }
+            _ => call,
         }
     }
     fn generate_call_conversion(
@@ -1020,6 +1234,7 @@ impl Builder {
                         } else {
                             format!("Box_::into_raw(super_callback{pos}) as *mut _")
                         },
+                        pointer_cast: String::new(),
                     },
                 },
             ));
@@ -1030,6 +1245,7 @@ impl Builder {
                 Chunk::FfiCallParameter {
                     transformation_type: TransformationType::ToGlibDirect {
                         name: format!("destroy_call{}", destroy.destroy_index),
+                        pointer_cast: String::new(),
                     },
                 },
             ));
@@ -1140,7 +1356,7 @@ impl Builder {
     ) -> Chunk {
         let value = self.generate_initialized_value(&parameter.name, uninitialized_vars);
         if let OutMemMode::UninitializedNamed(_) = mem_mode {
-            value
+            caller_allocated_out(value, parameter.nullable)
         } else {
             let array_length_name = self.find_array_length_name(&parameter.name);
             self.remove_extra_assume_init(&array_length_name, uninitialized_vars);
@@ -1169,9 +1385,16 @@ impl Builder {
                     type_: Option::None,
                 };
                 let ret = ret.expect("No return in optional outs mode");
-                let ret = Chunk::OptionalReturn {
-                    condition: "ret".into(),
-                    value: Box::new(ret),
+                let ret = if self.ret.ret.bool_return_is_error.is_some() {
+                    Chunk::ResultMapReturn {
+                        result: "ret".into(),
+                        value: Box::new(ret),
+                    }
+                } else {
+                    Chunk::OptionalReturn {
+                        condition: "ret".into(),
+                        value: Box::new(ret),
+                    }
                 };
                 (call, Some(ret))
             }
@@ -1184,7 +1407,11 @@ impl Builder {
                 };
                 let mut ret = ret.expect("No return in combined outs mode");
                 if let Chunk::Tuple(ref mut vec, _) = ret {
-                    vec.insert(0, Chunk::Custom("ret".into()));
+                    if self.ret_last {
+                        vec.push(Chunk::Custom("ret".into()));
+                    } else {
+                        vec.insert(0, Chunk::Custom("ret".into()));
+                    }
                 }
                 (call, Some(ret))
             }
@@ -1225,7 +1452,11 @@ impl Builder {
                             array_length_name,
                             ret: ret_info,
                         };
-                        vec.insert(0, conv);
+                        if self.ret_last {
+                            vec.push(conv);
+                        } else {
+                            vec.insert(0, conv);
+                        }
                     }
                 } else {
                     panic!("Return is not Tuple")
@@ -1259,6 +1490,19 @@ impl Builder {
     }
 }
 
+/// Caller allocated outs are filled in place, they are only wrapped in `Some`
+/// to match the return type if configured as nullable.
+fn caller_allocated_out(value: Chunk, nullable: library::Nullable) -> Chunk {
+    if *nullable {
+        Chunk::Call {
+            func_name: "Some".into(),
+            arguments: vec![value],
+        }
+    } else {
+        value
+    }
+}
+
 fn c_type_mem_mode_lib(
     env: &Env,
     typ: library::TypeId,
@@ -1397,3 +1641,161 @@ fn add_chunk_for_type(
         }
     }
 }
+
+/// Wraps the FFI call in a block logging the raw returned value, which is a
+/// pointer or a `Debug` sys type, before it's converted.
+fn trace_return(feature: &str, c_name: &str, call: Chunk) -> Chunk {
+    Chunk::Chunks(vec![
+        Chunk::Custom("{ #[allow(clippy::let_and_return)] let ret = ".into()),
+        call,
+        Chunk::Custom(format!(
+            "; #[cfg(feature = \"{feature}\")] \
+             log::trace!(\"{c_name} returned {{:?}}\", ret); ret }}"
+        )),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(chunk: &Chunk) -> &str {
+        match chunk {
+            Chunk::Custom(s) => s,
+            _ => panic!("not a custom chunk: {chunk:?}"),
+        }
+    }
+
+    #[test]
+    fn test_trace_arguments() {
+        let mut builder = Builder::new();
+        builder
+            .glib_name("ffi::gtk_widget_set_opacity")
+            .trace_feature(Some("trace"))
+            .transformations(&[
+                Transformation {
+                    ind_c: 0,
+                    ind_rust: Some(0),
+                    transformation_type: TransformationType::ToGlibUnknown {
+                        name: "self".into(),
+                    },
+                },
+                Transformation {
+                    ind_c: 1,
+                    ind_rust: Some(1),
+                    transformation_type: TransformationType::ToGlibDirect {
+                        name: "opacity".into(),
+                        pointer_cast: String::new(),
+                    },
+                },
+                Transformation {
+                    ind_c: 2,
+                    ind_rust: Some(2),
+                    transformation_type: TransformationType::ToGlibDirect {
+                        name: "handle".into(),
+                        pointer_cast: " as *mut _".into(),
+                    },
+                },
+            ]);
+        let mut chunks = Vec::new();
+        builder.add_trace(&mut chunks);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(custom(&chunks[0]), "#[cfg(feature = \"trace\")]");
+        assert_eq!(
+            custom(&chunks[1]),
+            "log::trace!(\"gtk_widget_set_opacity(self, opacity={:?}, handle={:?})\", opacity, \
+             handle);"
+        );
+    }
+
+    #[test]
+    fn test_in_garrays() {
+        let array = |transfer| Transformation {
+            ind_c: 0,
+            ind_rust: Some(0),
+            transformation_type: TransformationType::ToGlibArray {
+                name: "values".into(),
+                element_type: "i32".into(),
+                transfer,
+            },
+        };
+        let glib_type = |name: &str| format!("glib::{name}");
+        let mut builder = Builder::new();
+        builder.transformations(&[array(library::Transfer::None)]);
+        let mut body = Vec::new();
+        builder.add_in_garrays(&mut body, &glib_type);
+        assert_eq!(body.len(), 2);
+        match &body[0] {
+            Chunk::Let { name, value, .. } => {
+                assert_eq!(name, "values_");
+                assert_eq!(
+                    custom(value),
+                    "glib::ffi::g_array_sized_new(glib::ffi::GFALSE, glib::ffi::GFALSE, \
+                     std::mem::size_of::<i32>() as _, values.len() as _)"
+                );
+            }
+            chunk => panic!("not a let: {chunk:?}"),
+        }
+        assert_eq!(
+            custom(&body[1]),
+            "glib::ffi::g_array_append_vals(values_, values.as_ptr() as *const _, \
+             values.len() as _);"
+        );
+        let write_backs = builder.generate_inout_write_backs(&glib_type);
+        assert_eq!(write_backs.len(), 1);
+        assert_eq!(
+            custom(&write_backs[0]),
+            "glib::ffi::g_array_unref(values_);"
+        );
+
+        // The callee takes over the array
+        builder.transformations(&[array(library::Transfer::Full)]);
+        assert!(builder.generate_inout_write_backs(&glib_type).is_empty());
+    }
+
+    #[test]
+    fn test_nullable_caller_allocated_out() {
+        let builder = Builder::new();
+        let mut parameter = parameter_ffi_call_out::Parameter {
+            name: "iter".into(),
+            typ: TypeId::tid_none(),
+            transfer: library::Transfer::None,
+            caller_allocates: true,
+            nullable: library::Nullable(false),
+            is_error: false,
+            is_uninitialized: false,
+            try_from_glib: Default::default(),
+        };
+        let mem_mode = OutMemMode::UninitializedNamed("TreeIter".into());
+        let chunk = builder.out_parameter_to_return(&parameter, &mem_mode, &mut Vec::new());
+        assert_eq!(custom(&chunk), "iter");
+
+        parameter.nullable = library::Nullable(true);
+        match builder.out_parameter_to_return(&parameter, &mem_mode, &mut Vec::new()) {
+            Chunk::Call {
+                func_name,
+                arguments,
+            } => {
+                assert_eq!(func_name, "Some");
+                assert_eq!(custom(&arguments[0]), "iter");
+            }
+            chunk => panic!("not wrapped: {chunk:?}"),
+        }
+    }
+
+    #[test]
+    fn test_trace_return() {
+        let call = Chunk::Custom("ffi::gtk_label_get_text(self)".into());
+        let chunks = match trace_return("trace", "gtk_label_get_text", call) {
+            Chunk::Chunks(chunks) => chunks,
+            chunk => panic!("not wrapped: {chunk:?}"),
+        };
+        let code = chunks.iter().map(custom).collect::<String>();
+        assert_eq!(
+            code,
+            "{ #[allow(clippy::let_and_return)] let ret = ffi::gtk_label_get_text(self); \
+             #[cfg(feature = \"trace\")] log::trace!(\"gtk_label_get_text returned {:?}\", ret); \
+             ret }"
+        );
+    }
+}
@@ -5,6 +5,8 @@ use std::{
     ops::Index,
 };
 
+use log::warn;
+
 use super::Visibility;
 use crate::{
     analysis::{
@@ -14,7 +16,7 @@ use crate::{
         namespaces,
         special_functions::TraitInfo,
     },
-    config::{derives::Derive, Config},
+    config::{derives::Derive, gobjects::GObject, wrappers::CustomWrapper, Config},
     env::Env,
     gir_version::VERSION,
     library::TypeId,
@@ -79,6 +81,18 @@ pub fn single_version_file(w: &mut dyn Write, conf: &Config, prefix: &str) -> Re
     )
 }
 
+/// Writes code given in the configuration verbatim, delimited by comments so it
+/// can be told apart from the generated code.
+pub fn custom_code(w: &mut dyn Write, code: &Option<String>, position: &str) -> Result<()> {
+    if let Some(code) = code {
+        writeln!(w)?;
+        writeln!(w, "// Start of custom {position} from the configuration")?;
+        writeln!(w, "{}", code.trim_end())?;
+        writeln!(w, "// End of custom {position}")?;
+    }
+    Ok(())
+}
+
 pub fn uses(
     w: &mut dyn Write,
     env: &Env,
@@ -160,11 +174,15 @@ pub fn define_fundamental_type(
     ref_func: Option<&str>,
     unref_func: Option<&str>,
     parents: &[StatusedTypeId],
+    must_use: bool,
     visibility: Visibility,
 ) -> Result<()> {
     let sys_crate_name = env.main_sys_crate_name();
     writeln!(w, "{} {{", use_glib_type(env, "wrapper!"))?;
     doc_alias(w, glib_name, "", 1)?;
+    if must_use {
+        writeln!(w, "\t#[must_use]")?;
+    }
     external_doc_link(
         w,
         env.config.external_docs_url.as_deref(),
@@ -247,6 +265,8 @@ pub fn define_object_type(
     glib_func_name: &str,
     is_interface: bool,
     parents: &[StatusedTypeId],
+    configured_prerequisites: &[String],
+    must_use: bool,
     visibility: Visibility,
 ) -> Result<()> {
     let sys_crate_name = env.main_sys_crate_name();
@@ -268,6 +288,9 @@ pub fn define_object_type(
 
     writeln!(w, "{} {{", use_glib_type(env, "wrapper!"))?;
     doc_alias(w, glib_name, "", 1)?;
+    if must_use {
+        writeln!(w, "\t#[must_use]")?;
+    }
     external_doc_link(
         w,
         env.config.external_docs_url.as_deref(),
@@ -275,7 +298,15 @@ pub fn define_object_type(
         &visibility,
         1,
     )?;
-    if parents.is_empty() {
+    if is_interface && !configured_prerequisites.is_empty() {
+        // Used as is, e.g. if some of the prerequisites are ignored or
+        // implemented manually in another crate
+        writeln!(
+            w,
+            "\t{visibility} struct {type_name}(Interface<{sys_crate_name}::{glib_name}{class_name}>) @requires {};",
+            configured_prerequisites.join(", ")
+        )?;
+    } else if parents.is_empty() {
         writeln!(
             w,
             "\t{visibility} struct {type_name}({kind_name}<{sys_crate_name}::{glib_name}{class_name}>);"
@@ -360,12 +391,16 @@ fn define_boxed_type_internal(
     clear_function_expression: &Option<String>,
     get_type_fn: Option<&str>,
     derive: &[Derive],
+    must_use: bool,
     visibility: Visibility,
 ) -> Result<()> {
     let sys_crate_name = env.main_sys_crate_name();
     writeln!(w, "{} {{", use_glib_type(env, "wrapper!"))?;
 
     derives(w, derive, 1)?;
+    if must_use {
+        writeln!(w, "\t#[must_use]")?;
+    }
     writeln!(
         w,
         "\t{} struct {}(Boxed{}<{}::{}>);",
@@ -425,6 +460,7 @@ pub fn define_boxed_type(
     clear_function_expression: &Option<String>,
     get_type_fn: Option<(String, Option<Version>)>,
     derive: &[Derive],
+    must_use: bool,
     visibility: Visibility,
 ) -> Result<()> {
     writeln!(w)?;
@@ -445,6 +481,7 @@ pub fn define_boxed_type(
                 clear_function_expression,
                 Some(get_type_fn),
                 derive,
+                must_use,
                 visibility,
             )?;
 
@@ -463,6 +500,7 @@ pub fn define_boxed_type(
                 clear_function_expression,
                 None,
                 derive,
+                must_use,
                 visibility,
             )?;
         } else {
@@ -479,6 +517,7 @@ pub fn define_boxed_type(
                 clear_function_expression,
                 Some(get_type_fn),
                 derive,
+                must_use,
                 visibility,
             )?;
         }
@@ -496,6 +535,7 @@ pub fn define_boxed_type(
             clear_function_expression,
             None,
             derive,
+            must_use,
             visibility,
         )?;
     }
@@ -514,12 +554,16 @@ pub fn define_auto_boxed_type(
     clear_function_expression: &Option<String>,
     get_type_fn: &str,
     derive: &[Derive],
+    must_use: bool,
     visibility: Visibility,
 ) -> Result<()> {
     let sys_crate_name = env.main_sys_crate_name();
     writeln!(w)?;
     writeln!(w, "{} {{", use_glib_type(env, "wrapper!"))?;
     derives(w, derive, 1)?;
+    if must_use {
+        writeln!(w, "\t#[must_use]")?;
+    }
     writeln!(
         w,
         "\t{} struct {}(Boxed{}<{}::{}>);",
@@ -569,6 +613,54 @@ pub fn define_auto_boxed_type(
     Ok(())
 }
 
+/// Defines an inline-allocated boxed type without GType, copy and free
+/// functions, which is copied and cleared with the given expressions or
+/// bitwise
+pub fn define_plain_boxed_inline_type(
+    w: &mut dyn Write,
+    env: &Env,
+    type_name: &str,
+    glib_name: &str,
+    init_function_expression: &Option<String>,
+    copy_into_function_expression: &Option<String>,
+    clear_function_expression: &Option<String>,
+    derive: &[Derive],
+    must_use: bool,
+    visibility: Visibility,
+) -> Result<()> {
+    let sys_crate_name = env.main_sys_crate_name();
+    writeln!(w)?;
+    writeln!(w, "{} {{", use_glib_type(env, "wrapper!"))?;
+    derives(w, derive, 1)?;
+    if must_use {
+        writeln!(w, "\t#[must_use]")?;
+    }
+    writeln!(
+        w,
+        "\t{visibility} struct {type_name}(BoxedInline<{sys_crate_name}::{glib_name}>);"
+    )?;
+
+    if let (
+        Some(init_function_expression),
+        Some(copy_into_function_expression),
+        Some(clear_function_expression),
+    ) = (
+        init_function_expression,
+        copy_into_function_expression,
+        clear_function_expression,
+    ) {
+        writeln!(w)?;
+        writeln!(w, "\tmatch fn {{")?;
+        writeln!(w, "\t\tinit => {init_function_expression},",)?;
+        writeln!(w, "\t\tcopy_into => {copy_into_function_expression},",)?;
+        writeln!(w, "\t\tclear => {clear_function_expression},",)?;
+        writeln!(w, "\t}}")?;
+    }
+    writeln!(w, "}}")?;
+
+    Ok(())
+}
+
 fn define_shared_type_internal(
     w: &mut dyn Write,
     env: &Env,
@@ -578,11 +670,15 @@ fn define_shared_type_internal(
     unref_fn: &str,
     get_type_fn: Option<&str>,
     derive: &[Derive],
+    must_use: bool,
     visibility: Visibility,
 ) -> Result<()> {
     let sys_crate_name = env.main_sys_crate_name();
     writeln!(w, "{} {{", use_glib_type(env, "wrapper!"))?;
     derives(w, derive, 1)?;
+    if must_use {
+        writeln!(w, "\t#[must_use]")?;
+    }
     writeln!(
         w,
         "\t{visibility} struct {type_name}(Shared<{sys_crate_name}::{glib_name}>);"
@@ -600,6 +696,19 @@ fn define_shared_type_internal(
     Ok(())
 }
 
+pub fn define_custom_type(
+    w: &mut dyn Write,
+    wrapper: &CustomWrapper,
+    replacements: &[(&str, &str)],
+) -> Result<()> {
+    writeln!(w)?;
+    writeln!(w, "{} {{", wrapper.macro_name)?;
+    writeln!(w, "\t{}", wrapper.expand(replacements))?;
+    writeln!(w, "}}")?;
+
+    Ok(())
+}
+
 pub fn define_shared_type(
     w: &mut dyn Write,
     env: &Env,
@@ -609,6 +718,7 @@ pub fn define_shared_type(
     unref_fn: &str,
     get_type_fn: Option<(String, Option<Version>)>,
     derive: &[Derive],
+    must_use: bool,
     visibility: Visibility,
 ) -> Result<()> {
     writeln!(w)?;
@@ -625,13 +735,14 @@ pub fn define_shared_type(
                 unref_fn,
                 Some(get_type_fn),
                 derive,
+                must_use,
                 visibility,
             )?;
 
             writeln!(w)?;
             not_version_condition_no_docsrs(w, env, None, get_type_version, false, 0)?;
             define_shared_type_internal(
-                w, env, type_name, glib_name, ref_fn, unref_fn, None, derive, visibility,
+                w, env, type_name, glib_name, ref_fn, unref_fn, None, derive, must_use, visibility,
             )?;
         } else {
             define_shared_type_internal(
@@ -643,12 +754,13 @@ pub fn define_shared_type(
                 unref_fn,
                 Some(get_type_fn),
                 derive,
+                must_use,
                 visibility,
             )?;
         }
     } else {
         define_shared_type_internal(
-            w, env, type_name, glib_name, ref_fn, unref_fn, None, derive, visibility,
+            w, env, type_name, glib_name, ref_fn, unref_fn, None, derive, must_use, visibility,
         )?;
     }
 
@@ -795,14 +907,22 @@ pub fn not_version_condition(
     commented: bool,
     indent: usize,
 ) -> Result<()> {
-    if let Some(s) = version.and_then(|v| {
-        cfg_condition_string(Some(&format!("not({})", v.to_cfg(None))), commented, indent)
-    }) {
+    if let Some(s) = not_version_condition_string(version, commented, indent) {
         writeln!(w, "{s}")?;
     }
     Ok(())
 }
 
+pub fn not_version_condition_string(
+    version: Option<Version>,
+    commented: bool,
+    indent: usize,
+) -> Option<String> {
+    version.and_then(|v| {
+        cfg_condition_string(Some(&format!("not({})", v.to_cfg(None))), commented, indent)
+    })
+}
+
 pub fn not_version_condition_no_docsrs(
     w: &mut dyn Write,
     env: &Env,
@@ -894,6 +1014,18 @@ pub fn cfg_condition_string_doc(
     })
 }
 
+/// Returns the `cfg_condition` of a type combined with the condition hiding it
+/// from its `version_end` on
+pub fn object_cfg_condition(config: &GObject) -> Option<String> {
+    let not_version = config
+        .version_end
+        .map(|v| format!("not({})", v.to_cfg(None)));
+    match (config.cfg_condition.as_ref(), not_version) {
+        (Some(cfg), Some(not_version)) => Some(format!("all({cfg}, {not_version})")),
+        (cfg, not_version) => cfg.cloned().or(not_version),
+    }
+}
+
 pub fn cfg_condition_string(
     cfg_condition: Option<&(impl Display + ?Sized)>,
     commented: bool,
@@ -994,49 +1126,99 @@ pub fn write_vec<T: Display>(w: &mut dyn Write, v: &[T]) -> Result<()> {
     Ok(())
 }
 
+/// Implements `Default` by calling `new`, or the configured
+/// `default_constructor`, if it has no parameters and can't return `NULL`.
 pub fn declare_default_from_new(
     w: &mut dyn Write,
     env: &Env,
     name: &str,
+    full_name: &str,
     functions: &[analysis::functions::Info],
     has_builder: bool,
 ) -> Result<()> {
-    if let Some(func) = functions.iter().find(|f| {
+    let config = env.config.objects.get(full_name);
+    if config.map_or(false, |c| !c.generate_default) {
+        return Ok(());
+    }
+
+    let constructor = config.and_then(|c| c.default_constructor.as_deref());
+    let func = functions.iter().find(|f| {
         !f.hidden
             && f.status.need_generate()
-            && f.name == "new"
-            // Cannot generate Default implementation for Option<>
-            && f.ret.parameter.as_ref().map_or(false, |x| !*x.lib_par.nullable)
-    }) {
-        if func.parameters.rust_parameters.is_empty() {
-            writeln!(w)?;
-            version_condition(w, env, None, func.version, false, 0)?;
-            writeln!(
-                w,
-                "impl Default for {name} {{
-                     fn default() -> Self {{
-                         Self::new()
-                     }}
-                 }}"
-            )?;
-        } else if has_builder {
-            // create an alternative default implementation the uses `glib::object::Object::new()`
+            && constructor.map_or(f.name == "new", |c| f.codegen_name() == c)
+    });
+    let func = match func {
+        Some(func) => func,
+        None => {
+            if let Some(constructor) = constructor {
+                warn!("Default constructor `{constructor}` of {full_name} isn't generated");
+            }
+            return Ok(());
+        }
+    };
+    // Cannot generate Default implementation for Option<>
+    if !func.ret.is_non_nullable() {
+        if let Some(constructor) = constructor {
+            warn!(
+                "Default constructor `{constructor}` of {full_name} can return NULL, \
+                 no Default implemented"
+            );
+        }
+        return Ok(());
+    }
+
+    match default_call(
+        constructor.unwrap_or("new"),
+        !func.parameters.rust_parameters.is_empty(),
+        has_builder,
+    ) {
+        Some(call) => {
             writeln!(w)?;
             version_condition(w, env, None, func.version, false, 0)?;
-            writeln!(
-                w,
-                "impl Default for {name} {{
-                     fn default() -> Self {{
-                         glib::object::Object::new::<Self>()
-                     }}
-                 }}"
-            )?;
+            generate_default_impl(w, name, func.cfg_condition.as_ref(), &call)?;
+        }
+        None => {
+            if let Some(constructor) = constructor {
+                warn!(
+                    "Default constructor `{constructor}` of {full_name} has parameters, \
+                     no Default implemented"
+                );
+            }
         }
     }
 
     Ok(())
 }
 
+/// Returns the call that creates the default value with `constructor`.
+///
+/// A constructor taking parameters can only be replaced with
+/// `glib::object::Object::new()` when the type has a builder.
+fn default_call(constructor: &str, has_parameters: bool, has_builder: bool) -> Option<String> {
+    if !has_parameters {
+        Some(format!("Self::{constructor}()"))
+    } else if has_builder {
+        // create an alternative default implementation the uses `glib::object::Object::new()`
+        Some("glib::object::Object::new::<Self>()".to_owned())
+    } else {
+        None
+    }
+}
+
+fn generate_default_impl(
+    w: &mut dyn Write,
+    name: &str,
+    cfg: Option<&String>,
+    call: &str,
+) -> Result<()> {
+    cfg_condition(w, cfg, false, 0)?;
+    writeln!(w, "impl Default for {name} {{")?;
+    writeln!(w, "\tfn default() -> Self {{")?;
+    writeln!(w, "\t\t{call}")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")
+}
+
 /// Escapes string in format suitable for placing inside double quotes.
 pub fn escape_string(s: &str) -> String {
     let mut es = String::with_capacity(s.len() * 2);
@@ -1050,6 +1232,41 @@ pub fn escape_string(s: &str) -> String {
     es
 }
 
+/// Rust version that stabilized inline format arguments (`{self:p}`).
+pub const INLINE_FORMAT_ARGS: Version = Version(1, 58, 0);
+
+/// Returns the arguments of a formatting macro for `format`, which names the
+/// formatted variables inline. Unless `inline` is set, the names are moved
+/// after the format string for compilers older than `INLINE_FORMAT_ARGS`.
+pub fn format_args(format: &str, inline: bool) -> String {
+    if inline {
+        return format!("\"{format}\"");
+    }
+    let mut string = String::with_capacity(format.len());
+    let mut args = String::new();
+    let mut rest = format;
+    while let Some(start) = rest.find('{') {
+        string.push_str(&rest[..=start]);
+        rest = &rest[start + 1..];
+        if let Some(escaped) = rest.strip_prefix('{') {
+            string.push('{');
+            rest = escaped;
+            continue;
+        }
+        let end = rest.find('}').expect("Unterminated format argument");
+        let (name, spec) = rest[..end].split_at(rest[..end].find(':').unwrap_or(end));
+        string.push_str(spec);
+        string.push('}');
+        if !name.is_empty() {
+            args.push_str(", ");
+            args.push_str(name);
+        }
+        rest = &rest[end + 1..];
+    }
+    string.push_str(rest);
+    format!("\"{string}\"{args}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1060,4 +1277,56 @@ mod tests {
         assert_eq!(escape_string("no escaping here"), "no escaping here");
         assert_eq!(escape_string(r#"'"\"#), r#"'\"\\"#);
     }
+
+    #[test]
+    fn test_format_args() {
+        assert_eq!(
+            format_args("GList @ {self:p}", true),
+            r#""GList @ {self:p}""#
+        );
+        assert_eq!(
+            format_args("GList @ {self:p}", false),
+            r#""GList @ {:p}", self"#
+        );
+        assert_eq!(
+            format_args("{bits:#x} and {{{n}}}", false),
+            r#""{:#x} and {{{}}}", bits, n"#
+        );
+        assert_eq!(format_args("no arguments", false), r#""no arguments""#);
+    }
+
+    #[test]
+    fn test_default_call() {
+        assert_eq!(
+            default_call("new", false, false).as_deref(),
+            Some("Self::new()")
+        );
+        assert_eq!(
+            default_call("with_defaults", false, true).as_deref(),
+            Some("Self::with_defaults()")
+        );
+        assert_eq!(
+            default_call("with_defaults", true, true).as_deref(),
+            Some("glib::object::Object::new::<Self>()")
+        );
+        assert_eq!(default_call("with_defaults", true, false), None);
+    }
+
+    #[test]
+    fn test_generate_default_impl() {
+        let mut w = Vec::new();
+        let cfg = "unix".to_owned();
+        generate_default_impl(&mut w, "Foo", Some(&cfg), "Self::with_defaults()").unwrap();
+        assert_eq!(
+            String::from_utf8(w).unwrap(),
+            "#[cfg(unix)]
+#[cfg_attr(docsrs, doc(cfg(unix)))]
+impl Default for Foo {
+\tfn default() -> Self {
+\t\tSelf::with_defaults()
+\t}
+}
+"
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fmt::Display,
     io::{Result, Write},
     path::Path,
@@ -20,6 +21,7 @@ mod child_properties;
 mod constants;
 mod doc;
 mod enums;
+mod feature_tests;
 mod flags;
 pub mod function;
 mod function_body_chunk;
@@ -58,18 +60,38 @@ pub fn generate(env: &Env) {
 
 fn normal_generate(env: &Env) {
     let mut mod_rs: Vec<String> = Vec::new();
+    let mut submodules: BTreeMap<String, Vec<String>> = BTreeMap::new();
     let mut traits: Vec<String> = Vec::new();
     let mut builders: Vec<String> = Vec::new();
     let root_path = env.config.auto_path.as_path();
 
     generate_single_version_file(env);
-    objects::generate(env, root_path, &mut mod_rs, &mut traits, &mut builders);
-    records::generate(env, root_path, &mut mod_rs);
+    objects::generate(
+        env,
+        root_path,
+        &mut mod_rs,
+        &mut submodules,
+        &mut traits,
+        &mut builders,
+    );
+    records::generate(env, root_path, &mut mod_rs, &mut submodules);
     enums::generate(env, root_path, &mut mod_rs);
     flags::generate(env, root_path, &mut mod_rs);
     alias::generate(env, root_path, &mut mod_rs);
     functions::generate(env, root_path, &mut mod_rs);
     constants::generate(env, root_path, &mut mod_rs);
+    if env.config.generate_tests {
+        feature_tests::generate(env, root_path, &mut mod_rs);
+    }
+
+    for (module_path, contents) in &submodules {
+        let path = root_path.join(module_path).join("mod.rs");
+        save_to_file(path, env.config.make_backup, |w| {
+            general::start_comments(w, &env.config)?;
+            general::write_vec(w, contents)
+        });
+        mod_rs.push(format!("\npub mod {module_path};"));
+    }
 
     generate_mod_rs(env, root_path, &mod_rs, &traits, &builders);
 }
@@ -84,6 +106,10 @@ pub fn generate_mod_rs(
     let path = root_path.join("mod.rs");
     save_to_file(path, env.config.make_backup, |w| {
         general::start_comments(w, &env.config)?;
+        if let Some(ref sys_crate) = env.config.ffi_reexport {
+            writeln!(w)?;
+            writeln!(w, "pub use {sys_crate} as ffi;")?;
+        }
         general::write_vec(w, mod_rs)?;
         writeln!(w)?;
         if !traits.is_empty() {
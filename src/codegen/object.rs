@@ -6,8 +6,8 @@ use std::{
 use super::{
     child_properties, function, general,
     general::{
-        cfg_deprecated_string, not_version_condition_no_docsrs, version_condition,
-        version_condition_no_doc, version_condition_string,
+        cfg_deprecated_string, not_version_condition_no_docsrs, not_version_condition_string,
+        version_condition, version_condition_no_doc, version_condition_string,
     },
     properties, signal, trait_impls,
 };
@@ -39,6 +39,7 @@ pub fn generate(
     general::uses(w, env, &analysis.imports, analysis.version)?;
 
     let config = &env.config.objects[&analysis.full_name];
+    general::custom_code(w, &config.prologue, "prologue")?;
     if config.default_value.is_some() {
         log::error!(
             "`default_value` can only be used on flags and enums. {} is neither. Ignoring \
@@ -95,6 +96,7 @@ pub fn generate(
                 analysis.ref_fn.as_deref(),
                 analysis.unref_fn.as_deref(),
                 &analysis.supertypes,
+                config.must_use,
                 analysis.visibility,
             )?;
         } else {
@@ -107,6 +109,8 @@ pub fn generate(
                 &analysis.get_type,
                 analysis.is_interface,
                 &analysis.supertypes,
+                &config.prerequisites,
+                config.must_use,
                 analysis.visibility,
             )?;
         }
@@ -149,6 +153,8 @@ pub fn generate(
                     &analysis.get_type,
                     analysis.is_interface,
                     &supertypes,
+                    &config.prerequisites,
+                    config.must_use,
                     analysis.visibility,
                 )?;
 
@@ -179,6 +185,8 @@ pub fn generate(
             &analysis.get_type,
             analysis.is_interface,
             &supertypes,
+            &config.prerequisites,
+            config.must_use,
             analysis.visibility,
         )?;
     }
@@ -232,21 +240,24 @@ pub fn generate(
             )?;
         }
 
-        if !analysis.need_generate_trait() {
-            for func_analysis in &analysis.methods() {
-                function::generate(
-                    w,
-                    env,
-                    Some(analysis.type_id),
-                    func_analysis,
-                    Some(&analysis.specials),
-                    analysis.version,
-                    false,
-                    false,
-                    1,
-                )?;
+        for func_analysis in &analysis.methods() {
+            if analysis.need_generate_trait() && !func_analysis.inherent {
+                continue;
             }
+            function::generate(
+                w,
+                env,
+                Some(analysis.type_id),
+                func_analysis,
+                Some(&analysis.specials),
+                analysis.version,
+                false,
+                false,
+                1,
+            )?;
+        }
 
+        if !analysis.need_generate_trait() {
             for property in &analysis.properties {
                 properties::generate(w, env, property, false, false, 1)?;
             }
@@ -286,6 +297,7 @@ pub fn generate(
             w,
             env,
             &analysis.name,
+            &analysis.full_name,
             &analysis.functions,
             has_builder_properties(&analysis.builder_properties),
         )?;
@@ -344,7 +356,7 @@ pub fn generate(
         )?;
     }
 
-    Ok(())
+    general::custom_code(w, &config.epilogue, "epilogue")
 }
 
 fn generate_builder(w: &mut dyn Write, env: &Env, analysis: &analysis::object::Info) -> Result<()> {
@@ -444,8 +456,10 @@ fn generate_builder(w: &mut dyn Write, env: &Env, analysis: &analysis::object::I
             let deprecated_string =
                 cfg_deprecated_string(env, Some(*super_tid), property.deprecated_version, false, 1);
             let version_prefix = version_condition_string
+                .into_iter()
+                .chain(not_version_condition_string(property.not_version, false, 1))
                 .map(|version| format!("{comment_prefix}{version}\n"))
-                .unwrap_or_default();
+                .collect::<String>();
 
             let deprecation_prefix = deprecated_string
                 .map(|version| format!("{comment_prefix}{version}\n"))
@@ -484,13 +498,20 @@ fn generate_builder(w: &mut dyn Write, env: &Env, analysis: &analysis::object::I
 }
 
 fn generate_trait(w: &mut dyn Write, env: &Env, analysis: &analysis::object::Info) -> Result<()> {
+    if env.config.sealed_ext_traits {
+        generate_sealed(w, &analysis.name)?;
+    }
     write!(
         w,
-        "pub trait {}: IsA<{}> + 'static {{",
-        analysis.trait_name, analysis.name
+        "{}",
+        trait_declaration(
+            &analysis.trait_name,
+            &analysis.name,
+            env.config.sealed_ext_traits
+        )
     )?;
 
-    for func_analysis in &analysis.methods() {
+    for func_analysis in &analysis.trait_methods() {
         function::generate(
             w,
             env,
@@ -532,6 +553,7 @@ pub fn generate_reexports(
     env: &Env,
     analysis: &analysis::object::Info,
     module_name: &str,
+    module_path: Option<&str>,
     contents: &mut Vec<String>,
     traits: &mut Vec<String>,
     builders: &mut Vec<String>,
@@ -552,36 +574,150 @@ pub fn generate_reexports(
     ) {
         cfgs.push(cfg);
     }
+    if let Some(version_end) = env.config.objects[&analysis.full_name].version_end {
+        cfgs.push(format!("#[cfg(not({}))]", version_end.to_cfg(None)));
+    }
 
-    contents.push(String::new());
-    contents.extend_from_slice(&cfgs);
-    contents.push(format!("mod {module_name};"));
-    contents.extend_from_slice(&cfgs);
-
-    contents.push(format!(
-        "{} use self::{}::{};",
+    let mut reexports = Vec::new();
+    if analysis.need_generate_trait() {
+        reexports.push((traits, analysis.trait_name.clone()));
+    }
+    if has_builder_properties(&analysis.builder_properties) {
+        reexports.push((builders, format!("{}Builder", analysis.name)));
+    }
+    push_reexports(
+        &cfgs,
         analysis.visibility.export_visibility(),
+        &analysis.name,
         module_name,
-        analysis.name,
-    ));
+        module_path,
+        contents,
+        reexports,
+    );
+}
 
-    if analysis.need_generate_trait() {
-        for cfg in &cfgs {
-            traits.push(format!("\t{cfg}"));
+/// Adds the module of the type `name` and its re-export to `contents`, and
+/// the re-exports of its trait and builder to their lists in `reexports`.
+fn push_reexports(
+    cfgs: &[String],
+    visibility: &str,
+    name: &str,
+    module_name: &str,
+    module_path: Option<&str>,
+    contents: &mut Vec<String>,
+    reexports: Vec<(&mut Vec<String>, String)>,
+) {
+    contents.push(String::new());
+    contents.extend_from_slice(cfgs);
+    contents.push(format!("mod {module_name};"));
+    contents.extend_from_slice(cfgs);
+    contents.push(format!("{visibility} use self::{module_name}::{name};"));
+
+    // Types of a submodule are only reachable through its own re-exports
+    for (list, item) in reexports {
+        for cfg in cfgs {
+            list.push(format!("\t{cfg}"));
+        }
+        match module_path {
+            Some(module_path) => {
+                contents.extend_from_slice(cfgs);
+                contents.push(format!("pub use self::{module_name}::{item};"));
+                list.push(format!("\tpub use super::{module_path}::{item};"));
+            }
+            None => list.push(format!("\tpub use super::{module_name}::{item};")),
         }
-        traits.push(format!(
-            "\tpub use super::{}::{};",
-            module_name, analysis.trait_name
-        ));
     }
+}
 
-    if has_builder_properties(&analysis.builder_properties) {
-        for cfg in &cfgs {
-            builders.push(format!("\t{cfg}"));
-        }
-        builders.push(format!(
-            "\tpub use super::{}::{}Builder;",
-            module_name, analysis.name
-        ));
+/// Writes the private supertrait that keeps other crates from implementing
+/// the `Ext` trait of `name`.
+fn generate_sealed(w: &mut dyn Write, name: &str) -> Result<()> {
+    writeln!(w, "mod sealed {{")?;
+    writeln!(w, "\tpub trait Sealed {{}}")?;
+    writeln!(w, "\timpl<T: super::IsA<super::{name}>> Sealed for T {{}}")?;
+    writeln!(w, "}}")?;
+    writeln!(w)
+}
+
+fn trait_declaration(trait_name: &str, name: &str, sealed: bool) -> String {
+    let sealed = if sealed { " + sealed::Sealed" } else { "" };
+    format!("pub trait {trait_name}: IsA<{name}>{sealed} + 'static {{")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sealed_trait() {
+        let mut w = Vec::new();
+        generate_sealed(&mut w, "Widget").unwrap();
+        assert_eq!(
+            String::from_utf8(w).unwrap(),
+            "mod sealed {\n\tpub trait Sealed {}\n\timpl<T: super::IsA<super::Widget>> Sealed \
+             for T {}\n}\n\n"
+        );
+        assert_eq!(
+            trait_declaration("WidgetExt", "Widget", true),
+            "pub trait WidgetExt: IsA<Widget> + sealed::Sealed + 'static {"
+        );
+        assert_eq!(
+            trait_declaration("WidgetExt", "Widget", false),
+            "pub trait WidgetExt: IsA<Widget> + 'static {"
+        );
+    }
+
+    #[test]
+    fn test_push_reexports() {
+        let cfgs = vec!["#[cfg(feature = \"v3_20\")]".to_owned()];
+        let (mut contents, mut traits) = (Vec::new(), Vec::new());
+        push_reexports(
+            &cfgs,
+            "pub",
+            "AboutDialog",
+            "about_dialog",
+            Some("dialogs"),
+            &mut contents,
+            vec![(&mut traits, "AboutDialogExt".to_owned())],
+        );
+        assert_eq!(
+            contents,
+            [
+                "",
+                "#[cfg(feature = \"v3_20\")]",
+                "mod about_dialog;",
+                "#[cfg(feature = \"v3_20\")]",
+                "pub use self::about_dialog::AboutDialog;",
+                "#[cfg(feature = \"v3_20\")]",
+                "pub use self::about_dialog::AboutDialogExt;",
+            ]
+        );
+        assert_eq!(
+            traits,
+            [
+                "\t#[cfg(feature = \"v3_20\")]",
+                "\tpub use super::dialogs::AboutDialogExt;",
+            ]
+        );
+
+        let (mut contents, mut traits) = (Vec::new(), Vec::new());
+        push_reexports(
+            &[],
+            "pub(crate)",
+            "AboutDialog",
+            "about_dialog",
+            None,
+            &mut contents,
+            vec![(&mut traits, "AboutDialogExt".to_owned())],
+        );
+        assert_eq!(
+            contents,
+            [
+                "",
+                "mod about_dialog;",
+                "pub(crate) use self::about_dialog::AboutDialog;"
+            ]
+        );
+        assert_eq!(traits, ["\tpub use super::about_dialog::AboutDialogExt;"]);
     }
 }
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
 
 use log::info;
 
@@ -8,13 +8,16 @@ pub fn generate(
     env: &Env,
     root_path: &Path,
     mod_rs: &mut Vec<String>,
+    submodules: &mut BTreeMap<String, Vec<String>>,
     traits: &mut Vec<String>,
     builders: &mut Vec<String>,
 ) {
     info!("Generate objects");
     for class_analysis in env.analysis.objects.values() {
         let obj = &env.config.objects[&class_analysis.full_name];
-        if !obj.status.need_generate() {
+        if !obj.status.need_generate()
+            || matches!(obj.version_end, Some(v) if v <= env.config.min_cfg_version)
+        {
             continue;
         }
 
@@ -24,7 +27,11 @@ pub fn generate(
             .unwrap_or_else(|| module_name(split_namespace_name(&class_analysis.full_name).1));
 
         let generate_display_trait = obj.generate_display_trait;
-        let mut path = root_path.join(&mod_name);
+        let module_path = obj.module_path.as_deref();
+        let mut path = match module_path {
+            Some(module_path) => root_path.join(module_path).join(&mod_name),
+            None => root_path.join(&mod_name),
+        };
         path.set_extension("rs");
         info!("Generating file {:?}", path);
 
@@ -32,6 +39,18 @@ pub fn generate(
             super::object::generate(w, env, class_analysis, generate_display_trait)
         });
 
-        super::object::generate_reexports(env, class_analysis, &mod_name, mod_rs, traits, builders);
+        let contents = match module_path {
+            Some(module_path) => submodules.entry(module_path.to_owned()).or_default(),
+            None => &mut *mod_rs,
+        };
+        super::object::generate_reexports(
+            env,
+            class_analysis,
+            &mod_name,
+            module_path,
+            contents,
+            traits,
+            builders,
+        );
     }
 }
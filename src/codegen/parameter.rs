@@ -25,6 +25,7 @@ impl ToParameter for CParameter {
                 Some(bound) => {
                     bound.full_type_parameter_reference(ref_mode, self.nullable, r#async)
                 }
+                None if self.to_value => to_value_type(*self.nullable).to_owned(),
                 None => {
                     let type_name = RustType::builder(env, self.typ)
                         .direction(self.direction)
@@ -44,3 +45,23 @@ impl ToParameter for CParameter {
         }
     }
 }
+
+/// Type of a `GValue` parameter taken as `&dyn ToValue`
+fn to_value_type(nullable: bool) -> &'static str {
+    if nullable {
+        "Option<&dyn ToValue>"
+    } else {
+        "&dyn ToValue"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_value_type() {
+        assert_eq!(to_value_type(false), "&dyn ToValue");
+        assert_eq!(to_value_type(true), "Option<&dyn ToValue>");
+    }
+}
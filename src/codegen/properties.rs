@@ -1,7 +1,7 @@
 use std::io::{Result, Write};
 
 use super::{
-    general::{cfg_deprecated, doc_alias, version_condition},
+    general::{cfg_deprecated, doc_alias, not_version_condition, version_condition},
     property_body,
 };
 use crate::{
@@ -51,6 +51,7 @@ fn generate_prop_func(
         indent,
     )?;
     version_condition(w, env, None, prop.version, commented, indent)?;
+    not_version_condition(w, prop.not_version, commented, indent)?;
     let add_doc_alias = if let Some(func_name_alias) = prop.func_name_alias.as_ref() {
         &prop.name != func_name_alias && prop.name != prop.var_name
     } else {
@@ -122,7 +123,8 @@ fn body(env: &Env, prop: &Property, in_trait: bool) -> Chunk {
         .name(&prop.name)
         .in_trait(in_trait)
         .var_name(&prop.var_name)
-        .is_get(prop.is_get);
+        .is_get(prop.is_get)
+        .null_panic_message(prop.null_panic_message.as_deref());
 
     if let Ok(type_) = RustType::try_new(env, prop.typ) {
         builder.type_(type_.as_str());
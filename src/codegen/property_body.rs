@@ -1,5 +1,6 @@
 use crate::{
     chunk::Chunk,
+    codegen::general::escape_string,
     env::Env,
     nameutil::{use_glib_type, use_gtk_type},
 };
@@ -11,6 +12,7 @@ pub struct Builder<'a> {
     is_get: bool,
     is_child_property: bool,
     type_: String,
+    null_panic_message: Option<String>,
     env: &'a Env,
 }
 
@@ -24,6 +26,7 @@ impl<'a> Builder<'a> {
             is_get: Default::default(),
             is_child_property: Default::default(),
             type_: Default::default(),
+            null_panic_message: Default::default(),
         }
     }
 
@@ -36,6 +39,7 @@ impl<'a> Builder<'a> {
             var_name: Default::default(),
             is_get: Default::default(),
             type_: Default::default(),
+            null_panic_message: Default::default(),
         }
     }
 
@@ -64,6 +68,11 @@ impl<'a> Builder<'a> {
         self
     }
 
+    pub fn null_panic_message(&mut self, message: Option<&str>) -> &mut Self {
+        self.null_panic_message = message.map(ToOwned::to_owned);
+        self
+    }
+
     pub fn generate(&self) -> Chunk {
         let chunks = if self.is_get {
             self.chunks_for_get()
@@ -94,11 +103,12 @@ impl<'a> Builder<'a> {
                 "self"
             };
 
-            vec![Chunk::Custom(format!(
-                "{}::property({}, \"{}\")",
-                use_glib_type(self.env, "ObjectExt"),
+            vec![Chunk::Custom(property_getter(
+                &use_glib_type(self.env, "ObjectExt"),
                 self_,
-                self.name
+                &self.name,
+                &self.type_,
+                self.null_panic_message.as_deref(),
             ))]
         }
     }
@@ -135,3 +145,44 @@ impl<'a> Builder<'a> {
         }
     }
 }
+
+fn property_getter(
+    object_ext: &str,
+    self_: &str,
+    name: &str,
+    type_: &str,
+    null_panic_message: Option<&str>,
+) -> String {
+    match null_panic_message {
+        // Read as `Option<T>` so that NULL panics with the message
+        Some(message) => format!(
+            "{object_ext}::property::<Option<{type_}>>({self_}, \"{name}\").expect(\"{}\")",
+            escape_string(message)
+        ),
+        None => format!("{object_ext}::property({self_}, \"{name}\")"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_property_getter() {
+        assert_eq!(
+            property_getter("glib::ObjectExt", "self", "label", "glib::GString", None),
+            r#"glib::ObjectExt::property(self, "label")"#
+        );
+        assert_eq!(
+            property_getter(
+                "glib::ObjectExt",
+                "self.as_ref()",
+                "label",
+                "glib::GString",
+                Some(r#"Property "label" is NULL"#),
+            ),
+            "glib::ObjectExt::property::<Option<glib::GString>>(self.as_ref(), \"label\")\
+             .expect(\"Property \\\"label\\\" is NULL\")"
+        );
+    }
+}
@@ -5,16 +5,67 @@ use crate::{
     analysis::{self, record_type::RecordType, special_functions::Type},
     env::Env,
     library,
+    nameutil::use_glib_if_needed,
     traits::MaybeRef,
+    version::Version,
 };
 
 pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info) -> Result<()> {
     let type_ = analysis.type_(&env.library);
 
+    let config = &env.config.objects[&analysis.full_name];
+
     general::start_comments(w, &env.config)?;
     general::uses(w, env, &analysis.imports, type_.version)?;
+    general::custom_code(w, &config.prologue, "prologue")?;
+
+    let use_boxed_functions = use_boxed_functions(
+        config.use_boxed_functions,
+        analysis.glib_get_type.is_some(),
+        analysis.specials.has_trait(Type::Copy) && analysis.specials.has_trait(Type::Free),
+        &analysis.name,
+    );
 
-    if RecordType::of(env.type_(analysis.type_id).maybe_ref().unwrap()) == RecordType::AutoBoxed {
+    if let Some(ref fields) = analysis.plain_fields {
+        generate_plain_struct(w, env, analysis, &type_.c_type, fields)?;
+    } else if let Some(wrapper) = config
+        .wrapper
+        .as_ref()
+        .and_then(|kind| env.config.wrappers.get(kind))
+    {
+        let special_name = |ty: Type| {
+            analysis
+                .specials
+                .traits()
+                .get(&ty)
+                .map_or("", |f| f.glib_name.as_str())
+        };
+        let get_type = analysis
+            .glib_get_type
+            .as_ref()
+            .map_or("", |(f, _)| f.as_str());
+        general::define_custom_type(
+            w,
+            wrapper,
+            &[
+                ("name", analysis.name.as_str()),
+                ("c_type", type_.c_type.as_str()),
+                ("get_type", get_type),
+                ("ref", special_name(Type::Ref)),
+                ("unref", special_name(Type::Unref)),
+                ("copy", special_name(Type::Copy)),
+                ("free", special_name(Type::Free)),
+                ("visibility", analysis.visibility.export_visibility()),
+            ],
+        )?;
+    } else if use_boxed_functions == Some(true)
+        || (use_boxed_functions.is_none()
+            && RecordType::of(env.type_(analysis.type_id).maybe_ref().unwrap())
+                == RecordType::AutoBoxed
+            && (analysis.glib_get_type.is_some()
+                || analysis.boxed_inline
+                || !analysis.specials.has_trait(Type::Free)))
+    {
         if let Some((ref glib_get_type, _)) = analysis.glib_get_type {
             general::define_auto_boxed_type(
                 w,
@@ -27,6 +78,20 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
                 &analysis.clear_function_expression,
                 glib_get_type,
                 &analysis.derives,
+                config.must_use,
+                analysis.visibility,
+            )?;
+        } else if analysis.boxed_inline {
+            general::define_plain_boxed_inline_type(
+                w,
+                env,
+                &analysis.name,
+                &type_.c_type,
+                &analysis.init_function_expression,
+                &analysis.copy_into_function_expression,
+                &analysis.clear_function_expression,
+                &analysis.derives,
+                config.must_use,
                 analysis.visibility,
             )?;
         } else {
@@ -35,9 +100,10 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
                 analysis.name
             );
         }
-    } else if let (Some(ref_fn), Some(unref_fn)) = (
+    } else if let (Some(ref_fn), Some(unref_fn), None) = (
         analysis.specials.traits().get(&Type::Ref),
         analysis.specials.traits().get(&Type::Unref),
+        use_boxed_functions,
     ) {
         general::define_shared_type(
             w,
@@ -54,6 +120,7 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
                 }
             }),
             &analysis.derives,
+            config.must_use,
             analysis.visibility,
         )?;
     } else if let (Some(copy_fn), Some(free_fn)) = (
@@ -79,8 +146,11 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
                 }
             }),
             &analysis.derives,
+            config.must_use,
             analysis.visibility,
         )?;
+    } else if let Some(free_fn) = analysis.specials.traits().get(&Type::Free) {
+        generate_free_only_type(w, env, analysis, &type_.c_type, &free_fn.glib_name)?;
     } else {
         panic!(
             "Missing memory management functions for {}",
@@ -113,7 +183,14 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
         writeln!(w, "}}")?;
     }
 
-    general::declare_default_from_new(w, env, &analysis.name, &analysis.functions, false)?;
+    general::declare_default_from_new(
+        w,
+        env,
+        &analysis.name,
+        &analysis.full_name,
+        &analysis.functions,
+        false,
+    )?;
 
     trait_impls::generate(
         w,
@@ -126,6 +203,10 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
         None, // There is no need for #[cfg()] since it's applied on the whole file.
     )?;
 
+    if let Some(ref fields) = analysis.debug_fields {
+        generate_debug_impl(w, env, &analysis.name, &type_.c_type, fields)?;
+    }
+
     if analysis.concurrency != library::Concurrency::None {
         writeln!(w)?;
     }
@@ -141,9 +222,409 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
         writeln!(w, "unsafe impl Sync for {} {{}}", analysis.name)?;
     }
 
+    general::custom_code(w, &config.epilogue, "epilogue")
+}
+
+/// Returns the configured `use_boxed_functions`, ignoring it for records
+/// without a GType, or without the `copy` and `free` functions it forces
+fn use_boxed_functions(
+    configured: Option<bool>,
+    has_get_type: bool,
+    has_copy_free: bool,
+    name: &str,
+) -> Option<bool> {
+    match configured {
+        Some(_) if !has_get_type => {
+            log::warn!(
+                "`use_boxed_functions` can only be used on records with a GType. Ignoring it for {}.",
+                name,
+            );
+            None
+        }
+        Some(false) if !has_copy_free => {
+            log::warn!(
+                "`use_boxed_functions = false` needs `copy` and `free` functions. Ignoring it for {}.",
+                name,
+            );
+            None
+        }
+        configured => configured,
+    }
+}
+
+/// Generates a wrapper owning a pointer to a record that can only be freed
+fn generate_free_only_type(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::record::Info,
+    c_type: &str,
+    free_fn: &str,
+) -> Result<()> {
+    let name = &analysis.name;
+    let sys_crate_name = env.main_sys_crate_name();
+    let c_type = format!("{sys_crate_name}::{c_type}");
+    let phantom = "std::marker::PhantomData";
+
+    writeln!(w)?;
+    general::derives(w, &analysis.derives, 0)?;
+    writeln!(w, "#[repr(transparent)]")?;
+    writeln!(
+        w,
+        "{} struct {name}(std::ptr::NonNull<{c_type}>);",
+        analysis.visibility.export_visibility()
+    )?;
+
+    writeln!(w)?;
+    writeln!(w, "impl Drop for {name} {{")?;
+    writeln!(w, "\t#[inline]")?;
+    writeln!(w, "\tfn drop(&mut self) {{")?;
+    writeln!(w, "\t\tunsafe {{")?;
+    writeln!(w, "\t\t\t{sys_crate_name}::{free_fn}(self.0.as_ptr());")?;
+    writeln!(w, "\t\t}}")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")?;
+
+    for (ptr, cast) in [("*const", " as *const _"), ("*mut", "")] {
+        writeln!(w)?;
+        writeln!(w, "#[doc(hidden)]")?;
+        writeln!(w, "impl<'a> ToGlibPtr<'a, {ptr} {c_type}> for {name} {{")?;
+        writeln!(w, "\ttype Storage = {phantom}<&'a Self>;")?;
+        writeln!(w)?;
+        writeln!(w, "\t#[inline]")?;
+        writeln!(
+            w,
+            "\tfn to_glib_none(&'a self) -> Stash<'a, {ptr} {c_type}, Self> {{"
+        )?;
+        writeln!(w, "\t\tStash(self.0.as_ptr(){cast}, {phantom})")?;
+        writeln!(w, "\t}}")?;
+        writeln!(w, "}}")?;
+    }
+
+    writeln!(w)?;
+    writeln!(w, "#[doc(hidden)]")?;
+    writeln!(w, "impl<'a> ToGlibPtrMut<'a, *mut {c_type}> for {name} {{")?;
+    writeln!(w, "\ttype Storage = {phantom}<&'a mut Self>;")?;
+    writeln!(w)?;
+    writeln!(w, "\t#[inline]")?;
+    writeln!(
+        w,
+        "\tfn to_glib_none_mut(&'a mut self) -> StashMut<'a, *mut {c_type}, Self> {{"
+    )?;
+    writeln!(w, "\t\tStashMut(self.0.as_ptr(), {phantom})")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")?;
+
+    writeln!(w)?;
+    writeln!(w, "#[doc(hidden)]")?;
+    writeln!(w, "impl IntoGlibPtr<*mut {c_type}> for {name} {{")?;
+    writeln!(w, "\t#[inline]")?;
+    writeln!(w, "\tunsafe fn into_glib_ptr(self) -> *mut {c_type} {{")?;
+    writeln!(w, "\t\tlet s = std::mem::ManuallyDrop::new(self);")?;
+    writeln!(w, "\t\ts.0.as_ptr()")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")?;
+
+    for ptr in ["*mut", "*const"] {
+        writeln!(w)?;
+        writeln!(w, "#[doc(hidden)]")?;
+        writeln!(w, "impl FromGlibPtrFull<{ptr} {c_type}> for {name} {{")?;
+        writeln!(w, "\t#[inline]")?;
+        writeln!(
+            w,
+            "\tunsafe fn from_glib_full(ptr: {ptr} {c_type}) -> Self {{"
+        )?;
+        writeln!(w, "\t\tdebug_assert!(!ptr.is_null());")?;
+        writeln!(
+            w,
+            "\t\tSelf(std::ptr::NonNull::new_unchecked(ptr as *mut _))"
+        )?;
+        writeln!(w, "\t}}")?;
+        writeln!(w, "}}")?;
+    }
+
+    writeln!(w)?;
+    writeln!(w, "#[doc(hidden)]")?;
+    writeln!(w, "impl FromGlibPtrBorrow<*mut {c_type}> for {name} {{")?;
+    writeln!(w, "\t#[inline]")?;
+    writeln!(
+        w,
+        "\tunsafe fn from_glib_borrow(ptr: *mut {c_type}) -> Borrowed<Self> {{"
+    )?;
+    writeln!(w, "\t\tdebug_assert!(!ptr.is_null());")?;
+    writeln!(
+        w,
+        "\t\tBorrowed::new(Self(std::ptr::NonNull::new_unchecked(ptr)))"
+    )?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")
+}
+
+fn generate_plain_struct(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::record::Info,
+    c_type: &str,
+    fields: &[(String, String)],
+) -> Result<()> {
+    let name = &analysis.name;
+    let c_type = format!("{}::{c_type}", env.main_sys_crate_name());
+
+    writeln!(w)?;
+    writeln!(w, "#[derive(Clone, Copy)]")?;
+    general::derives(w, &analysis.derives, 0)?;
+    if env.config.objects[&analysis.full_name].must_use {
+        writeln!(w, "#[must_use]")?;
+    }
+    writeln!(w, "#[repr(C)]")?;
+    writeln!(
+        w,
+        "{} struct {name} {{",
+        analysis.visibility.export_visibility()
+    )?;
+    for (field, typ) in fields {
+        writeln!(w, "\tpub {field}: {typ},")?;
+    }
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+    generate_layout_asserts(w, name, &c_type, env.config.supports_rust(CONST_PANIC))?;
+
+    generate_plain_struct_impls(w, name, &c_type, &use_glib_if_needed(env, "ffi"))
+}
+
+/// Rust version that allows `assert!` in constants.
+const CONST_PANIC: Version = Version(1, 57, 0);
+
+/// Checks at compile time that the struct can be used in place of `c_type`.
+///
+/// Without `assert!` in constants, arrays whose lengths must be equal are used.
+fn generate_layout_asserts(
+    w: &mut dyn Write,
+    name: &str,
+    c_type: &str,
+    const_panic: bool,
+) -> Result<()> {
+    for func in ["size_of", "align_of"] {
+        let (left, right) = (
+            format!("std::mem::{func}::<{name}>()"),
+            format!("std::mem::{func}::<{c_type}>()"),
+        );
+        if const_panic {
+            writeln!(w, "const _: () = assert!({left} == {right});")?;
+        } else {
+            writeln!(w, "const _: [(); {left}] = [(); {right}];")?;
+        }
+    }
     Ok(())
 }
 
+fn generate_plain_struct_impls(
+    w: &mut dyn Write,
+    name: &str,
+    c_type: &str,
+    glib_ffi: &str,
+) -> Result<()> {
+    let phantom = "std::marker::PhantomData";
+
+    writeln!(w)?;
+    writeln!(w, "#[doc(hidden)]")?;
+    writeln!(w, "impl<'a> ToGlibPtr<'a, *const {c_type}> for {name} {{")?;
+    writeln!(w, "\ttype Storage = {phantom}<&'a Self>;")?;
+    writeln!(w)?;
+    writeln!(w, "\t#[inline]")?;
+    writeln!(
+        w,
+        "\tfn to_glib_none(&'a self) -> Stash<'a, *const {c_type}, Self> {{"
+    )?;
+    writeln!(w, "\t\tStash(self as *const Self as *const _, {phantom})")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")?;
+
+    writeln!(w)?;
+    writeln!(w, "#[doc(hidden)]")?;
+    writeln!(w, "impl<'a> ToGlibPtrMut<'a, *mut {c_type}> for {name} {{")?;
+    writeln!(w, "\ttype Storage = {phantom}<&'a mut Self>;")?;
+    writeln!(w)?;
+    writeln!(w, "\t#[inline]")?;
+    writeln!(
+        w,
+        "\tfn to_glib_none_mut(&'a mut self) -> StashMut<'a, *mut {c_type}, Self> {{"
+    )?;
+    writeln!(w, "\t\tStashMut(self as *mut Self as *mut _, {phantom})")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")?;
+
+    for ptr in ["*const", "*mut"] {
+        writeln!(w)?;
+        writeln!(w, "#[doc(hidden)]")?;
+        writeln!(w, "impl FromGlibPtrNone<{ptr} {c_type}> for {name} {{")?;
+        writeln!(w, "\t#[inline]")?;
+        writeln!(
+            w,
+            "\tunsafe fn from_glib_none(ptr: {ptr} {c_type}) -> Self {{"
+        )?;
+        writeln!(w, "\t\tdebug_assert!(!ptr.is_null());")?;
+        writeln!(w, "\t\t*(ptr as *const Self)")?;
+        writeln!(w, "\t}}")?;
+        writeln!(w, "}}")?;
+    }
+
+    writeln!(w)?;
+    writeln!(w, "#[doc(hidden)]")?;
+    writeln!(w, "impl Uninitialized for {name} {{")?;
+    writeln!(w, "\t#[inline]")?;
+    writeln!(w, "\tunsafe fn uninitialized() -> Self {{")?;
+    writeln!(w, "\t\tstd::mem::zeroed()")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")?;
+
+    writeln!(w)?;
+    writeln!(w, "#[doc(hidden)]")?;
+    writeln!(w, "impl FromGlibPtrFull<*mut {c_type}> for {name} {{")?;
+    writeln!(w, "\t#[inline]")?;
+    writeln!(
+        w,
+        "\tunsafe fn from_glib_full(ptr: *mut {c_type}) -> Self {{"
+    )?;
+    writeln!(w, "\t\tlet res = from_glib_none(ptr);")?;
+    writeln!(w, "\t\t{glib_ffi}::g_free(ptr as *mut _);")?;
+    writeln!(w, "\t\tres")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")?;
+
+    for (ptr, null, cast) in [("*const", "null", " as *const _"), ("*mut", "null_mut", "")] {
+        writeln!(w)?;
+        writeln!(w, "#[doc(hidden)]")?;
+        writeln!(w, "impl FromGlibPtrBorrow<{ptr} {c_type}> for {name} {{")?;
+        writeln!(w, "\t#[inline]")?;
+        writeln!(
+            w,
+            "\tunsafe fn from_glib_borrow(ptr: {ptr} {c_type}) -> Borrowed<Self> {{"
+        )?;
+        writeln!(w, "\t\tBorrowed::new(from_glib_none(ptr))")?;
+        writeln!(w, "\t}}")?;
+        writeln!(w, "}}")?;
+
+        writeln!(w)?;
+        writeln!(w, "#[doc(hidden)]")?;
+        writeln!(
+            w,
+            "impl FromGlibContainerAsVec<{c_type}, {ptr} {c_type}> for {name} {{"
+        )?;
+        writeln!(
+            w,
+            "\tunsafe fn from_glib_none_num_as_vec(ptr: {ptr} {c_type}, num: usize) -> Vec<Self> {{"
+        )?;
+        writeln!(w, "\t\tif num == 0 || ptr.is_null() {{")?;
+        writeln!(w, "\t\t\treturn Vec::new();")?;
+        writeln!(w, "\t\t}}")?;
+        writeln!(
+            w,
+            "\t\tstd::slice::from_raw_parts(ptr as *const Self, num).to_vec()"
+        )?;
+        writeln!(w, "\t}}")?;
+        writeln!(w)?;
+        writeln!(
+            w,
+            "\tunsafe fn from_glib_container_num_as_vec(ptr: {ptr} {c_type}, num: usize) -> Vec<Self> {{"
+        )?;
+        writeln!(
+            w,
+            "\t\tlet res = Self::from_glib_none_num_as_vec(ptr, num);"
+        )?;
+        writeln!(w, "\t\t{glib_ffi}::g_free(ptr as *mut _);")?;
+        writeln!(w, "\t\tres")?;
+        writeln!(w, "\t}}")?;
+        writeln!(w)?;
+        writeln!(
+            w,
+            "\tunsafe fn from_glib_full_num_as_vec(ptr: {ptr} {c_type}, num: usize) -> Vec<Self> {{"
+        )?;
+        writeln!(w, "\t\tSelf::from_glib_container_num_as_vec(ptr, num)")?;
+        writeln!(w, "\t}}")?;
+        writeln!(w, "}}")?;
+
+        writeln!(w)?;
+        writeln!(w, "#[doc(hidden)]")?;
+        writeln!(
+            w,
+            "impl<'a> ToGlibContainerFromSlice<'a, {ptr} {c_type}> for {name} {{"
+        )?;
+        writeln!(w, "\ttype Storage = &'a [Self];")?;
+        writeln!(w)?;
+        writeln!(
+            w,
+            "\tfn to_glib_none_from_slice(t: &'a [Self]) -> ({ptr} {c_type}, &'a [Self]) {{"
+        )?;
+        writeln!(w, "\t\t(t.as_ptr() as {ptr} {c_type}, t)")?;
+        writeln!(w, "\t}}")?;
+        writeln!(w)?;
+        writeln!(
+            w,
+            "\tfn to_glib_container_from_slice(t: &'a [Self]) -> ({ptr} {c_type}, &'a [Self]) {{"
+        )?;
+        writeln!(
+            w,
+            "\t\t(ToGlibContainerFromSlice::to_glib_full_from_slice(t), t)"
+        )?;
+        writeln!(w, "\t}}")?;
+        writeln!(w)?;
+        writeln!(
+            w,
+            "\tfn to_glib_full_from_slice(t: &[Self]) -> {ptr} {c_type} {{"
+        )?;
+        writeln!(w, "\t\tif t.is_empty() {{")?;
+        writeln!(w, "\t\t\treturn std::ptr::{null}();")?;
+        writeln!(w, "\t\t}}")?;
+        writeln!(w, "\t\tunsafe {{")?;
+        writeln!(
+            w,
+            "\t\t\tlet res = {glib_ffi}::g_malloc(std::mem::size_of_val(t)) as *mut {c_type};"
+        )?;
+        writeln!(
+            w,
+            "\t\t\tstd::ptr::copy_nonoverlapping(t.as_ptr() as *const {c_type}, res, t.len());"
+        )?;
+        writeln!(w, "\t\t\tres{cast}")?;
+        writeln!(w, "\t\t}}")?;
+        writeln!(w, "\t}}")?;
+        writeln!(w, "}}")?;
+    }
+    Ok(())
+}
+
+fn generate_debug_impl(
+    w: &mut dyn Write,
+    env: &Env,
+    name: &str,
+    c_type: &str,
+    fields: &[(String, bool)],
+) -> Result<()> {
+    writeln!(w)?;
+    writeln!(w, "impl std::fmt::Debug for {name} {{")?;
+    writeln!(
+        w,
+        "\tfn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{"
+    )?;
+    writeln!(
+        w,
+        "\t\tlet ptr: *const {}::{c_type} = self.to_glib_none().0;",
+        env.main_sys_crate_name()
+    )?;
+    writeln!(w, "\t\tunsafe {{")?;
+    writeln!(w, "\t\t\tf.debug_struct(\"{name}\")")?;
+    for (field, is_bool) in fields {
+        if *is_bool {
+            writeln!(w, "\t\t\t\t.field(\"{field}\", &((*ptr).{field} != 0))")?;
+        } else {
+            writeln!(w, "\t\t\t\t.field(\"{field}\", &(*ptr).{field})")?;
+        }
+    }
+    writeln!(w, "\t\t\t\t.finish()")?;
+    writeln!(w, "\t\t}}")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")
+}
+
 pub fn generate_reexports(
     env: &Env,
     analysis: &analysis::record::Info,
@@ -167,6 +648,9 @@ pub fn generate_reexports(
         cfg.push_str(&s);
         cfg.push('\n');
     };
+    if let Some(version_end) = env.config.objects[&analysis.full_name].version_end {
+        cfg.push_str(&format!("#[cfg(not({}))]\n", version_end.to_cfg(None)));
+    }
     contents.push(String::new());
     contents.push(format!("{cfg}mod {module_name};"));
     contents.push(format!(
@@ -177,3 +661,70 @@ pub fn generate_reexports(
         analysis.name
     ));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_use_boxed_functions() {
+        assert_eq!(use_boxed_functions(None, true, true, "Test"), None);
+        assert_eq!(
+            use_boxed_functions(Some(true), true, false, "Test"),
+            Some(true)
+        );
+        assert_eq!(
+            use_boxed_functions(Some(false), true, true, "Test"),
+            Some(false)
+        );
+        assert_eq!(use_boxed_functions(Some(false), true, false, "Test"), None);
+        assert_eq!(use_boxed_functions(Some(true), false, true, "Test"), None);
+        assert_eq!(use_boxed_functions(Some(false), false, true, "Test"), None);
+    }
+
+    #[test]
+    fn test_layout_asserts() {
+        let mut w = Vec::new();
+        generate_layout_asserts(&mut w, "Rectangle", "ffi::PangoRectangle", true).unwrap();
+        assert_eq!(
+            String::from_utf8(w).unwrap(),
+            "const _: () = assert!(std::mem::size_of::<Rectangle>() == \
+             std::mem::size_of::<ffi::PangoRectangle>());\n\
+             const _: () = assert!(std::mem::align_of::<Rectangle>() == \
+             std::mem::align_of::<ffi::PangoRectangle>());\n"
+        );
+
+        let mut w = Vec::new();
+        generate_layout_asserts(&mut w, "Rectangle", "ffi::PangoRectangle", false).unwrap();
+        assert_eq!(
+            String::from_utf8(w).unwrap(),
+            "const _: [(); std::mem::size_of::<Rectangle>()] = \
+             [(); std::mem::size_of::<ffi::PangoRectangle>()];\n\
+             const _: [(); std::mem::align_of::<Rectangle>()] = \
+             [(); std::mem::align_of::<ffi::PangoRectangle>()];\n"
+        );
+    }
+
+    #[test]
+    fn test_plain_struct_impls() {
+        let mut w = Vec::new();
+        generate_plain_struct_impls(&mut w, "Rectangle", "ffi::PangoRectangle", "glib::ffi")
+            .unwrap();
+        let code = String::from_utf8(w).unwrap();
+        for impl_ in [
+            "impl<'a> ToGlibPtr<'a, *const ffi::PangoRectangle> for Rectangle",
+            "impl<'a> ToGlibPtrMut<'a, *mut ffi::PangoRectangle> for Rectangle",
+            "impl FromGlibPtrNone<*const ffi::PangoRectangle> for Rectangle",
+            "impl FromGlibPtrFull<*mut ffi::PangoRectangle> for Rectangle",
+            "impl FromGlibPtrBorrow<*mut ffi::PangoRectangle> for Rectangle",
+            "impl FromGlibContainerAsVec<ffi::PangoRectangle, *const ffi::PangoRectangle> \
+             for Rectangle",
+            "impl<'a> ToGlibContainerFromSlice<'a, *mut ffi::PangoRectangle> for Rectangle",
+            "impl Uninitialized for Rectangle",
+        ] {
+            assert!(code.contains(impl_), "missing `{impl_}`");
+        }
+        assert!(code.contains("glib::ffi::g_free(ptr as *mut _);"));
+        assert!(code.contains("return std::ptr::null();"));
+    }
+}
@@ -1,14 +1,21 @@
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
 
 use log::info;
 
 use crate::{env::Env, file_saver::*, nameutil::*};
 
-pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
+pub fn generate(
+    env: &Env,
+    root_path: &Path,
+    mod_rs: &mut Vec<String>,
+    submodules: &mut BTreeMap<String, Vec<String>>,
+) {
     info!("Generate records");
     for record_analysis in env.analysis.records.values() {
         let obj = &env.config.objects[&record_analysis.full_name];
-        if !obj.status.need_generate() {
+        if !obj.status.need_generate()
+            || matches!(obj.version_end, Some(v) if v <= env.config.min_cfg_version)
+        {
             continue;
         }
 
@@ -17,7 +24,11 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
             .clone()
             .unwrap_or_else(|| module_name(split_namespace_name(&record_analysis.full_name).1));
 
-        let mut path = root_path.join(&mod_name);
+        let module_path = obj.module_path.as_deref();
+        let mut path = match module_path {
+            Some(module_path) => root_path.join(module_path).join(&mod_name),
+            None => root_path.join(&mod_name),
+        };
         path.set_extension("rs");
         info!("Generating file {:?}", path);
 
@@ -25,6 +36,10 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
             super::record::generate(w, env, record_analysis)
         });
 
-        super::record::generate_reexports(env, record_analysis, &mod_name, mod_rs);
+        let contents = match module_path {
+            Some(module_path) => submodules.entry(module_path.to_owned()).or_default(),
+            None => &mut *mod_rs,
+        };
+        super::record::generate_reexports(env, record_analysis, &mod_name, contents);
     }
 }
@@ -61,17 +61,51 @@ impl ToReturnValue for analysis::return_value::Info {
         par.lib_par
             .to_return_value(env, try_from_glib, is_trampoline)
             .map(|type_name| {
-                if self.nullable_return_is_error.is_some() && type_name.starts_with("Option<") {
+                // The type of the value the `Option<T>` is unwrapped to
+                let non_nullable = || {
+                    library::Parameter {
+                        nullable: library::Nullable(false),
+                        ..par.lib_par.clone()
+                    }
+                    .to_return_value(env, try_from_glib, is_trampoline)
+                    .unwrap_or_default()
+                };
+                let is_garray = matches!(env.type_(par.lib_par.typ), library::Type::Array(..));
+                if self.nullable_return_is_error.is_some() && is_garray {
+                    // `GArray` is returned as `Vec<T>` even if nullable
+                    format!("Result<{}, {}>", type_name, use_glib_type(env, "BoolError"))
+                } else if self.nullable_return_is_error.is_some()
+                    && type_name.starts_with("Option<")
+                {
                     // Change `Option<T>` to `Result<T, glib::BoolError>`
                     format!(
                         "Result<{}, {}BoolError>",
-                        &type_name[7..(type_name.len() - 1)],
+                        non_nullable(),
                         if env.namespaces.glib_ns_id == namespaces::MAIN {
                             ""
                         } else {
                             "glib::"
                         }
                     )
+                } else if self.null_panic_message.is_some() && type_name.starts_with("Option<") {
+                    // Change `Option<T>` to `T`, panicking on `None`
+                    non_nullable()
+                } else if self.lazy_list {
+                    // Change `Vec<T>` to `glib::List<T>` or `glib::SList<T>`
+                    lazy_list_type(
+                        &env.library,
+                        par.lib_par.typ,
+                        |tid| RustType::try_new(env, tid).into_string(),
+                        |name| use_glib_type(env, name),
+                    )
+                } else if self.invalid_char_is_none && type_name == "char" {
+                    "Option<char>".to_owned()
+                } else if self.borrowed {
+                    format!(
+                        "{}<{}>",
+                        use_glib_type(env, "translate::Borrowed"),
+                        type_name
+                    )
                 } else {
                     type_name
                 }
@@ -79,6 +113,37 @@ impl ToReturnValue for analysis::return_value::Info {
     }
 }
 
+/// Returns the name of the `glib::List` or `glib::SList` a `GList` or `GSList`
+/// is lazily converted to.
+pub fn lazy_list_name(library: &library::Library, tid: TypeId) -> &'static str {
+    match library.type_(tid) {
+        library::Type::SList(_) => "SList",
+        _ => "List",
+    }
+}
+
+/// Returns the `glib::List` or `glib::SList` type of a lazily converted list.
+/// Strings are held as `glib::GStringPtr`, which unlike `GString` has the
+/// layout of a `char *`.
+fn lazy_list_type(
+    library: &library::Library,
+    tid: TypeId,
+    rust_type: impl FnOnce(TypeId) -> String,
+    glib_type: impl Fn(&str) -> String,
+) -> String {
+    let element = match library.type_(tid) {
+        library::Type::List(element) | library::Type::SList(element) => *element,
+        _ => unreachable!("lazy list of a non-list type"),
+    };
+    let element = match library.type_(element) {
+        library::Type::Basic(
+            library::Basic::Utf8 | library::Basic::Filename | library::Basic::OsString,
+        ) => glib_type("GStringPtr"),
+        _ => rust_type(element),
+    };
+    format!("{}<{}>", glib_type(lazy_list_name(library, tid)), element)
+}
+
 /// Returns the `TypeId` of the returned types from the provided function.
 pub fn out_parameter_types(analysis: &analysis::functions::Info) -> Vec<TypeId> {
     // If it returns an error, there is nothing for us to check.
@@ -179,7 +244,9 @@ fn out_parameter_as_return_parts(
         }
         Optional => {
             if num_outs > 1 {
-                if analysis.ret.nullable_return_is_error.is_some() {
+                if analysis.ret.nullable_return_is_error.is_some()
+                    || analysis.ret.bool_return_is_error.is_some()
+                {
                     (
                         "Result<(",
                         format!("), {}>", use_glib_type(env, "BoolError")),
@@ -187,7 +254,9 @@ fn out_parameter_as_return_parts(
                 } else {
                     ("Option<(", ")>".to_owned())
                 }
-            } else if analysis.ret.nullable_return_is_error.is_some() {
+            } else if analysis.ret.nullable_return_is_error.is_some()
+                || analysis.ret.bool_return_is_error.is_some()
+            {
                 ("Result<", format!(", {}>", use_glib_type(env, "BoolError")))
             } else {
                 ("Option<", ">".to_owned())
@@ -270,3 +339,44 @@ fn out_parameter_as_return(out: &analysis::Parameter, env: &Env) -> String {
         _ => name,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::{Class, Library, Type, INTERNAL_NAMESPACE, MAIN_NAMESPACE};
+
+    #[test]
+    fn test_lazy_list_type() {
+        let mut library = Library::new("Test");
+        let widget = library.add_type(MAIN_NAMESPACE, "Widget", Type::Class(Class::default()));
+        let strings = library.add_type(
+            INTERNAL_NAMESPACE,
+            "List(#utf8)",
+            Type::List(TypeId::tid_utf8()),
+        );
+        let paths = library.add_type(
+            INTERNAL_NAMESPACE,
+            "SList(#filename)",
+            Type::SList(TypeId::tid_filename()),
+        );
+        let widgets = library.add_type(INTERNAL_NAMESPACE, "SList(#Widget)", Type::SList(widget));
+
+        let rust_type = |tid: TypeId| {
+            assert_eq!(tid, widget);
+            "Widget".to_owned()
+        };
+        let glib_type = |name: &str| format!("glib::{name}");
+        assert_eq!(
+            lazy_list_type(&library, strings, rust_type, glib_type),
+            "glib::List<glib::GStringPtr>"
+        );
+        assert_eq!(
+            lazy_list_type(&library, paths, rust_type, glib_type),
+            "glib::SList<glib::GStringPtr>"
+        );
+        assert_eq!(
+            lazy_list_type(&library, widgets, rust_type, glib_type),
+            "glib::SList<Widget>"
+        );
+    }
+}
@@ -1,14 +1,15 @@
 use std::io::{Result, Write};
 
 use super::{
-    general::{cfg_deprecated, doc_alias, doc_hidden, version_condition},
+    general::{cfg_deprecated, doc_alias, doc_hidden, not_version_condition, version_condition},
     signal_body,
-    trampoline::{self, func_string},
+    trampoline::{self, func_string, local_func_string, stream_item},
 };
 use crate::{
     analysis,
     chunk::Chunk,
     env::Env,
+    nameutil::use_glib_type,
     writer::{primitives::tabs, ToCode},
 };
 
@@ -31,6 +32,7 @@ pub fn generate(
     writeln!(w)?;
     cfg_deprecated(w, env, None, analysis.deprecated_version, commented, indent)?;
     version_condition(w, env, None, analysis.version, commented, indent)?;
+    not_version_condition(w, analysis.not_version, commented, indent)?;
     doc_hidden(w, analysis.doc_hidden, comment_prefix, indent)?;
     // Strip the "prefix" from "prefix::prop-name", if any.
     // Ex.: "notify::is-locked".
@@ -86,12 +88,21 @@ pub fn generate(
         return Ok(());
     }
 
+    if analysis.generate_local {
+        generate_local(w, env, analysis, pub_prefix, only_declaration, indent)?;
+    }
+
+    if analysis.generate_stream {
+        generate_stream(w, env, analysis, pub_prefix, only_declaration, indent)?;
+    }
+
     if let Some(ref emit_name) = analysis.action_emit_name {
         writeln!(w)?;
         if !in_trait || only_declaration {
             cfg_deprecated(w, env, None, analysis.deprecated_version, commented, indent)?;
         }
         version_condition(w, env, None, analysis.version, commented, indent)?;
+        not_version_condition(w, analysis.not_version, commented, indent)?;
 
         let function_type = function_type_string(env, analysis, false);
 
@@ -152,6 +163,159 @@ pub fn generate(
     Ok(())
 }
 
+fn generate_local(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::signals::Info,
+    pub_prefix: &str,
+    only_declaration: bool,
+    indent: usize,
+) -> Result<()> {
+    let trampoline = analysis.trampoline.as_ref().unwrap();
+    let function_type = local_func_string(env, trampoline, Some("Self"));
+    let (param_str, detail_arg) = if !analysis.is_detailed {
+        ("&self, f: F", "")
+    } else {
+        ("&self, detail: Option<&str>, f: F", "detail, ")
+    };
+    let args = (0..trampoline.parameters.rust_parameters.len())
+        .map(|pos| format!("arg{pos}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let suffix = if only_declaration { ";" } else { " {" };
+
+    writeln!(w)?;
+    cfg_deprecated(w, env, None, analysis.deprecated_version, false, indent)?;
+    version_condition(w, env, None, analysis.version, false, indent)?;
+    not_version_condition(w, analysis.not_version, false, indent)?;
+    doc_hidden(w, analysis.doc_hidden, "", indent)?;
+    writeln!(w, "{}// rustdoc-stripper-ignore-next", tabs(indent))?;
+    writeln!(
+        w,
+        "{}/// Like [`{}()`][Self::{1}()] but the closure doesn't need to be `Send`.",
+        tabs(indent),
+        analysis.connect_name,
+    )?;
+    writeln!(w, "{}///", tabs(indent))?;
+    writeln!(
+        w,
+        "{}/// Panics if the signal is emitted from another thread than the one it was connected from.",
+        tabs(indent),
+    )?;
+    writeln!(
+        w,
+        "{}{}fn {}_local<F: {}>({}) -> SignalHandlerId{}",
+        tabs(indent),
+        pub_prefix,
+        analysis.connect_name,
+        function_type,
+        param_str,
+        suffix,
+    )?;
+    if !only_declaration {
+        writeln!(
+            w,
+            "{}let f = {}::new(f);",
+            tabs(indent + 1),
+            use_glib_type(env, "thread_guard::ThreadGuard"),
+        )?;
+        writeln!(
+            w,
+            "{}self.{}({}move |{}| (f.get_ref())({3}))",
+            tabs(indent + 1),
+            analysis.connect_name,
+            detail_arg,
+            args,
+        )?;
+        writeln!(w, "{}}}", tabs(indent))?;
+    }
+
+    Ok(())
+}
+
+fn generate_stream(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::signals::Info,
+    pub_prefix: &str,
+    only_declaration: bool,
+    indent: usize,
+) -> Result<()> {
+    let trampoline = analysis.trampoline.as_ref().unwrap();
+    let (item_type, item) = stream_item(env, trampoline);
+    let (param_str, detail_arg) = if !analysis.is_detailed {
+        ("&self", "")
+    } else {
+        ("&self, detail: Option<&str>", "detail, ")
+    };
+    let args = (1..trampoline.parameters.rust_parameters.len())
+        .map(|pos| format!(", arg{pos}"))
+        .collect::<String>();
+    let name = analysis
+        .connect_name
+        .strip_prefix("connect_")
+        .unwrap_or(&analysis.connect_name);
+    let suffix = if only_declaration { ";" } else { " {" };
+
+    writeln!(w)?;
+    writeln!(w, "{}#[cfg(feature = \"futures\")]", tabs(indent))?;
+    cfg_deprecated(w, env, None, analysis.deprecated_version, false, indent)?;
+    version_condition(w, env, None, analysis.version, false, indent)?;
+    not_version_condition(w, analysis.not_version, false, indent)?;
+    doc_hidden(w, analysis.doc_hidden, "", indent)?;
+    writeln!(w, "{}// rustdoc-stripper-ignore-next", tabs(indent))?;
+    writeln!(
+        w,
+        "{}/// Returns a stream of the emissions of the signal connected to by [`{}()`][Self::{1}()].",
+        tabs(indent),
+        analysis.connect_name,
+    )?;
+    writeln!(w, "{}///", tabs(indent))?;
+    writeln!(
+        w,
+        "{}/// The signal handler is disconnected on the first emission after the stream was dropped.",
+        tabs(indent),
+    )?;
+    writeln!(
+        w,
+        "{}{}fn {}_stream({}) -> std::pin::Pin<Box_<dyn futures_core::Stream<Item = {}> + 'static>>{}",
+        tabs(indent),
+        pub_prefix,
+        name,
+        param_str,
+        item_type,
+        suffix,
+    )?;
+    if !only_declaration {
+        let i = tabs(indent + 1);
+        writeln!(
+            w,
+            "{i}let (sender, receiver) = futures_channel::mpsc::unbounded();\n\
+             {i}let handler_id = std::sync::Arc::new(std::sync::Mutex::new(None::<SignalHandlerId>));\n\
+             {i}let id = self.{}({}{{\n\
+             {i}\tlet handler_id = handler_id.clone();\n\
+             {i}\tmove |obj{}| {{\n\
+             {i}\t\tif sender.unbounded_send({}).is_err() {{\n\
+             {i}\t\t\tif let Some(id) = handler_id.lock().unwrap().take() {{\n\
+             {i}\t\t\t\t{}(obj, id);\n\
+             {i}\t\t\t}}\n\
+             {i}\t\t}}\n\
+             {i}\t}}\n\
+             {i}}});\n\
+             {i}*handler_id.lock().unwrap() = Some(id);\n\
+             {i}Box_::pin(receiver)",
+            analysis.connect_name,
+            detail_arg,
+            args,
+            item,
+            use_glib_type(env, "prelude::ObjectExt::disconnect"),
+        )?;
+        writeln!(w, "{}}}", tabs(indent))?;
+    }
+
+    Ok(())
+}
+
 fn function_type_string(
     env: &Env,
     analysis: &analysis::signals::Info,
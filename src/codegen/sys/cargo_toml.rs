@@ -4,7 +4,13 @@ use log::info;
 use toml::{self, value::Table, Value};
 
 use super::collect_versions;
-use crate::{config::Config, env::Env, file_saver::save_to_file, nameutil, version::Version};
+use crate::{
+    config::{opaque_types::OpaqueTypes, Config},
+    env::Env,
+    file_saver::save_to_file,
+    nameutil,
+    version::Version,
+};
 
 pub fn generate(env: &Env) -> String {
     info!("Generating sys Cargo.toml for {}", env.config.library_name);
@@ -39,6 +45,13 @@ fn fill_empty(root: &mut Table, env: &Env, crate_name: &str) {
         set_string(package, "name", package_name);
         set_string(package, "version", "0.0.1");
         set_string(package, "edition", "2021");
+        if let Some(shared_lib) = env.namespaces.main().shared_libs.first() {
+            set_string(
+                package,
+                "links",
+                nameutil::shared_lib_name_to_link_name(shared_lib),
+            );
+        }
     }
 
     {
@@ -49,7 +62,9 @@ fn fill_empty(root: &mut Table, env: &Env, crate_name: &str) {
     let deps = upsert_table(root, "dependencies");
     for ext_lib in &env.config.external_libraries {
         let dep = upsert_table(deps, &ext_lib.crate_name);
-        let ext_package = if ext_lib.crate_name == "cairo" {
+        let ext_package = if let Some(ref sys_package) = ext_lib.sys_package {
+            sys_package.clone()
+        } else if ext_lib.crate_name == "cairo" {
             format!("{}-sys-rs", ext_lib.crate_name)
         } else if ext_lib.crate_name == "gdk_pixbuf" {
             "gdk-pixbuf-sys".into()
@@ -93,7 +108,11 @@ fn fill_empty(root: &mut Table, env: &Env, crate_name: &str) {
             &_ => "ADD GIT REPOSITORY URL HERE",
         };
         set_string(dep, "package", ext_package);
-        set_string(dep, "git", repo_url);
+        if let Some(ref sys_path) = ext_lib.sys_path {
+            set_string(dep, "path", sys_path);
+        } else {
+            set_string(dep, "git", repo_url);
+        }
     }
 }
 
@@ -102,6 +121,14 @@ fn fill_in(root: &mut Table, env: &Env) {
         let package = upsert_table(root, "package");
         set_string(package, "build", "build.rs");
         // set_string(package, "version", "0.2.0");
+        if let Some(version) = env.config.min_rust_version {
+            // Both `rust-version` and the 2021 edition need Rust 1.56
+            if version >= Version(1, 56, 0) {
+                set_string(package, "rust-version", version.to_string());
+            } else if package.get("edition").and_then(Value::as_str) == Some("2021") {
+                set_string(package, "edition", "2018");
+            }
+        }
     }
 
     {
@@ -133,6 +160,11 @@ fn fill_in(root: &mut Table, env: &Env) {
             features.insert(version.to_feature(), Value::Array(prev_array));
             Some(version)
         });
+        if env.config.opaque_types == OpaqueTypes::Extern {
+            features
+                .entry("extern_types")
+                .or_insert_with(|| Value::Array(Vec::new()));
+        }
     }
 
     {
@@ -1,3 +1,5 @@
+use log::warn;
+
 use crate::{
     analysis::{rust_type::*, types::*},
     codegen::sys::{ffi_type::ffi_type, functions::function_signature},
@@ -19,6 +21,8 @@ pub struct Fields {
     /// specified GObject cfg condition
     pub cfg_condition: Option<String>,
     pub fields: Vec<FieldInfo>,
+    /// Bitfields collapsed into integer fields, accessed through methods.
+    pub bitfields: Vec<BitfieldInfo>,
 }
 
 pub struct FieldInfo {
@@ -30,6 +34,25 @@ pub struct FieldInfo {
     unsafe_access: bool,
     /// Include this field in Debug impl?
     pub debug: bool,
+    /// Comment written above the field, if any
+    pub comment: Option<String>,
+}
+
+pub struct BitfieldInfo {
+    /// C field name, used for the getter and the setter
+    pub name: String,
+    /// Name of the integer field storing the bitfield
+    pub storage: String,
+    /// Rust type name of the storage field
+    pub typ: String,
+    /// Size of the storage field in bits
+    pub storage_bits: u8,
+    /// Position of the lowest bit of the bitfield in the storage field
+    pub offset: u8,
+    /// Width of the bitfield
+    pub bits: u8,
+    /// Include this bitfield in Debug impl?
+    pub debug: bool,
 }
 
 impl Fields {
@@ -45,6 +68,22 @@ impl Fields {
 }
 
 impl FieldInfo {
+    /// Creates a function pointer field whose signature can't be generated.
+    ///
+    /// Only the size of the pointer matters for the layout, so the rest of the
+    /// struct is still usable to e.g. override virtual methods.
+    pub fn untyped_function(name: &str, debug: bool, unsafe_access: bool) -> Self {
+        Self {
+            name: name.to_owned(),
+            typ: "Option<unsafe extern \"C\" fn()>".to_owned(),
+            unsafe_access,
+            debug,
+            comment: Some(
+                "unsupported signature, cast to the right type before calling".to_owned(),
+            ),
+        }
+    }
+
     /// Generates a string that accesses the field in the context of &self
     /// receiver.
     pub fn access_str(&self) -> String {
@@ -57,7 +96,7 @@ impl FieldInfo {
 }
 
 pub fn from_record(env: &Env, record: &Record) -> Fields {
-    let (fields, truncated) = analyze_fields(env, false, &record.fields);
+    let (fields, bitfields, truncated) = analyze_fields(env, &record.c_type, false, &record.fields);
     let derives_copy = truncated.is_none() && record.derives_copy(&env.library);
     Fields {
         name: record.c_type.clone(),
@@ -67,11 +106,12 @@ pub fn from_record(env: &Env, record: &Record) -> Fields {
         kind: "struct",
         cfg_condition: get_gobject_cfg_condition(env, &record.name),
         fields,
+        bitfields,
     }
 }
 
 pub fn from_class(env: &Env, klass: &Class) -> Fields {
-    let (fields, truncated) = analyze_fields(env, false, &klass.fields);
+    let (fields, bitfields, truncated) = analyze_fields(env, &klass.c_type, false, &klass.fields);
     let derives_copy = truncated.is_none() && klass.derives_copy(&env.library);
     Fields {
         name: klass.c_type.clone(),
@@ -81,85 +121,215 @@ pub fn from_class(env: &Env, klass: &Class) -> Fields {
         kind: "struct",
         cfg_condition: get_gobject_cfg_condition(env, &klass.name),
         fields,
+        bitfields,
     }
 }
 
 pub fn from_union(env: &Env, union: &Union) -> Fields {
-    let (fields, truncated) = analyze_fields(env, true, &union.fields);
+    let name = union.c_type.as_ref().unwrap().clone();
+    let (fields, bitfields, truncated) = analyze_fields(env, &name, true, &union.fields);
     let derives_copy = truncated.is_none() && union.derives_copy(&env.library);
     Fields {
-        name: union.c_type.as_ref().unwrap().clone(),
+        name,
         external: union.is_external(&env.library),
         truncated,
         derives_copy,
         kind: "union",
         cfg_condition: None,
         fields,
+        bitfields,
     }
 }
 
+/// Fields of unions require unsafe access and their bitfields are never
+/// collapsed.
 fn analyze_fields(
     env: &Env,
-    unsafe_access: bool,
+    name: &str,
+    is_union: bool,
     fields: &[Field],
-) -> (Vec<FieldInfo>, Option<String>) {
+) -> (Vec<FieldInfo>, Vec<BitfieldInfo>, Option<String>) {
     let mut truncated = None;
     let mut infos = Vec::with_capacity(fields.len());
+    let mut bitfields = Vec::new();
+    // Consecutive bitfields of the same type and their Rust type
+    let mut run: Vec<&Field> = Vec::new();
+    let mut run_typ = String::new();
+    let mut run_start = 0;
 
-    let mut is_bitfield = false;
-    for field in fields {
-        // See IsIncomplete for &[Field].
-        if is_bitfield && field.bits.is_some() {
-            truncated = Some(format!("field {} has incomplete type", &field.name));
+    for (i, field) in fields.iter().enumerate() {
+        if let (Some(prev), Some(_)) = (run.last(), field.bits) {
+            // See IsIncomplete for &[Field].
+            if is_union || !bitfields_collapsible(prev, field) {
+                truncated = Some(format!("field {} has incomplete type", &field.name));
+                break;
+            }
+            run.push(field);
+            continue;
+        }
+        if let Some(reason) = ambiguous_bitfields(env, fields, run_start, &run) {
+            truncated = Some(reason);
             break;
         }
-        is_bitfield = field.bits.is_some();
+        push_bitfields(env, &mut infos, &mut bitfields, &run, &run_typ, is_union);
+        run.clear();
 
         let typ = match field_ffi_type(env, field) {
-            e @ Err(..) => {
+            Some(e @ Err(..)) => {
                 truncated = Some(e.into_string());
                 break;
             }
-            Ok(typ) => typ,
+            Some(Ok(typ)) => typ.into_string(),
+            None => {
+                warn!(
+                    "Function pointer field {}.{} has an unsupported signature, \
+                     generating it without parameters",
+                    name, field.name
+                );
+                infos.push(FieldInfo::untyped_function(
+                    &field.name,
+                    field_debug(env, field),
+                    is_union,
+                ));
+                continue;
+            }
         };
-        // Skip private fields from Debug impl. Ignore volatile as well,
-        // they are usually used as synchronization primites,
-        // so we wouldn't want to introduce additional reads.
-        let debug = !field.private && !field.is_volatile() && field.implements_debug(&env.library);
+        if field.bits.is_some() {
+            run.push(field);
+            run_typ = typ;
+            run_start = i;
+            continue;
+        }
 
         infos.push(FieldInfo {
             name: field.name.clone(),
-            typ: typ.into_string(),
-            debug,
-            unsafe_access,
+            typ,
+            debug: field_debug(env, field),
+            unsafe_access: is_union,
+            comment: None,
         });
     }
+    match ambiguous_bitfields(env, fields, run_start, &run) {
+        Some(reason) => {
+            truncated.get_or_insert(reason);
+        }
+        None => push_bitfields(env, &mut infos, &mut bitfields, &run, &run_typ, is_union),
+    }
+
+    (infos, bitfields, truncated)
+}
+
+/// Returns the reason for truncating the fields if the layout of the
+/// bitfields `run` starting at `fields[run_start]` depends on the compiler.
+fn ambiguous_bitfields(
+    env: &Env,
+    fields: &[Field],
+    run_start: usize,
+    run: &[&Field],
+) -> Option<String> {
+    if run.len() < 2 {
+        return None;
+    }
+    let prev = run_start.checked_sub(1).map(|i| &fields[i]);
+    let next = fields.get(run_start + run.len());
+    if bitfield_run_is_unambiguous(&env.library, prev, run, next) {
+        None
+    } else {
+        Some(format!(
+            "bitfields of {} have an ambiguous layout",
+            &run[0].name
+        ))
+    }
+}
+
+/// Skip private fields from Debug impl. Ignore volatile as well,
+/// they are usually used as synchronization primites,
+/// so we wouldn't want to introduce additional reads.
+fn field_debug(env: &Env, field: &Field) -> bool {
+    !field.private && !field.is_volatile() && field.implements_debug(&env.library)
+}
+
+/// Adds the fields storing consecutive bitfields `run` of Rust type `typ`.
+///
+/// A single bitfield is stored in a field of its own type, otherwise the
+/// bitfields are packed into `_bitfieldN` fields like C compilers do and
+/// accessed through methods.
+fn push_bitfields(
+    env: &Env,
+    infos: &mut Vec<FieldInfo>,
+    bitfields: &mut Vec<BitfieldInfo>,
+    run: &[&Field],
+    typ: &str,
+    unsafe_access: bool,
+) {
+    match run {
+        [] => return,
+        [field] => {
+            infos.push(FieldInfo {
+                name: field.name.clone(),
+                typ: typ.to_owned(),
+                debug: field_debug(env, field),
+                unsafe_access,
+                comment: None,
+            });
+            return;
+        }
+        _ => (),
+    }
 
-    (infos, truncated)
+    let storage_bits = run[0]
+        .c_type
+        .as_deref()
+        .and_then(bitfield_unit_bits)
+        .expect("collapsible bitfields");
+    let (positions, _) = pack_bitfields(run, storage_bits);
+    let first_storage = infos.len();
+    for (field, position) in run.iter().zip(positions) {
+        // Unnamed zero width bitfields only align the next bitfield
+        let (unit, offset) = match position {
+            Some(position) => position,
+            None => continue,
+        };
+        if first_storage + unit == infos.len() {
+            infos.push(FieldInfo {
+                name: format!("_bitfield{}", infos.len()),
+                typ: typ.to_owned(),
+                debug: false,
+                unsafe_access: false,
+                comment: None,
+            });
+        }
+        bitfields.push(BitfieldInfo {
+            name: field.name.clone(),
+            storage: infos.last().unwrap().name.clone(),
+            typ: typ.to_owned(),
+            storage_bits,
+            offset,
+            bits: field.bits.unwrap_or(0),
+            debug: field_debug(env, field),
+        });
+    }
 }
 
-fn field_ffi_type(env: &Env, field: &Field) -> Result {
+/// Returns `None` for a function pointer, like a virtual method of a class
+/// struct, whose signature can't be generated.
+fn field_ffi_type(env: &Env, field: &Field) -> Option<Result> {
     if field.is_incomplete(&env.library) {
-        return Err(TypeError::Ignored(format!(
+        return Some(Err(TypeError::Ignored(format!(
             "field {} has incomplete type",
             &field.name
-        )));
+        ))));
     }
     if let Some(ref c_type) = field.c_type {
-        ffi_type(env, field.typ, c_type)
+        Some(ffi_type(env, field.typ, c_type))
     } else if let Some(func) = env.library.type_(field.typ).maybe_ref_as::<Function>() {
         let (failure, signature) = function_signature(env, func, true);
-        let signature = format!("Option<unsafe extern \"C\" fn{signature}>");
-        if failure {
-            Err(TypeError::Unimplemented(signature))
-        } else {
-            Ok(signature.into())
-        }
+        (!failure).then(|| Ok(format!("Option<unsafe extern \"C\" fn{signature}>").into()))
     } else {
-        Err(TypeError::Ignored(format!(
+        Some(Err(TypeError::Ignored(format!(
             "field {} has empty c:type",
             &field.name
-        )))
+        ))))
     }
 }
 
@@ -8,7 +8,7 @@ use log::info;
 use super::{ffi_type::ffi_type, fields, functions, statics};
 use crate::{
     codegen::general::{self, cfg_condition, version_condition},
-    config::constants,
+    config::{constants, opaque_types::OpaqueTypes},
     env::Env,
     file_saver::*,
     library::*,
@@ -40,6 +40,12 @@ fn write_link_attr(w: &mut dyn Write, shared_libs: &[String]) -> Result<()> {
 fn generate_lib(w: &mut dyn Write, env: &Env) -> Result<()> {
     general::start_comments(w, &env.config)?;
     statics::begin(w)?;
+    if env.config.opaque_types == OpaqueTypes::Extern {
+        writeln!(
+            w,
+            "#![cfg_attr(feature = \"extern_types\", feature(extern_types))]"
+        )?;
+    }
 
     include_custom_modules(w, env)?;
     statics::after_extern_crates(w)?;
@@ -349,11 +355,19 @@ fn generate_unions(w: &mut dyn Write, env: &Env, unions: &[&Union]) -> Result<()
 
         let align = config.and_then(|c| c.align);
         let fields = fields::from_union(env, union);
-        generate_from_fields(w, &fields, align)?;
+        generate_from_fields(w, env, &fields, align)?;
     }
     Ok(())
 }
 
+/// Returns the format arguments printing the type name and address of `self`.
+fn self_pointer_format_args(env: &Env, name: &str) -> String {
+    general::format_args(
+        &format!("{name} @ {{self:p}}"),
+        env.config.supports_rust(general::INLINE_FORMAT_ARGS),
+    )
+}
+
 fn generate_debug_impl(w: &mut dyn Write, name: &str, impl_content: &str) -> Result<()> {
     writeln!(
         w,
@@ -379,21 +393,74 @@ fn generate_classes_structs(w: &mut dyn Write, env: &Env, classes: &[&Class]) ->
 
         let align = config.and_then(|c| c.align);
         let fields = fields::from_class(env, class);
-        generate_from_fields(w, &fields, align)?;
+        generate_from_fields(w, env, &fields, align)?;
     }
     Ok(())
 }
 
-fn generate_opaque_type(w: &mut dyn Write, name: &str) -> Result<()> {
-    writeln!(
-        w,
-        r#"#[repr(C)]
+/// Generates a type whose fields are unknown. An `extern type` needs a nightly
+/// compiler, so it is only declared with the `extern_types` feature of the
+/// crate, falling back to the phantom struct otherwise.
+fn generate_opaque_type(
+    w: &mut dyn Write,
+    opaque_types: OpaqueTypes,
+    name: &str,
+    cfg: Option<&String>,
+    align: Option<u32>,
+) -> Result<()> {
+    if opaque_types != OpaqueTypes::Extern {
+        cfg_condition(w, cfg, false, 0)?;
+        return write_opaque_type(w, opaque_types, name, align);
+    }
+    for (feature, opaque_types) in [
+        ("feature = \"extern_types\"", OpaqueTypes::Extern),
+        ("not(feature = \"extern_types\")", OpaqueTypes::Phantom),
+    ] {
+        let cfg = cfg.map_or_else(
+            || feature.to_owned(),
+            |cfg| format!("all({cfg}, {feature})"),
+        );
+        cfg_condition(w, Some(&cfg), false, 0)?;
+        write_opaque_type(w, opaque_types, name, align)?;
+    }
+    Ok(())
+}
+
+fn write_opaque_type(
+    w: &mut dyn Write,
+    opaque_types: OpaqueTypes,
+    name: &str,
+    align: Option<u32>,
+) -> Result<()> {
+    if let Some(align) = align.filter(|_| opaque_types != OpaqueTypes::Extern) {
+        writeln!(w, "#[repr(align({align}))]")?;
+    }
+    match opaque_types {
+        OpaqueTypes::Empty => writeln!(
+            w,
+            r#"#[repr(C)]
+pub struct {name} {{
+    _data: [u8; 0],
+}}
+"#
+        ),
+        OpaqueTypes::Phantom => writeln!(
+            w,
+            r#"#[repr(C)]
 pub struct {name} {{
     _data: [u8; 0],
     _marker: core::marker::PhantomData<(*mut u8, core::marker::PhantomPinned)>,
 }}
 "#
-    )
+        ),
+        OpaqueTypes::Extern => writeln!(
+            w,
+            r#"extern "C" {{
+    pub type {name};
+}}
+"#
+        ),
+    }
 }
 
 fn generate_interfaces_structs(
@@ -414,15 +481,20 @@ fn generate_interfaces_structs(
             .objects
             .get(&full_name)
             .and_then(|obj| obj.cfg_condition.as_ref());
-        cfg_condition(w, cfg_condition_, false, 0)?;
-        generate_opaque_type(w, &interface.c_type)?;
+        generate_opaque_type(
+            w,
+            env.config.opaque_types,
+            &interface.c_type,
+            cfg_condition_,
+            None,
+        )?;
         cfg_condition(w, cfg_condition_, false, 0)?;
         generate_debug_impl(
             w,
             &interface.c_type,
             &format!(
-                "write!(f, \"{name} @ {{self:p}}\")",
-                name = interface.c_type
+                "write!(f, {})",
+                self_pointer_format_args(env, &interface.c_type)
             ),
         )?;
     }
@@ -452,19 +524,19 @@ fn generate_records(w: &mut dyn Write, env: &Env, records: &[&Record]) -> Result
             // 4. ...
             // 5. Thus, we use custom generated GHookList.
             //    Hopefully someone will profit from all this.
-            generate_ghooklist(w)?;
+            generate_ghooklist(w, env)?;
         } else if record.disguised {
             generate_disguised(w, env, record)?;
         } else {
             let align = config.and_then(|c| c.align);
             let fields = fields::from_record(env, record);
-            generate_from_fields(w, &fields, align)?;
+            generate_from_fields(w, env, &fields, align)?;
         }
     }
     Ok(())
 }
 
-fn generate_ghooklist(w: &mut dyn Write) -> Result<()> {
+fn generate_ghooklist(w: &mut dyn Write, env: &Env) -> Result<()> {
     w.write_all(
         br#"#[repr(C)]
 #[derive(Copy, Clone)]
@@ -480,13 +552,12 @@ pub struct GHookList {
     pub dummy: [gpointer; 2],
 }
 
-impl ::std::fmt::Debug for GHookList {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-        write!(f, "GHookList @ {self:p}")
-    }
-}
-
 "#,
+    )?;
+    generate_debug_impl(
+        w,
+        "GHookList",
+        &format!("write!(f, {})", self_pointer_format_args(env, "GHookList")),
     )
 }
 
@@ -497,32 +568,100 @@ fn generate_disguised(w: &mut dyn Write, env: &Env, record: &Record) -> Result<(
         .objects
         .get(&full_name)
         .and_then(|obj| obj.cfg_condition.as_ref());
-    cfg_condition(w, cfg_condition_, false, 0)?;
-    generate_opaque_type(w, &format!("_{}", record.c_type))?;
+    generate_opaque_type(
+        w,
+        env.config.opaque_types,
+        &format!("_{}", record.c_type),
+        cfg_condition_,
+        None,
+    )?;
     cfg_condition(w, cfg_condition_, false, 0)?;
     writeln!(w, "pub type {name} = *mut _{name};", name = record.c_type)?;
     writeln!(w)
 }
 
+/// Generates getters and setters for the bitfields that were collapsed into
+/// integer fields. Big endian targets allocate the bitfields from the other
+/// end of the integer, so they are only available on little endian ones.
+fn generate_bitfield_accessors(w: &mut dyn Write, fields: &fields::Fields) -> Result<()> {
+    if fields.bitfields.is_empty() {
+        return Ok(());
+    }
+
+    cfg_condition(w, fields.cfg_condition.as_ref(), false, 0)?;
+    writeln!(w, "#[cfg(target_endian = \"little\")]")?;
+    writeln!(w, "impl {name} {{", name = &fields.name)?;
+    for (i, bitfield) in fields.bitfields.iter().enumerate() {
+        if i > 0 {
+            writeln!(w)?;
+        }
+        let storage = &bitfield.storage;
+        let typ = &bitfield.typ;
+        let (get, set) = if bitfield.bits == bitfield.storage_bits {
+            (
+                format!("self.{storage}"),
+                format!("self.{storage} = value;"),
+            )
+        } else {
+            // Shifting left first and then right sign-extends signed bitfields
+            let left = bitfield.storage_bits - bitfield.offset - bitfield.bits;
+            let right = bitfield.storage_bits - bitfield.bits;
+            let get = if left > 0 {
+                format!("(self.{storage} << {left}) >> {right}")
+            } else {
+                format!("self.{storage} >> {right}")
+            };
+            let bits = bitfield.bits;
+            let (mask, value) = match bitfield.offset {
+                0 => (format!("!(!0 << {bits})"), "value".to_owned()),
+                offset => (
+                    format!("!(!0 << {bits}) << {offset}"),
+                    format!("(value << {offset})"),
+                ),
+            };
+            let set = format!(
+                "let mask: {typ} = {mask};\n\
+                 \t\tself.{storage} = (self.{storage} & !mask) | ({value} & mask);"
+            );
+            (get, set)
+        };
+        writeln!(
+            w,
+            "\tpub fn {name}(&self) -> {typ} {{\n\t\t{get}\n\t}}\n",
+            name = mangle_keywords(&*bitfield.name)
+        )?;
+        writeln!(
+            w,
+            "\tpub fn set_{name}(&mut self, value: {typ}) {{\n\t\t{set}\n\t}}",
+            name = bitfield.name
+        )?;
+    }
+    writeln!(w, "}}\n")
+}
+
 fn generate_from_fields(
     w: &mut dyn Write,
+    env: &Env,
     fields: &fields::Fields,
     align: Option<u32>,
 ) -> Result<()> {
-    cfg_condition(w, fields.cfg_condition.as_ref(), false, 0)?;
-    if let Some(align) = align {
-        writeln!(w, "#[repr(align({align}))]")?;
-    }
-    let traits = fields.derived_traits().join(", ");
-    if !traits.is_empty() {
-        writeln!(w, "#[derive({traits})]")?;
-    }
     if fields.external {
-        // It would be nice to represent those using extern types
-        // from RFC 1861, once they are available in stable Rust.
-        // https://github.com/rust-lang/rust/issues/43467
-        generate_opaque_type(w, &fields.name)?;
+        generate_opaque_type(
+            w,
+            env.config.opaque_types,
+            &fields.name,
+            fields.cfg_condition.as_ref(),
+            align,
+        )?;
     } else {
+        cfg_condition(w, fields.cfg_condition.as_ref(), false, 0)?;
+        if let Some(align) = align {
+            writeln!(w, "#[repr(align({align}))]")?;
+        }
+        let traits = fields.derived_traits().join(", ");
+        if !traits.is_empty() {
+            writeln!(w, "#[derive({traits})]")?;
+        }
         writeln!(w, "#[repr(C)]")?;
         writeln!(
             w,
@@ -531,18 +670,14 @@ fn generate_from_fields(
             name = &fields.name
         )?;
         for field in &fields.fields {
-            writeln!(
-                w,
-                "\tpub {field_name}: {field_type},",
-                field_name = &field.name,
-                field_type = &field.typ
-            )?;
+            generate_field(w, field)?;
         }
         if let Some(ref reason) = fields.truncated {
             writeln!(w, "\t_truncated_record_marker: c_void,")?;
             writeln!(w, "\t// {reason}")?;
         }
         writeln!(w, "}}\n")?;
+        generate_bitfield_accessors(w, fields)?;
     }
 
     cfg_condition(w, fields.cfg_condition.as_ref(), false, 0)?;
@@ -555,23 +690,139 @@ fn generate_from_fields(
         w,
         "\tfn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {{"
     )?;
-    writeln!(
-        w,
-        "\t\tf.debug_struct(&format!(\"{name} @ {{self:p}}\"))",
-        name = &fields.name
-    )?;
+    let debug_bitfields: Vec<_> = if fields.external {
+        Vec::new()
+    } else {
+        fields.bitfields.iter().filter(|f| f.debug).collect()
+    };
+    if debug_bitfields.is_empty() {
+        writeln!(
+            w,
+            "\t\tf.debug_struct(&format!({}))",
+            self_pointer_format_args(env, &fields.name)
+        )?;
+    } else {
+        // The getters of the bitfields are only available on some targets
+        writeln!(
+            w,
+            "\t\tlet mut d = f.debug_struct(&format!({}));",
+            self_pointer_format_args(env, &fields.name)
+        )?;
+    }
+    // Chained calls when there's no bitfield, statements on `d` otherwise
+    let (field_prefix, field_suffix) = if debug_bitfields.is_empty() {
+        (" ", "")
+    } else {
+        ("d", ";")
+    };
     for field in fields.fields.iter().filter(|f| f.debug) {
         // TODO: We should generate debug for field manually if automatic one is not
         // available.
         writeln!(
             w,
-            "\t\t .field(\"{field_name}\", {field_get})",
+            "\t\t{field_prefix}.field(\"{field_name}\", {field_get}){field_suffix}",
             field_name = &field.name,
             field_get = &field.access_str()
         )?;
     }
-    writeln!(w, "\t\t .finish()")?;
+    for bitfield in &debug_bitfields {
+        writeln!(w, "\t\t#[cfg(target_endian = \"little\")]")?;
+        writeln!(
+            w,
+            "\t\td.field(\"{name}\", &self.{method}());",
+            name = &bitfield.name,
+            method = mangle_keywords(&*bitfield.name)
+        )?;
+    }
+    writeln!(w, "\t\t{field_prefix}.finish()")?;
     writeln!(w, "\t}}")?;
     writeln!(w, "}}")?;
     writeln!(w)
 }
+
+fn generate_field(w: &mut dyn Write, field: &fields::FieldInfo) -> Result<()> {
+    if let Some(ref comment) = field.comment {
+        writeln!(w, "\t// {comment}")?;
+    }
+    writeln!(
+        w,
+        "\tpub {field_name}: {field_type},",
+        field_name = &field.name,
+        field_type = &field.typ
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opaque_type(opaque_types: OpaqueTypes, cfg: Option<&str>, align: Option<u32>) -> String {
+        let mut w = Vec::new();
+        let cfg = cfg.map(ToOwned::to_owned);
+        generate_opaque_type(&mut w, opaque_types, "TestOpaque", cfg.as_ref(), align).unwrap();
+        String::from_utf8(w).unwrap()
+    }
+
+    #[test]
+    fn test_opaque_type_empty() {
+        assert_eq!(
+            opaque_type(OpaqueTypes::Empty, Some("unix"), Some(8)),
+            "#[cfg(unix)]
+#[repr(align(8))]
+#[repr(C)]
+pub struct TestOpaque {
+    _data: [u8; 0],
+}
+
+"
+        );
+    }
+
+    #[test]
+    fn test_opaque_type_phantom() {
+        assert_eq!(
+            opaque_type(OpaqueTypes::Phantom, None, None),
+            "#[repr(C)]
+pub struct TestOpaque {
+    _data: [u8; 0],
+    _marker: core::marker::PhantomData<(*mut u8, core::marker::PhantomPinned)>,
+}
+
+"
+        );
+    }
+
+    #[test]
+    fn test_opaque_type_extern() {
+        assert_eq!(
+            opaque_type(OpaqueTypes::Extern, Some("unix"), Some(8)),
+            "#[cfg(all(unix, feature = \"extern_types\"))]
+extern \"C\" {
+    pub type TestOpaque;
+}
+
+#[cfg(all(unix, not(feature = \"extern_types\")))]
+#[repr(align(8))]
+#[repr(C)]
+pub struct TestOpaque {
+    _data: [u8; 0],
+    _marker: core::marker::PhantomData<(*mut u8, core::marker::PhantomPinned)>,
+}
+
+"
+        );
+    }
+
+    #[test]
+    fn test_untyped_function_field() {
+        let mut w = Vec::new();
+        let field = fields::FieldInfo::untyped_function("finalize", true, false);
+        generate_field(&mut w, &field).unwrap();
+        assert_eq!(
+            String::from_utf8(w).unwrap(),
+            "\t// unsupported signature, cast to the right type before calling
+\tpub finalize: Option<unsafe extern \"C\" fn()>,
+"
+        );
+    }
+}
@@ -14,10 +14,11 @@ use crate::{
         bounds::Bounds, ffi_type::ffi_type, ref_mode::RefMode, rust_type::RustType,
         trampoline_parameters::*, trampolines::Trampoline, try_from_glib::TryFromGlib,
     },
+    config::panic_policy::PanicPolicy,
     consts::TYPE_PARAMETERS_START,
     env::Env,
     library,
-    nameutil::{use_glib_if_needed, use_gtk_type},
+    nameutil::{use_glib_if_needed, use_glib_type, use_gtk_type},
     traits::IntoString,
     writer::primitives::tabs,
 };
@@ -57,12 +58,61 @@ pub fn generate(
     writeln!(w, "{prepend}\tlet f: &F = &*(f as *const F);")?;
     transformation_vars(w, env, analysis, &prepend)?;
     let call = trampoline_call_func(env, analysis, in_trait);
-    writeln!(w, "{prepend}\t{call}")?;
+    if let Some(policy) = env.config.trampoline_panic {
+        guarded_call(w, env, analysis, &call, policy, &prepend)?;
+    } else {
+        writeln!(w, "{prepend}\t{call}")?;
+    }
     writeln!(w, "{prepend}}}")?;
 
     Ok(())
 }
 
+/// Writes the closure call so that a panic doesn't unwind into C code.
+fn guarded_call(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &Trampoline,
+    call: &str,
+    policy: PanicPolicy,
+    prepend: &str,
+) -> Result<()> {
+    let on_panic = on_panic(
+        env,
+        policy,
+        &analysis.name,
+        analysis.ret.typ != Default::default(),
+    );
+    writeln!(
+        w,
+        "{prepend}\tmatch std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {call})) {{"
+    )?;
+    writeln!(w, "{prepend}\t\tOk(ret) => ret,")?;
+    writeln!(w, "{prepend}\t\tErr(_) => {on_panic},")?;
+    writeln!(w, "{prepend}\t}}")
+}
+
+/// Returns the expression handling a panic caught in the trampoline `name`.
+pub fn on_panic(env: &Env, policy: PanicPolicy, name: &str, returns_value: bool) -> String {
+    let abort = "std::process::abort()";
+    match policy {
+        PanicPolicy::Abort => abort.to_owned(),
+        PanicPolicy::Log => {
+            let log = format!(
+                "{}(\"{}\", \"Panic in `{}`\")",
+                use_glib_type(env, "g_critical!"),
+                env.config.library_name,
+                name,
+            );
+            if returns_value {
+                format!("{{ {log}; {abort} }}")
+            } else {
+                log
+            }
+        }
+    }
+}
+
 pub fn func_string(
     env: &Env,
     analysis: &Trampoline,
@@ -93,6 +143,55 @@ pub fn func_string(
     }
 }
 
+/// The closure type of `connect_*_local` functions, without the concurrency bounds
+pub fn local_func_string(
+    env: &Env,
+    analysis: &Trampoline,
+    replace_self_bound: Option<impl AsRef<str>>,
+) -> String {
+    let param_str = func_parameters(env, analysis, replace_self_bound, true);
+    let return_str = func_returns(env, analysis);
+
+    format!("Fn({param_str}){return_str} + 'static")
+}
+
+/// The item type of `_stream` functions and the expression building an item
+/// from the closure arguments `arg1`, `arg2`, ...
+pub fn stream_item(env: &Env, analysis: &Trampoline) -> (String, String) {
+    let (types, values): (Vec<_>, Vec<_>) = analysis
+        .parameters
+        .rust_parameters
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(pos, par)| {
+            let s = func_parameter(env, par, &analysis.bounds);
+            if let Some(inner) = s.strip_prefix('&') {
+                (
+                    format!("<{inner} as ToOwned>::Owned"),
+                    format!("ToOwned::to_owned(arg{pos})"),
+                )
+            } else if let Some(inner) = s.strip_prefix("Option<&").and_then(|s| s.strip_suffix('>'))
+            {
+                (
+                    format!("Option<<{inner} as ToOwned>::Owned>"),
+                    format!("arg{pos}.map(ToOwned::to_owned)"),
+                )
+            } else {
+                (s, format!("arg{pos}"))
+            }
+        })
+        .unzip();
+    let tuple = |items: Vec<String>| {
+        if items.len() == 1 {
+            format!("({},)", items[0])
+        } else {
+            format!("({})", items.join(", "))
+        }
+    };
+    (tuple(types), tuple(values))
+}
+
 fn func_parameters(
     env: &Env,
     analysis: &Trampoline,
@@ -3,6 +3,7 @@ use crate::{
         self, conversion_type::ConversionType, rust_type::RustType, try_from_glib::TryFromGlib,
     },
     chunk::conversion_from_glib::Mode,
+    codegen::{general::escape_string, return_value::lazy_list_name},
     env::Env,
     library,
     nameutil::use_glib_type,
@@ -68,6 +69,9 @@ impl TranslateFromGlib for Mode {
                             (format!("FromGlibPtrContainer::{}", trans.0), trans.1)
                         }
                     }
+                    library::Type::Array(inner_tid) => {
+                        garray_to_vec(env, *inner_tid, self.transfer, false)
+                    }
                     _ => trans,
                 }
             }
@@ -107,12 +111,19 @@ impl TranslateFromGlib for analysis::return_value::Info {
                         assert!(*par.lib_par.nullable);
                         (
                             prefix,
-                            format!(
-                                "{}.{}.ok_or_else(|| {}(\"{}\"))",
-                                from_glib_xxx.1,
-                                suffix_function,
-                                use_glib_type(env, "bool_error!"),
-                                msg
+                            ok_or_bool_error(
+                                &format!("{}.{}", from_glib_xxx.1, suffix_function),
+                                &use_glib_type(env, "bool_error!"),
+                                msg,
+                            ),
+                        )
+                    } else if let Some(ref msg) = self.null_panic_message {
+                        assert!(*par.lib_par.nullable);
+                        (
+                            prefix,
+                            expect_non_null(
+                                &format!("{}.{}", from_glib_xxx.1, suffix_function),
+                                msg,
                             ),
                         )
                     } else {
@@ -124,22 +135,39 @@ impl TranslateFromGlib for analysis::return_value::Info {
                     format!(", \"{}\")", self.bool_return_is_error.as_ref().unwrap()),
                 ),
                 None if self.nullable_return_is_error.is_some() => {
-                    let res = Mode::from(par).translate_from_glib_as_function(env, array_length);
-                    if let Some(ref msg) = self.nullable_return_is_error {
-                        assert!(*par.lib_par.nullable);
-                        (
-                            format!("Option::<_>::{}", res.0),
-                            format!(
-                                "{}.ok_or_else(|| {}(\"{}\"))",
-                                res.1,
-                                use_glib_type(env, "bool_error!"),
-                                msg
-                            ),
-                        )
-                    } else {
-                        res
-                    }
+                    assert!(*par.lib_par.nullable);
+                    let res = nullable_from_glib(env, par, array_length);
+                    (
+                        res.0,
+                        ok_or_bool_error(
+                            &res.1,
+                            &use_glib_type(env, "bool_error!"),
+                            self.nullable_return_is_error.as_ref().unwrap(),
+                        ),
+                    )
+                }
+                None if self.null_panic_message.is_some() => {
+                    assert!(*par.lib_par.nullable);
+                    let res = nullable_from_glib(env, par, array_length);
+                    (
+                        res.0,
+                        expect_non_null(&res.1, self.null_panic_message.as_ref().unwrap()),
+                    )
                 }
+                // `glib::List` and `glib::SList` take over the list as is
+                None if self.lazy_list => lazy_list_from_glib(
+                    &use_glib_type(env, lazy_list_name(&env.library, par.lib_par.typ)),
+                    par.lib_par.transfer,
+                ),
+                None if self.invalid_char_is_none => ("char::from_u32(".into(), ")".into()),
+                None if self.borrowed => ("from_glib_borrow(".into(), ")".into()),
+                None if self.integer_from_pointer => (
+                    String::new(),
+                    format!(
+                        " as {}",
+                        RustType::try_new(env, par.lib_par.typ).into_string()
+                    ),
+                ),
                 None => Mode::from(par).translate_from_glib_as_function(env, array_length),
             },
             None => (String::new(), ";".into()),
@@ -147,6 +175,91 @@ impl TranslateFromGlib for analysis::return_value::Info {
     }
 }
 
+/// Converts a nullable pointer into an `Option`, to be unwrapped by the caller.
+fn nullable_from_glib(
+    env: &Env,
+    par: &analysis::Parameter,
+    array_length: Option<&str>,
+) -> (String, String) {
+    match env.type_(par.lib_par.typ) {
+        library::Type::Array(inner_tid) => {
+            garray_to_vec(env, *inner_tid, par.lib_par.transfer, true)
+        }
+        _ => {
+            let res = Mode::from(par).translate_from_glib_as_function(env, array_length);
+            (format!("Option::<_>::{}", res.0), res.1)
+        }
+    }
+}
+
+/// Turns the nullable value converted by `post` into a `Result`, failing
+/// with `msg` on `NULL`.
+fn ok_or_bool_error(post: &str, bool_error: &str, msg: &str) -> String {
+    format!(
+        "{post}.ok_or_else(|| {bool_error}(\"{}\"))",
+        escape_string(msg)
+    )
+}
+
+/// Unwraps the nullable value converted by `post`, panicking with `msg` on
+/// `NULL`.
+fn expect_non_null(post: &str, msg: &str) -> String {
+    format!("{post}.expect(\"{}\")", escape_string(msg))
+}
+
+/// `GArray` has no `FromGlibPtrContainer` implementation, so its elements are
+/// copied out inline and the array released according to the transfer.
+fn garray_to_vec(
+    env: &Env,
+    inner_tid: library::TypeId,
+    transfer: library::Transfer,
+    nullable: bool,
+) -> (String, String) {
+    let inner = RustType::builder(env, inner_tid).try_build().into_string();
+    let unref =
+        (transfer != library::Transfer::None).then(|| use_glib_type(env, "ffi::g_array_unref"));
+    garray_closure(
+        &use_glib_type(env, "ffi::GArray"),
+        &inner,
+        unref.as_deref(),
+        nullable,
+    )
+}
+
+/// A closure copying the elements out of a `GArray`, `nullable` ones are
+/// returned as `Option<Vec<T>>` and others as an empty `Vec<T>` if `NULL`.
+fn garray_closure(
+    garray: &str,
+    inner: &str,
+    unref: Option<&str>,
+    nullable: bool,
+) -> (String, String) {
+    let (null, ret) = if nullable {
+        ("None", "Some(v)")
+    } else {
+        ("Vec::new()", "v")
+    };
+    let unref = unref.map_or_else(String::new, |unref| format!(" {unref}(array);"));
+    (
+        format!(
+            "(|array: *mut {garray}| {{ if array.is_null() {{ return {null}; }} \
+             let v = if (*array).len == 0 {{ Vec::new() }} else {{ \
+             std::slice::from_raw_parts((*array).data as *const {inner}, (*array).len as usize).to_vec() }};\
+             {unref} {ret} }})("
+        ),
+        ")".into(),
+    )
+}
+
+/// The conversions of `glib::List` and `glib::SList` are inherent functions,
+/// which copy or reference the elements of a container transfer list.
+fn lazy_list_from_glib(list: &str, transfer: library::Transfer) -> (String, String) {
+    (
+        format!("{}::{}", list, from_glib_xxx(transfer, None).0),
+        ")".into(),
+    )
+}
+
 fn from_glib_xxx(transfer: library::Transfer, array_length: Option<&str>) -> (String, String) {
     use crate::library::Transfer;
     let good_print = |name: &str| format!(", {name}.assume_init() as _)");
@@ -166,3 +279,67 @@ fn from_glib_xxx(transfer: library::Transfer, array_length: Option<&str>) -> (St
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::Transfer;
+
+    #[test]
+    fn test_lazy_list_from_glib() {
+        let from_glib = |transfer| {
+            let (prefix, suffix) = lazy_list_from_glib("glib::SList", transfer);
+            format!("{prefix}ret{suffix}")
+        };
+        assert_eq!(
+            from_glib(Transfer::None),
+            "glib::SList::from_glib_none(ret)"
+        );
+        assert_eq!(
+            from_glib(Transfer::Full),
+            "glib::SList::from_glib_full(ret)"
+        );
+        assert_eq!(
+            from_glib(Transfer::Container),
+            "glib::SList::from_glib_container(ret)"
+        );
+    }
+
+    #[test]
+    fn test_nullable_return_value() {
+        assert_eq!(
+            ok_or_bool_error(")", "glib::bool_error!", r#"Failed to "load" C:\"#),
+            r#").ok_or_else(|| glib::bool_error!("Failed to \"load\" C:\\"))"#
+        );
+        assert_eq!(
+            expect_non_null(").map(|o| o.unsafe_cast())", "Failed to create"),
+            r#").map(|o| o.unsafe_cast()).expect("Failed to create")"#
+        );
+        assert_eq!(
+            expect_non_null(")", r#"no "window""#),
+            r#").expect("no \"window\"")"#
+        );
+    }
+
+    #[test]
+    fn test_garray_closure() {
+        let from_glib = |unref, nullable| {
+            let (prefix, suffix) = garray_closure("glib::ffi::GArray", "i32", unref, nullable);
+            format!("{prefix}ret{suffix}")
+        };
+        assert_eq!(
+            from_glib(None, false),
+            "(|array: *mut glib::ffi::GArray| { if array.is_null() { return Vec::new(); } \
+             let v = if (*array).len == 0 { Vec::new() } else { \
+             std::slice::from_raw_parts((*array).data as *const i32, (*array).len as usize)\
+             .to_vec() }; v })(ret)"
+        );
+        assert_eq!(
+            from_glib(Some("glib::ffi::g_array_unref"), true),
+            "(|array: *mut glib::ffi::GArray| { if array.is_null() { return None; } \
+             let v = if (*array).len == 0 { Vec::new() } else { \
+             std::slice::from_raw_parts((*array).data as *const i32, (*array).len as usize)\
+             .to_vec() }; glib::ffi::g_array_unref(array); Some(v) })(ret)"
+        );
+    }
+}
@@ -11,7 +11,10 @@ impl TranslateToGlib for TransformationType {
     fn translate_to_glib(&self) -> String {
         use self::TransformationType::*;
         match *self {
-            ToGlibDirect { ref name } => name.clone(),
+            ToGlibDirect {
+                ref name,
+                ref pointer_cast,
+            } => format!("{name}{pointer_cast}"),
             ToGlibScalar {
                 ref name,
                 needs_into,
@@ -50,6 +53,8 @@ impl TranslateToGlib for TransformationType {
             ToGlibUnknown { ref name } => format!("/*Unknown conversion*/{name}"),
             ToSome(ref name) => format!("Some({name})"),
             IntoRaw(ref name) => format!("Box_::into_raw({name}) as *mut _"),
+            ToGlibInOutScalar { ref name } => format!("&mut {name}_"),
+            ToGlibArray { ref name, .. } => format!("{name}_"),
             _ => unreachable!("Unexpected transformation type {:?}", self),
         }
     }
@@ -94,3 +99,52 @@ fn to_glib_xxx(
         Container => ("".into(), ".to_glib_container().0"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_glib_direct() {
+        let direct = |pointer_cast: &str| TransformationType::ToGlibDirect {
+            name: "handle".into(),
+            pointer_cast: pointer_cast.into(),
+        };
+        assert_eq!(direct("").translate_to_glib(), "handle");
+        assert_eq!(direct(" as *mut _").translate_to_glib(), "handle as *mut _");
+    }
+
+    #[test]
+    fn test_to_glib_to_value() {
+        let to_value = |to_glib_extra: &str| TransformationType::ToGlibPointer {
+            name: "value".into(),
+            instance_parameter: false,
+            transfer: Transfer::None,
+            ref_mode: RefMode::ByRef,
+            to_glib_extra: to_glib_extra.into(),
+            explicit_target_type: String::new(),
+            pointer_cast: String::new(),
+            in_trait: false,
+            nullable: false,
+            move_: false,
+        };
+        assert_eq!(
+            to_value(".to_value()").translate_to_glib(),
+            "value.to_value().to_glib_none().0"
+        );
+        assert_eq!(
+            to_value(".map(|v| v.to_value())").translate_to_glib(),
+            "value.map(|v| v.to_value()).to_glib_none().0"
+        );
+    }
+
+    #[test]
+    fn test_to_glib_array() {
+        let array = TransformationType::ToGlibArray {
+            name: "values".into(),
+            element_type: "i32".into(),
+            transfer: Transfer::None,
+        };
+        assert_eq!(array.translate_to_glib(), "values_");
+    }
+}
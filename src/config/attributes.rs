@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+/// What a custom GIR `<attribute>` annotation is used for during generation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AttributeBehavior {
+    Rename,     // the value is the new Rust name
+    Nullable,   // the value is a boolean for the return value
+    Skip,       // the function is ignored if the value is true
+    VersionEnd, // the value is the version the function was removed in
+}
+
+impl FromStr for AttributeBehavior {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rename" => Ok(Self::Rename),
+            "nullable" => Ok(Self::Nullable),
+            "skip" => Ok(Self::Skip),
+            "version_end" => Ok(Self::VersionEnd),
+            _ => Err(format!("Wrong attribute behavior '{s}'")),
+        }
+    }
+}
+
+/// Parses a boolean attribute value, spelled like in Rust or like in GIR files.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bool() {
+        assert_eq!(parse_bool("true"), Some(true));
+        assert_eq!(parse_bool("1"), Some(true));
+        assert_eq!(parse_bool("false"), Some(false));
+        assert_eq!(parse_bool("0"), Some(false));
+        assert_eq!(parse_bool("yes"), None);
+    }
+}
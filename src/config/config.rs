@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs,
     ops::Index,
     path::{Component, Path, PathBuf},
@@ -9,11 +9,17 @@ use std::{
 use log::warn;
 
 use super::{
+    attributes::AttributeBehavior,
     external_libraries::{read_external_libraries, ExternalLibrary},
-    gobjects, WorkMode,
+    gobjects,
+    opaque_types::OpaqueTypes,
+    panic_policy::PanicPolicy,
+    wrappers::{read_wrappers, CustomWrapper},
+    WorkMode,
 };
 use crate::{
     analysis::namespaces::{self, Namespace, NsId},
+    codegen::Visibility,
     config::error::TomlHelper,
     env::Env,
     git::{repo_hash, repo_remote_url, toplevel},
@@ -94,6 +100,9 @@ impl GirVersion {
 pub struct Config {
     pub work_mode: WorkMode,
     pub girs_dirs: Vec<PathBuf>,
+    /// Explicit `.gir` files of libraries (`name-version`), which are then not
+    /// searched in `girs_dirs`
+    pub gir_files: HashMap<String, PathBuf>,
     // Version in girs_dirs, detected by git
     pub girs_version: Vec<GirVersion>,
     pub library_name: String,
@@ -113,7 +122,7 @@ pub struct Config {
     pub concurrency: library::Concurrency,
     pub single_version_file: Option<PathBuf>,
     pub generate_display_trait: bool,
-    pub trust_return_value_nullability: bool,
+    pub trust_return_value_nullability: gobjects::TrustReturnValueNullability,
     pub docs_rs_features: Vec<String>,
     pub disable_format: bool,
     pub split_build_rs: bool,
@@ -124,6 +133,57 @@ pub struct Config {
     /// to another doc source, for example when builds on docs.rs
     /// are limited due to license issues.
     pub external_docs_url: Option<String>,
+    /// Cargo feature of the generated crate behind which every generated
+    /// wrapper logs its FFI call with `log::trace!`.
+    pub trace_feature: Option<String>,
+    /// Whether commented functions are preceded by a `//TODO` line with the
+    /// reason they can't be generated
+    pub generate_skip_reasons: bool,
+    /// Maps names of GIR `<attribute>` annotations to what they do
+    pub attribute_behaviors: HashMap<String, AttributeBehavior>,
+    /// Overrides the pkg-config package names of the main namespace
+    pub packages: Vec<String>,
+    /// Overrides the C headers of the main namespace
+    pub c_includes: Vec<String>,
+    /// Additional prefixes removed from the names of all functions
+    pub strip_prefixes: Vec<String>,
+    /// Guards closure calls in trampolines against panics
+    pub trampoline_panic: Option<PanicPolicy>,
+    /// Wrapper macros that objects can use instead of `glib::wrapper!`
+    pub wrappers: HashMap<String, CustomWrapper>,
+    /// Generate round-trip tests for enums and flags
+    pub generate_tests: bool,
+    /// Maps C platform types like `time_t` to the Rust types used for them
+    pub platform_types: BTreeMap<String, String>,
+    /// Included namespaces that are allowed to have no `.gir` file
+    pub ignore_missing_includes: Vec<String>,
+    /// Namespaces whose types are all manual unless configured otherwise
+    pub manual_namespaces: Vec<String>,
+    /// Whether `get_` prefixes are removed from the names of getters
+    pub rename_getters: bool,
+    /// Whether getters returning a boolean are renamed to `is_*`
+    pub rename_bool_getters: bool,
+    /// Base URL of the C documentation of the main namespace
+    pub doc_base_url: Option<String>,
+    /// Whether the `*Ext` traits have a sealed supertrait, so that they can't
+    /// be implemented outside of the crate
+    pub sealed_ext_traits: bool,
+    /// Path of the sys crate of the main namespace in the generated code,
+    /// `ffi` by default
+    pub sys_crate_path: Option<String>,
+    /// Name of the sys crate re-exported as `ffi` by the generated `mod.rs`
+    pub ffi_reexport: Option<String>,
+    /// Representation of opaque types in sys crates
+    pub opaque_types: OpaqueTypes,
+    /// Whether getters of nullable properties panic on NULL instead of
+    /// returning an `Option`
+    pub panic_on_null_properties: bool,
+    /// Oldest Rust version the generated crates declare to compile with
+    pub min_rust_version: Option<Version>,
+    /// File listing the API changes since the previous generation run
+    pub api_changelog: Option<PathBuf>,
+    /// Fail if the generated code removes or changes public items
+    pub semver_check: bool,
 }
 
 impl Config {
@@ -131,6 +191,7 @@ impl Config {
         config_file: S,
         work_mode: W,
         girs_dirs: &[String],
+        gir_file_args: &[String],
         library_name: S,
         library_version: S,
         target_path: S,
@@ -138,6 +199,7 @@ impl Config {
         make_backup: bool,
         show_statistics: bool,
         disable_format: bool,
+        semver_check: bool,
     ) -> Result<Self, String>
     where
         S: Into<Option<&'a str>>,
@@ -218,6 +280,17 @@ impl Config {
             (Some(a), Some(b)) => (a.to_owned(), b.to_owned()),
         };
 
+        let mut gir_files = read_gir_files(&toml, &config_dir)?;
+        // Files given on the command line are relative to the current directory
+        // and are for the main library unless prefixed with `name-version=`
+        for arg in gir_file_args.iter().filter(|x| !x.is_empty()) {
+            let (library, path) = match arg.split_once('=') {
+                Some((library, path)) => (library.to_owned(), path),
+                None => (format!("{library_name}-{library_version}"), arg.as_str()),
+            };
+            gir_files.insert(library, PathBuf::from(path));
+        }
+
         let target_path: PathBuf = match target_path.into() {
             Some("") | None => {
                 let path = toml.lookup_str("options.target_path", "No target path specified")?;
@@ -236,10 +309,10 @@ impl Config {
             None if work_mode == WorkMode::Normal => target_path.join("src").join("auto"),
             None => target_path.join("src"),
         };
-        if work_mode == WorkMode::Normal && auto_path.exists() {
-            std::fs::remove_dir_all(&auto_path)
-                .map_err(|e| format!("remove_dir_all failed: {e:?}"))?;
-        }
+        let api_changelog = match toml.lookup("options.api_changelog") {
+            Some(v) => Some(config_dir.join(v.as_result_str("options.api_changelog")?)),
+            None => None,
+        };
 
         let doc_target_path: PathBuf = match doc_target_path.into() {
             Some("") | None => match toml.lookup("options.doc_target_path") {
@@ -259,11 +332,21 @@ impl Config {
             None => true,
         };
 
-        let trust_return_value_nullability =
-            match toml.lookup("options.trust_return_value_nullability") {
+        let trust_return_value_nullability = gobjects::TrustReturnValueNullability {
+            default: match toml.lookup("options.trust_return_value_nullability") {
                 Some(v) => v.as_result_bool("options.trust_return_value_nullability")?,
                 None => false,
-            };
+            },
+            namespaces: read_namespace_trust_return_value_nullability(&toml)?,
+        };
+
+        let visibility = match toml.lookup("options.visibility") {
+            Some(v) => v
+                .as_result_str("options.visibility")?
+                .parse::<Visibility>()
+                .map_err(|err| err.to_string())?,
+            None => Default::default(),
+        };
 
         let mut docs_rs_features = Vec::new();
         for v in match toml.lookup("options.docs_rs_features") {
@@ -286,7 +369,8 @@ impl Config {
                     concurrency,
                     generate_display_trait,
                     generate_builder,
-                    trust_return_value_nullability,
+                    &trust_return_value_nullability,
+                    visibility,
                 )
             })
             .unwrap_or_default();
@@ -296,7 +380,8 @@ impl Config {
             concurrency,
             generate_display_trait,
             generate_builder,
-            trust_return_value_nullability,
+            &trust_return_value_nullability,
+            visibility,
         );
 
         let external_libraries = read_external_libraries(&toml)?;
@@ -351,10 +436,86 @@ impl Config {
         let lib_version_overrides = read_lib_version_overrides(&toml)?;
         let feature_dependencies = read_feature_dependencies(&toml)?;
         let external_docs_url = read_external_docs_url(&toml)?;
+        let generate_skip_reasons = match toml.lookup("options.generate_skip_reasons") {
+            Some(v) => v.as_result_bool("options.generate_skip_reasons")?,
+            None => false,
+        };
+        let trace_feature = match toml.lookup("options.trace_feature") {
+            Some(v) => Some(v.as_result_str("options.trace_feature")?.to_owned()),
+            None => None,
+        };
+        let attribute_behaviors = read_attribute_behaviors(&toml)?;
+        let packages = read_string_vec(&toml, "options.packages")?;
+        let c_includes = read_string_vec(&toml, "options.c_includes")?;
+        let strip_prefixes = read_string_vec(&toml, "options.strip_prefixes")?;
+        let trampoline_panic = match toml.lookup("options.trampoline_panic") {
+            Some(v) => Some(v.as_result_str("options.trampoline_panic")?.parse()?),
+            None => None,
+        };
+        let min_rust_version = read_min_rust_version(&toml)?;
+        let panic_on_null_properties = match toml.lookup("options.panic_on_null_properties") {
+            Some(v) => v.as_result_bool("options.panic_on_null_properties")?,
+            None => false,
+        };
+        let opaque_types = match toml.lookup("options.opaque_types") {
+            Some(v) => v.as_result_str("options.opaque_types")?.parse()?,
+            None => Default::default(),
+        };
+        let generate_tests = match toml.lookup("options.generate_tests") {
+            Some(v) => v.as_result_bool("options.generate_tests")?,
+            None => false,
+        };
+        let platform_types = read_platform_types(&toml)?;
+        let ignore_missing_includes = read_string_vec(&toml, "options.ignore_missing_includes")?;
+        let manual_namespaces = read_string_vec(&toml, "options.manual_namespaces")?;
+        if manual_namespaces.contains(&library_name) {
+            return Err(format!(
+                "options.manual_namespaces can't contain the library itself ({library_name})"
+            ));
+        }
+        let rename_getters = match toml.lookup("options.rename_getters") {
+            Some(v) => v.as_result_bool("options.rename_getters")?,
+            None => true,
+        };
+        let rename_bool_getters = match toml.lookup("options.rename_bool_getters") {
+            Some(v) => v.as_result_bool("options.rename_bool_getters")?,
+            None => true,
+        };
+        let doc_base_url = match toml.lookup("options.doc_base_url") {
+            Some(v) => Some(v.as_result_str("options.doc_base_url")?.to_owned()),
+            None => None,
+        };
+        let sealed_ext_traits = match toml.lookup("options.sealed_ext_traits") {
+            Some(v) => v.as_result_bool("options.sealed_ext_traits")?,
+            None => false,
+        };
+        let ffi_reexport = match toml.lookup("options.ffi_reexport") {
+            Some(v) => Some(v.as_result_str("options.ffi_reexport")?.to_owned()),
+            None => None,
+        };
+        let sys_crate_path = match toml.lookup("options.sys_crate_path") {
+            Some(v) => Some(v.as_result_str("options.sys_crate_path")?.to_owned()),
+            // The re-export is glob imported into the crate root with the
+            // rest of the generated code
+            None if ffi_reexport.is_some() => Some("crate::ffi".to_owned()),
+            None => None,
+        };
+        let wrappers = read_wrappers(&toml)?;
+        for object in objects.values() {
+            if let Some(ref wrapper) = object.wrapper {
+                if !wrappers.contains_key(wrapper) {
+                    return Err(format!(
+                        "Unknown wrapper `{wrapper}` for object {}, it has to be defined in options.wrappers",
+                        object.name
+                    ));
+                }
+            }
+        }
 
         Ok(Self {
             work_mode,
             girs_dirs,
+            gir_files,
             girs_version,
             library_name,
             library_version,
@@ -380,9 +541,50 @@ impl Config {
             lib_version_overrides,
             feature_dependencies,
             external_docs_url,
+            trace_feature,
+            generate_skip_reasons,
+            attribute_behaviors,
+            packages,
+            c_includes,
+            strip_prefixes,
+            trampoline_panic,
+            wrappers,
+            generate_tests,
+            platform_types,
+            ignore_missing_includes,
+            manual_namespaces,
+            rename_getters,
+            rename_bool_getters,
+            doc_base_url,
+            sealed_ext_traits,
+            sys_crate_path,
+            ffi_reexport,
+            opaque_types,
+            panic_on_null_properties,
+            min_rust_version,
+            api_changelog,
+            semver_check,
         })
     }
 
+    /// Returns the value of the first GIR `<attribute>` that is configured
+    /// with the given behavior.
+    pub fn attribute_value<'a>(
+        &self,
+        attributes: &'a [(String, String)],
+        behavior: AttributeBehavior,
+    ) -> Option<&'a str> {
+        attributes
+            .iter()
+            .find(|(name, _)| self.attribute_behaviors.get(name) == Some(&behavior))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the generated code may use features stabilized in `version`.
+    pub fn supports_rust(&self, version: Version) -> bool {
+        self.min_rust_version.map_or(true, |v| v >= version)
+    }
+
     pub fn library_full_name(&self) -> String {
         format!("{}-{}", self.library_name, self.library_version)
     }
@@ -414,10 +616,30 @@ impl Config {
         }
     }
 
+    /// Returns the base URL of the C documentation of the given namespace
+    pub fn doc_base_url(&self, env: &Env, ns_id: NsId) -> Option<&str> {
+        if ns_id == namespaces::MAIN {
+            self.doc_base_url.as_deref()
+        } else {
+            let namespace = env.namespaces.index(ns_id);
+            self.find_ext_library(namespace)
+                .and_then(|lib| lib.doc_base_url.as_deref())
+        }
+    }
+
     pub fn resolve_type_ids(&mut self, library: &Library) {
         gobjects::resolve_type_ids(&mut self.objects, library);
     }
 
+    /// Removes the files generated by the previous run.
+    pub fn remove_auto_path(&self) -> Result<(), String> {
+        if self.work_mode == WorkMode::Normal && self.auto_path.exists() {
+            std::fs::remove_dir_all(&self.auto_path)
+                .map_err(|e| format!("remove_dir_all failed: {e:?}"))?;
+        }
+        Ok(())
+    }
+
     pub fn check_disable_format(&mut self) {
         if !self.disable_format && !crate::fmt::check_fmt() {
             warn!("Formatter not found, options.disable_format set to true");
@@ -549,10 +771,227 @@ fn read_external_docs_url(toml: &toml::Value) -> Result<Option<String>, String>
     )
 }
 
+/// The generated code always uses `impl Trait` arguments (Rust 1.26) and
+/// `#[doc(alias)]` (Rust 1.48), which can't be replaced.
+const MIN_SUPPORTED_RUST_VERSION: Version = Version(1, 48, 0);
+
+fn read_min_rust_version(toml: &toml::Value) -> Result<Option<Version>, String> {
+    let version: Version = match toml.lookup("options.min_rust_version") {
+        Some(v) => v.as_result_str("options.min_rust_version")?.parse()?,
+        None => return Ok(None),
+    };
+    if version < MIN_SUPPORTED_RUST_VERSION {
+        return Err(format!(
+            "options.min_rust_version can't be lower than {MIN_SUPPORTED_RUST_VERSION}, \
+             the generated code needs `#[doc(alias)]`"
+        ));
+    }
+    Ok(Some(version))
+}
+
+fn read_gir_files(
+    toml: &toml::Value,
+    config_dir: &Path,
+) -> Result<HashMap<String, PathBuf>, String> {
+    let table = match toml.lookup("options.gir_files") {
+        Some(v) => v
+            .as_table()
+            .ok_or_else(|| "options.gir_files expected to be a table".to_string())?,
+        None => return Ok(Default::default()),
+    };
+
+    table
+        .iter()
+        .map(|(library, path)| {
+            let path = path
+                .as_str()
+                .ok_or_else(|| format!("options.gir_files.{library} expected to be a string"))?;
+            Ok((library.clone(), config_dir.join(path)))
+        })
+        .collect()
+}
+
+fn read_string_vec(toml: &toml::Value, option: &str) -> Result<Vec<String>, String> {
+    match toml.lookup(option) {
+        Some(a) => a
+            .as_result_vec(option)?
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(str::to_owned)
+                    .ok_or_else(|| format!("{option} expected to be array of string"))
+            })
+            .collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn read_attribute_behaviors(
+    toml: &toml::Value,
+) -> Result<HashMap<String, AttributeBehavior>, String> {
+    let table = match toml.lookup("options.attributes") {
+        Some(v) => v
+            .as_table()
+            .ok_or_else(|| "options.attributes expected to be a table".to_string())?,
+        None => return Ok(Default::default()),
+    };
+
+    table
+        .iter()
+        .map(|(name, behavior)| {
+            let behavior: AttributeBehavior =
+                behavior.as_result_str("options.attributes")?.parse()?;
+            Ok((name.clone(), behavior))
+        })
+        .collect()
+}
+
+fn read_platform_types(toml: &toml::Value) -> Result<BTreeMap<String, String>, String> {
+    let table = match toml.lookup("options.platform_types") {
+        Some(v) => v
+            .as_table()
+            .ok_or_else(|| "options.platform_types expected to be a table".to_string())?,
+        None => return Ok(Default::default()),
+    };
+
+    table
+        .iter()
+        .map(|(c_type, rust_type)| {
+            let rust_type = rust_type.as_result_str("options.platform_types")?;
+            Ok((c_type.clone(), rust_type.to_owned()))
+        })
+        .collect()
+}
+
+fn read_namespace_trust_return_value_nullability(
+    toml: &toml::Value,
+) -> Result<HashMap<String, bool>, String> {
+    let option = "options.namespace_trust_return_value_nullability";
+    let table = match toml.lookup(option) {
+        Some(v) => v
+            .as_table()
+            .ok_or_else(|| format!("{option} expected to be a table"))?,
+        None => return Ok(Default::default()),
+    };
+
+    table
+        .iter()
+        .map(|(namespace, trust)| Ok((namespace.clone(), trust.as_result_bool(option)?)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_read_string_vec() {
+        let toml: toml::Value = toml::from_str(
+            r#"
+[options]
+packages = ["gtk4", "gtk4-x11"]
+c_includes = [0]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            read_string_vec(&toml, "options.packages").unwrap(),
+            ["gtk4", "gtk4-x11"]
+        );
+        assert!(read_string_vec(&toml, "options.c_includes").is_err());
+        assert!(read_string_vec(&toml, "options.missing")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_read_attribute_behaviors() {
+        let toml: toml::Value = toml::from_str(
+            r#"
+[options]
+attributes = { "rust.name" = "rename", "rust.skip" = "skip" }
+"#,
+        )
+        .unwrap();
+        let behaviors = read_attribute_behaviors(&toml).unwrap();
+        assert_eq!(behaviors.len(), 2);
+        assert_eq!(behaviors["rust.name"], AttributeBehavior::Rename);
+        assert_eq!(behaviors["rust.skip"], AttributeBehavior::Skip);
+
+        let toml: toml::Value = toml::from_str(
+            r#"
+[options]
+attributes = { "rust.name" = "unknown" }
+"#,
+        )
+        .unwrap();
+        assert!(read_attribute_behaviors(&toml).is_err());
+    }
+
+    #[test]
+    fn test_read_platform_types() {
+        let toml: toml::Value = toml::from_str(
+            r#"
+[options.platform_types]
+time_t = "libc::time_t"
+"#,
+        )
+        .unwrap();
+        let platform_types = read_platform_types(&toml).unwrap();
+        assert_eq!(platform_types.len(), 1);
+        assert_eq!(platform_types["time_t"], "libc::time_t");
+
+        let toml: toml::Value = toml::from_str(
+            r#"
+[options.platform_types]
+time_t = 8
+"#,
+        )
+        .unwrap();
+        assert!(read_platform_types(&toml).is_err());
+    }
+
+    #[test]
+    fn test_read_min_rust_version() {
+        let toml: toml::Value = toml::from_str("[options]\nmin_rust_version = \"1.56\"").unwrap();
+        assert_eq!(
+            read_min_rust_version(&toml).unwrap(),
+            Some(Version(1, 56, 0))
+        );
+
+        let toml: toml::Value = toml::from_str("[options]\nmin_rust_version = \"1.40\"").unwrap();
+        assert!(read_min_rust_version(&toml).is_err());
+
+        let toml: toml::Value = toml::from_str("[options]").unwrap();
+        assert_eq!(read_min_rust_version(&toml).unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_gir_files() {
+        let toml: toml::Value = toml::from_str(
+            r#"
+[options.gir_files]
+"Gtk-4.0" = "../build/gtk/Gtk-4.0.gir"
+"#,
+        )
+        .unwrap();
+        let gir_files = read_gir_files(&toml, Path::new("bindings")).unwrap();
+        assert_eq!(gir_files.len(), 1);
+        assert_eq!(
+            gir_files["Gtk-4.0"],
+            Path::new("bindings/../build/gtk/Gtk-4.0.gir")
+        );
+
+        let toml: toml::Value = toml::from_str(
+            r#"
+[options.gir_files]
+"Gtk-4.0" = 4
+"#,
+        )
+        .unwrap();
+        assert!(read_gir_files(&toml, Path::new("")).is_err());
+    }
+
     #[test]
     fn test_make_single_version_file() {
         let target_path = Path::new("/tmp/glib");
@@ -9,6 +9,14 @@ pub struct ExternalLibrary {
     pub crate_name: String,
     pub lib_name: String,
     pub min_version: Option<Version>,
+    /// Name of the sys package to depend on, if it doesn't follow the usual
+    /// `<crate>-sys` scheme.
+    pub sys_package: Option<String>,
+    /// Local path of the sys package, used instead of a git dependency.
+    pub sys_path: Option<String>,
+    /// Base URL of the C documentation, used to link items that aren't
+    /// generated in Rust.
+    pub doc_base_url: Option<String>,
 }
 
 pub fn read_external_libraries(toml: &toml::Value) -> Result<Vec<ExternalLibrary>, String> {
@@ -23,6 +31,9 @@ pub fn read_external_libraries(toml: &toml::Value) -> Result<Vec<ExternalLibrary
                     crate_name: crate_name_.clone(),
                     lib_name: crate_name_,
                     min_version: None,
+                    sys_package: None,
+                    sys_path: None,
+                    doc_base_url: None,
                     namespace,
                 }
             })
@@ -44,11 +55,27 @@ pub fn read_external_libraries(toml: &toml::Value) -> Result<Vec<ExternalLibrary
                     .get("min_version")
                     .map(|v| v.as_str().expect("min required version must be a string"))
                     .map(|v| Version::from_str(v).expect("Invalid version number"));
+                let sys_package = info.get("sys_package").map(|v| {
+                    v.as_str()
+                        .expect("sys package name must be a string")
+                        .to_owned()
+                });
+                let sys_path = info
+                    .get("sys_path")
+                    .map(|v| v.as_str().expect("sys path must be a string").to_owned());
+                let doc_base_url = info.get("doc_base_url").map(|v| {
+                    v.as_str()
+                        .expect("doc base url must be a string")
+                        .to_owned()
+                });
                 let lib = ExternalLibrary {
                     namespace: namespace.to_owned(),
                     crate_name: crate_name_,
                     lib_name: crate_name(namespace),
                     min_version,
+                    sys_package,
+                    sys_path,
+                    doc_base_url,
                 };
                 external_libraries.push(lib);
             } else if let Some(namespace) = custom_lib.1.as_str() {
@@ -58,6 +85,9 @@ pub fn read_external_libraries(toml: &toml::Value) -> Result<Vec<ExternalLibrary
                     crate_name: crate_name_.clone(),
                     lib_name: crate_name(custom_lib.1.as_str().expect("No custom lib name set")),
                     min_version: None,
+                    sys_package: None,
+                    sys_path: None,
+                    doc_base_url: None,
                 };
                 external_libraries.push(lib);
             } else {
@@ -107,6 +137,9 @@ other-lib="OtherLib"
                 crate_name: "glib".to_owned(),
                 lib_name: "glib".to_owned(),
                 min_version: None,
+                sys_package: None,
+                sys_path: None,
+                doc_base_url: None,
             }
         );
         assert_eq!(
@@ -116,6 +149,9 @@ other-lib="OtherLib"
                 crate_name: "gdk".to_owned(),
                 lib_name: "gdk".to_owned(),
                 min_version: None,
+                sys_package: None,
+                sys_path: None,
+                doc_base_url: None,
             }
         );
         assert_eq!(
@@ -125,6 +161,9 @@ other-lib="OtherLib"
                 crate_name: "gdk_pixbuf".to_owned(),
                 lib_name: "gdk_pixbuf".to_owned(),
                 min_version: None,
+                sys_package: None,
+                sys_path: None,
+                doc_base_url: None,
             }
         );
         // Sorted alphabetically
@@ -135,6 +174,9 @@ other-lib="OtherLib"
                 crate_name: "coollib".to_owned(),
                 lib_name: "cool_lib".to_owned(),
                 min_version: None,
+                sys_package: None,
+                sys_path: None,
+                doc_base_url: None,
             }
         );
         assert_eq!(
@@ -144,6 +186,9 @@ other-lib="OtherLib"
                 crate_name: "other-lib".to_owned(),
                 lib_name: "other_lib".to_owned(),
                 min_version: None,
+                sys_package: None,
+                sys_path: None,
+                doc_base_url: None,
             }
         );
     }
@@ -167,6 +212,9 @@ OtherLib={min_version = "0.4.0"}
                 crate_name: "coollib".to_owned(),
                 lib_name: "cool_lib".to_owned(),
                 min_version: Some(Version::from_str("0.3.0").unwrap()),
+                sys_package: None,
+                sys_path: None,
+                doc_base_url: None,
             }
         );
         assert_eq!(
@@ -176,7 +224,50 @@ OtherLib={min_version = "0.4.0"}
                 crate_name: "other_lib".to_owned(),
                 lib_name: "other_lib".to_owned(),
                 min_version: Some(Version::from_str("0.4.0").unwrap()),
+                sys_package: None,
+                sys_path: None,
+                doc_base_url: None,
             }
         );
     }
+
+    #[test]
+    fn test_read_external_libraries_with_sys_package() {
+        let toml = toml(
+            r#"
+[external_libraries]
+CoolLib={crate = "coollib", sys_package = "cool-ffi", sys_path = "../cool-ffi"}
+"#,
+        );
+        let libs = read_external_libraries(&toml).unwrap();
+
+        assert_eq!(
+            libs[0],
+            ExternalLibrary {
+                namespace: "CoolLib".to_owned(),
+                crate_name: "coollib".to_owned(),
+                lib_name: "cool_lib".to_owned(),
+                min_version: None,
+                sys_package: Some("cool-ffi".to_owned()),
+                sys_path: Some("../cool-ffi".to_owned()),
+                doc_base_url: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_external_libraries_with_doc_base_url() {
+        let toml = toml(
+            r#"
+[external_libraries]
+GLib={doc_base_url = "https://docs.gtk.org/glib"}
+"#,
+        );
+        let libs = read_external_libraries(&toml).unwrap();
+
+        assert_eq!(
+            libs[0].doc_base_url.as_deref(),
+            Some("https://docs.gtk.org/glib")
+        );
+    }
 }
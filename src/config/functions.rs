@@ -12,9 +12,9 @@ use super::{
     string_type::StringType,
 };
 use crate::{
-    analysis::safety_assertion_mode::SafetyAssertionMode,
+    analysis::{ref_mode::RefMode, safety_assertion_mode::SafetyAssertionMode},
     codegen::Visibility,
-    library::{Infallible, Mandatory, Nullable},
+    library::{Infallible, Mandatory, Nullable, ParameterScope},
     version::Version,
 };
 
@@ -67,7 +67,18 @@ pub struct Parameter {
     pub infallible: Option<Infallible>,
     pub length_of: Option<String>,
     pub string_type: Option<StringType>,
+    pub ref_mode: Option<RefMode>,
+    pub type_name: Option<String>,
     pub callback_parameters: CallbackParameters,
+    // for callbacks: names of the user data and destroy notify parameters
+    pub user_data: Option<String>,
+    pub destroy: Option<String>,
+    pub scope: Option<ParameterScope>,
+    // for `GValue` parameters: take `&dyn ToValue` instead of `&glib::Value`
+    pub to_value: bool,
+    // classes or interfaces the parameter has to be or implement in addition
+    // to its type
+    pub additional_bounds: Vec<String>,
 }
 
 impl Parse for Parameter {
@@ -93,7 +104,14 @@ impl Parse for Parameter {
                 "move",
                 "pattern",
                 "string_type",
+                "ref_mode",
+                "type",
                 "callback_parameter",
+                "user_data",
+                "destroy",
+                "scope",
+                "to_value",
+                "additional_bounds",
             ],
             &format!("function parameter {object_name}"),
         );
@@ -103,6 +121,18 @@ impl Parse for Parameter {
             .and_then(Value::as_bool)
             .unwrap_or(false);
         let move_ = toml.lookup("move").and_then(Value::as_bool);
+        let to_value = toml
+            .lookup("to_value")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let additional_bounds = toml
+            .lookup_vec("additional_bounds", "Invalid additional_bounds")
+            .map(|v| {
+                v.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
         let nullable = toml
             .lookup("nullable")
             .and_then(Value::as_bool)
@@ -134,19 +164,73 @@ impl Parse for Parameter {
                 }
             },
         };
+        let ref_mode = toml.lookup("ref_mode").and_then(Value::as_str);
+        let ref_mode = match ref_mode {
+            None => None,
+            Some(val) => match RefMode::from_str(val) {
+                Ok(val) => Some(val),
+                Err(error_str) => {
+                    error!(
+                        "Error: {} for parameter for object {}",
+                        error_str, object_name
+                    );
+                    None
+                }
+            },
+        };
+        let type_name = toml
+            .lookup("type")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
+        if string_type.is_some() && type_name.is_some() {
+            error!(
+                "\"string_type\" and \"type\" parameters can't be passed at the same time for \
+                 object {}, only \"type\" will be applied in this case",
+                object_name
+            );
+        }
         let callback_parameters =
             CallbackParameters::parse(toml.lookup("callback_parameter"), object_name);
+        let user_data = toml
+            .lookup("user_data")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
+        let destroy = toml
+            .lookup("destroy")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
+        let scope = toml.lookup("scope").and_then(Value::as_str);
+        let scope = match scope {
+            None => None,
+            Some(val) => match ParameterScope::from_str(val) {
+                Ok(val) => Some(val),
+                Err(error_str) => {
+                    error!(
+                        "Error: {} for parameter for object {}",
+                        error_str, object_name
+                    );
+                    None
+                }
+            },
+        };
 
         Some(Self {
             ident,
             constant,
             move_,
+            to_value,
+            additional_bounds,
             nullable,
             mandatory,
             infallible,
             length_of,
             string_type,
+            ref_mode,
+            type_name,
             callback_parameters,
+            user_data,
+            destroy,
+            scope,
         })
     }
 }
@@ -166,9 +250,14 @@ pub struct Return {
     pub infallible: Option<Infallible>,
     pub bool_return_is_error: Option<String>,
     pub nullable_return_is_error: Option<String>,
+    pub null_panic_message: Option<String>,
     pub use_return_for_result: Option<bool>,
     pub string_type: Option<StringType>,
     pub type_name: Option<String>,
+    pub lazy_list: Option<bool>,
+    pub invalid_char_is_none: Option<bool>,
+    pub borrowed: Option<bool>,
+    pub after_outs: Option<bool>,
 }
 
 impl Return {
@@ -180,9 +269,14 @@ impl Return {
                 infallible: None,
                 bool_return_is_error: None,
                 nullable_return_is_error: None,
+                null_panic_message: None,
                 use_return_for_result: None,
                 string_type: None,
                 type_name: None,
+                lazy_list: None,
+                invalid_char_is_none: None,
+                borrowed: None,
+                after_outs: None,
             };
         }
 
@@ -194,9 +288,14 @@ impl Return {
                 "infallible",
                 "bool_return_is_error",
                 "nullable_return_is_error",
+                "null_panic_message",
                 "use_return_for_result",
                 "string_type",
                 "type",
+                "lazy_list",
+                "invalid_char_is_none",
+                "borrowed",
+                "after_outs",
             ],
             "return",
         );
@@ -218,6 +317,10 @@ impl Return {
             .lookup("nullable_return_is_error")
             .and_then(Value::as_str)
             .map(ToOwned::to_owned);
+        let null_panic_message = v
+            .lookup("null_panic_message")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
         let use_return_for_result = v.lookup("use_return_for_result").and_then(Value::as_bool);
         let string_type = v.lookup("string_type").and_then(Value::as_str);
         let string_type = match string_type {
@@ -241,6 +344,10 @@ impl Return {
                 object_name
             );
         }
+        let lazy_list = v.lookup("lazy_list").and_then(Value::as_bool);
+        let invalid_char_is_none = v.lookup("invalid_char_is_none").and_then(Value::as_bool);
+        let borrowed = v.lookup("borrowed").and_then(Value::as_bool);
+        let after_outs = v.lookup("after_outs").and_then(Value::as_bool);
 
         Self {
             nullable,
@@ -248,9 +355,14 @@ impl Return {
             infallible,
             bool_return_is_error,
             nullable_return_is_error,
+            null_panic_message,
             use_return_for_result,
             string_type,
             type_name,
+            lazy_list,
+            invalid_char_is_none,
+            borrowed,
+            after_outs,
         }
     }
 }
@@ -291,6 +403,23 @@ pub struct Function {
     pub is_constructor: Option<bool>,
     pub assertion: Option<SafetyAssertionMode>,
     pub generate_doc: bool,
+    pub inherent: bool,
+    pub deprecated_version: Option<Version>,
+    pub version_end: Option<Version>,
+    pub must_use: Option<bool>,
+    pub body: Option<String>,
+    /// Overrides the `trust_return_value_nullability` of the object
+    pub trust_return_value_nullability: Option<bool>,
+    /// Overrides `options.rename_getters`
+    pub rename_getter: Option<bool>,
+    /// Overrides `options.rename_bool_getters`
+    pub rename_bool_getter: Option<bool>,
+    /// Name of the type to move the function to, or `*` for the global
+    /// functions
+    pub move_to: Option<String>,
+    /// Whether an unsafe `_raw` variant taking the FFI types is generated if
+    /// the function can't be generated
+    pub generate_raw: bool,
 }
 
 impl Parse for Function {
@@ -328,6 +457,16 @@ impl Parse for Function {
                 "assertion",
                 "visibility",
                 "generate_doc",
+                "inherent",
+                "deprecated_version",
+                "version_end",
+                "must_use",
+                "body",
+                "trust_return_value_nullability",
+                "rename_getter",
+                "rename_bool_getter",
+                "move_to",
+                "generate_raw",
             ],
             &format!("function {object_name}"),
         );
@@ -425,6 +564,40 @@ impl Parse for Function {
             .lookup("generate_doc")
             .and_then(Value::as_bool)
             .unwrap_or(true);
+        let inherent = toml.lookup("inherent").and_then(Value::as_bool);
+        if inherent == Some(false) {
+            error!(
+                "`inherent = false` isn't supported for {}, methods of types without subclasses can't be moved to a trait",
+                object_name
+            );
+        }
+        let inherent = inherent.unwrap_or(false);
+        let deprecated_version = toml
+            .lookup("deprecated_version")
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse().ok());
+        let version_end = toml
+            .lookup("version_end")
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse().ok());
+        let must_use = toml.lookup("must_use").and_then(Value::as_bool);
+        let body = toml
+            .lookup("body")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
+        let trust_return_value_nullability = toml
+            .lookup("trust_return_value_nullability")
+            .and_then(Value::as_bool);
+        let rename_getter = toml.lookup("rename_getter").and_then(Value::as_bool);
+        let rename_bool_getter = toml.lookup("rename_bool_getter").and_then(Value::as_bool);
+        let move_to = toml
+            .lookup("move_to")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
+        let generate_raw = toml
+            .lookup("generate_raw")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
         Some(Self {
             ident,
             status,
@@ -445,6 +618,16 @@ impl Parse for Function {
             is_constructor,
             assertion,
             generate_doc,
+            inherent,
+            deprecated_version,
+            version_end,
+            must_use,
+            body,
+            trust_return_value_nullability,
+            rename_getter,
+            rename_bool_getter,
+            move_to,
+            generate_raw,
         })
     }
 }
@@ -921,6 +1104,23 @@ name = "func1"
         assert_eq!(f.ret.infallible, Some(Infallible(false)));
     }
 
+    #[test]
+    fn parse_return_null_panic_message() {
+        let toml = toml(
+            r#"
+name = "func1"
+    [return]
+    null_panic_message = "Failed to create"
+"#,
+        );
+        let f = Function::parse(&toml, "a");
+        let f = f.unwrap();
+        assert_eq!(
+            f.ret.null_panic_message.as_deref(),
+            Some("Failed to create")
+        );
+    }
+
     #[test]
     fn parse_parameter_infallible_default() {
         let toml = toml(
@@ -967,4 +1167,363 @@ name = "func1"
         let param1 = &f.parameters[0];
         assert_eq!(param1.infallible, Some(Infallible(false)));
     }
+
+    #[test]
+    fn function_parse_unsafe() {
+        let r = toml(
+            r#"
+name = "func1"
+unsafe = true
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert!(f.unsafe_);
+
+        let r = toml(
+            r#"
+name = "func1"
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert!(!f.unsafe_);
+    }
+
+    #[test]
+    fn function_parse_return_bool_return_is_error() {
+        let toml = toml(
+            r#"
+name = "func1"
+[return]
+bool_return_is_error = "Failed to do it"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(
+            f.ret.bool_return_is_error.as_deref(),
+            Some("Failed to do it")
+        );
+    }
+
+    #[test]
+    fn function_parse_inherent() {
+        let r = toml(
+            r#"
+name = "func1"
+inherent = true
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert!(f.inherent);
+
+        let r = toml(
+            r#"
+name = "func1"
+inherent = false
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert!(!f.inherent);
+
+        let r = toml(
+            r#"
+name = "func1"
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert!(!f.inherent);
+    }
+
+    #[test]
+    fn function_parse_deprecated_version() {
+        let toml = toml(
+            r#"
+name = "func1"
+deprecated_version = "3.20"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(f.deprecated_version, Some(Version(3, 20, 0)));
+    }
+
+    #[test]
+    fn function_parse_parameter_ref_mode() {
+        let toml = toml(
+            r#"
+name = "func1"
+[[parameter]]
+name = "par1"
+ref_mode = "none"
+[[parameter]]
+name = "par2"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        let pars = f.parameters;
+        assert_eq!(pars[0].ref_mode, Some(RefMode::None));
+        assert_eq!(pars[1].ref_mode, None);
+    }
+
+    #[test]
+    fn function_parse_parameter_type() {
+        let toml = toml(
+            r#"
+name = "func1"
+[[parameter]]
+name = "par1"
+type = "gsize"
+[[parameter]]
+name = "par2"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        let pars = f.parameters;
+        assert_eq!(pars[0].type_name, Some("gsize".to_owned()));
+        assert_eq!(pars[1].type_name, None);
+    }
+
+    #[test]
+    fn function_parse_return_type() {
+        let toml = toml(
+            r#"
+name = "func1"
+[return]
+type = "guintptr"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(f.ret.type_name, Some("guintptr".to_owned()));
+    }
+
+    #[test]
+    fn function_parse_version_end() {
+        let toml = toml(
+            r#"
+name = "func1"
+version_end = "3.20"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(f.version_end, Some(Version(3, 20, 0)));
+    }
+
+    #[test]
+    fn function_parse_must_use() {
+        let toml = toml(
+            r#"
+name = "func1"
+must_use = true
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(f.must_use, Some(true));
+
+        let r = toml(
+            r#"
+name = "func1"
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert_eq!(f.must_use, None);
+    }
+
+    #[test]
+    fn function_parse_trust_return_value_nullability() {
+        let r = toml(
+            r#"
+name = "func1"
+trust_return_value_nullability = true
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert_eq!(f.trust_return_value_nullability, Some(true));
+
+        let r = toml(
+            r#"
+name = "func1"
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert_eq!(f.trust_return_value_nullability, None);
+    }
+
+    #[test]
+    fn function_parse_rename_getter() {
+        let r = toml(
+            r#"
+name = "get_value"
+rename_getter = false
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert_eq!(f.rename_getter, Some(false));
+        assert_eq!(f.rename_bool_getter, None);
+
+        let r = toml(
+            r#"
+name = "get_visible"
+rename_bool_getter = false
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert_eq!(f.rename_getter, None);
+        assert_eq!(f.rename_bool_getter, Some(false));
+    }
+
+    #[test]
+    fn function_parse_body() {
+        let toml = toml(
+            r#"
+name = "func1"
+body = "unimplemented!()"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(f.body.as_deref(), Some("unimplemented!()"));
+    }
+
+    #[test]
+    fn function_parse_return_use_return_for_result() {
+        let r = toml(
+            r#"
+name = "func1"
+[return]
+use_return_for_result = true
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert_eq!(f.ret.use_return_for_result, Some(true));
+
+        let r = toml(
+            r#"
+name = "func1"
+"#,
+        );
+        let f = Function::parse(&r, "a").unwrap();
+        assert_eq!(f.ret.use_return_for_result, None);
+    }
+
+    #[test]
+    fn function_parse_return_lazy_list() {
+        let toml = toml(
+            r#"
+name = "func1"
+[return]
+lazy_list = true
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(f.ret.lazy_list, Some(true));
+    }
+
+    #[test]
+    fn function_parse_return_invalid_char_is_none() {
+        let toml = toml(
+            r#"
+name = "func1"
+[return]
+invalid_char_is_none = true
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(f.ret.invalid_char_is_none, Some(true));
+    }
+
+    #[test]
+    fn function_parse_return_borrowed() {
+        let toml = toml(
+            r#"
+name = "func1"
+[return]
+borrowed = true
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(f.ret.borrowed, Some(true));
+    }
+
+    #[test]
+    fn function_parse_return_after_outs() {
+        let toml = toml(
+            r#"
+name = "func1"
+[return]
+after_outs = true
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(f.ret.after_outs, Some(true));
+    }
+
+    #[test]
+    fn parse_parameter_callback_indices() {
+        let toml = toml(
+            r#"
+name = "func1"
+    [[parameter]]
+    name = "func"
+    user_data = "data"
+    destroy = "notify"
+    scope = "notified"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        let param = &f.parameters[0];
+        assert_eq!(param.user_data.as_deref(), Some("data"));
+        assert_eq!(param.destroy.as_deref(), Some("notify"));
+        assert_eq!(param.scope, Some(ParameterScope::Notified));
+    }
+
+    #[test]
+    fn parse_parameter_to_value() {
+        let toml = toml(
+            r#"
+name = "func1"
+    [[parameter]]
+    name = "value"
+    to_value = true
+    [[parameter]]
+    name = "other"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert!(f.parameters[0].to_value);
+        assert!(!f.parameters[1].to_value);
+    }
+
+    #[test]
+    fn parse_parameter_additional_bounds() {
+        let toml = toml(
+            r#"
+name = "func1"
+    [[parameter]]
+    name = "widget"
+    additional_bounds = ["Gtk.Scrollable", "Gtk.Orientable"]
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert_eq!(
+            f.parameters[0].additional_bounds,
+            ["Gtk.Scrollable", "Gtk.Orientable"]
+        );
+    }
+
+    #[test]
+    fn function_parse_generate_raw() {
+        let toml = toml(
+            r#"
+name = "func1"
+generate_raw = true
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert!(f.generate_raw);
+
+        let toml = toml(
+            r#"
+name = "func1"
+"#,
+        );
+        let f = Function::parse(&toml, "a").unwrap();
+        assert!(!f.generate_raw);
+    }
 }
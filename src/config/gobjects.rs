@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     str::FromStr,
     sync::Arc,
 };
@@ -80,6 +80,8 @@ pub struct GObject {
     pub final_type: Option<bool>,
     pub fundamental_type: Option<bool>,
     pub exhaustive: bool,
+    /// Implement `std::error::Error` for an error domain
+    pub generate_error_trait: bool,
     pub trait_name: Option<String>,
     pub child_properties: Option<ChildProperties>,
     pub concurrency: library::Concurrency,
@@ -99,6 +101,34 @@ pub struct GObject {
     pub visibility: Visibility,
     pub default_value: Option<String>,
     pub generate_doc: bool,
+    pub rust_path: Option<String>,
+    pub extra_imports: Vec<String>,
+    pub prologue: Option<String>,
+    pub epilogue: Option<String>,
+    /// Forces `g_boxed_copy`/`g_boxed_free` if `true`, or the record's own
+    /// `copy`/`free` functions if `false`
+    pub use_boxed_functions: Option<bool>,
+    pub error_quark: Option<String>,
+    pub get_type: Option<String>,
+    pub strip_prefixes: Vec<String>,
+    pub version_end: Option<Version>,
+    pub local_signals: bool,
+    pub wrapper: Option<String>,
+    pub prerequisites: Vec<String>,
+    pub lazy_lists: bool,
+    pub signal_streams: bool,
+    /// Value related traits not implemented for an enum or flags
+    pub skip_value_traits: Vec<String>,
+    /// Implement `Debug` for a record by printing its scalar fields
+    pub debug_fields: bool,
+    /// Generate a record as a `Copy` Rust struct with the same layout
+    pub plain_struct: bool,
+    /// Submodule of the generated code the type is placed in
+    pub module_path: Option<String>,
+    /// Implement `Default` by calling a constructor without parameters
+    pub generate_default: bool,
+    /// Rust name of the constructor used for `Default` instead of `new`
+    pub default_constructor: Option<String>,
 }
 
 impl Default for GObject {
@@ -120,6 +150,7 @@ impl Default for GObject {
             final_type: None,
             fundamental_type: None,
             exhaustive: false,
+            generate_error_trait: false,
             trait_name: None,
             child_properties: None,
             concurrency: Default::default(),
@@ -139,6 +170,26 @@ impl Default for GObject {
             visibility: Default::default(),
             default_value: None,
             generate_doc: true,
+            rust_path: None,
+            extra_imports: Vec::new(),
+            prologue: None,
+            epilogue: None,
+            use_boxed_functions: None,
+            error_quark: None,
+            get_type: None,
+            strip_prefixes: Vec::new(),
+            version_end: None,
+            local_signals: false,
+            wrapper: None,
+            prerequisites: Vec::new(),
+            lazy_lists: false,
+            signal_streams: false,
+            skip_value_traits: Vec::default(),
+            debug_fields: false,
+            plain_struct: false,
+            module_path: None,
+            generate_default: true,
+            default_constructor: None,
         }
     }
 }
@@ -146,22 +197,54 @@ impl Default for GObject {
 // TODO: ?change to HashMap<String, GStatus>
 pub type GObjects = BTreeMap<String, GObject>;
 
+/// Traits implemented for enums and flags with a `get_type` function that can
+/// be listed in `skip_value_traits`
+pub const VALUE_TRAITS: &[&str] = &[
+    "StaticType",
+    "HasParamSpec",
+    "ValueType",
+    "FromValue",
+    "ToValue",
+    "From",
+];
+
+/// Default of `trust_return_value_nullability` for the objects
+#[derive(Clone, Debug, Default)]
+pub struct TrustReturnValueNullability {
+    pub default: bool,
+    /// Overrides of the default for the objects of a namespace
+    pub namespaces: HashMap<String, bool>,
+}
+
+impl TrustReturnValueNullability {
+    pub fn for_object(&self, name: &str) -> bool {
+        name.split_once('.')
+            .and_then(|(namespace, _)| self.namespaces.get(namespace))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
 pub fn parse_toml(
     toml_objects: &Value,
     concurrency: library::Concurrency,
     generate_display_trait: bool,
     generate_builder: bool,
-    trust_return_value_nullability: bool,
+    trust_return_value_nullability: &TrustReturnValueNullability,
+    visibility: Visibility,
 ) -> GObjects {
     let mut objects = GObjects::new();
     for toml_object in toml_objects.as_array().unwrap() {
-        let gobject = parse_object(
+        let mut gobject = parse_object(
             toml_object,
             concurrency,
             generate_display_trait,
             generate_builder,
             trust_return_value_nullability,
         );
+        if toml_object.lookup("visibility").is_none() {
+            gobject.visibility = visibility;
+        }
         objects.insert(gobject.name.clone(), gobject);
     }
     objects
@@ -234,7 +317,7 @@ fn parse_object(
     concurrency: library::Concurrency,
     default_generate_display_trait: bool,
     generate_builder: bool,
-    trust_return_value_nullability: bool,
+    trust_return_value_nullability: &TrustReturnValueNullability,
 ) -> GObject {
     let name: String = toml_object
         .lookup("name")
@@ -264,6 +347,7 @@ fn parse_object(
             "final_type",
             "fundamental_type",
             "exhaustive",
+            "generate_error_trait",
             "trait",
             "trait_name",
             "cfg_condition",
@@ -281,6 +365,26 @@ fn parse_object(
             "visibility",
             "default_value",
             "generate_doc",
+            "rust_path",
+            "extra_imports",
+            "prologue",
+            "epilogue",
+            "use_boxed_functions",
+            "error_quark",
+            "get_type",
+            "strip_prefixes",
+            "version_end",
+            "local_signals",
+            "wrapper",
+            "prerequisites",
+            "lazy_lists",
+            "signal_streams",
+            "skip_value_traits",
+            "debug_fields",
+            "plain_struct",
+            "module_path",
+            "generate_default",
+            "default_constructor",
         ],
         &format!("object {name}"),
     );
@@ -352,6 +456,10 @@ fn parse_object(
         .lookup("exhaustive")
         .and_then(Value::as_bool)
         .unwrap_or(false);
+    let generate_error_trait = toml_object
+        .lookup("generate_error_trait")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
     let trait_name = toml_object
         .lookup("trait_name")
         .and_then(Value::as_str)
@@ -378,7 +486,7 @@ fn parse_object(
     let trust_return_value_nullability = toml_object
         .lookup("trust_return_value_nullability")
         .and_then(Value::as_bool)
-        .unwrap_or(trust_return_value_nullability);
+        .unwrap_or_else(|| trust_return_value_nullability.for_object(&name));
     let manual_traits = toml_object
         .lookup_vec("manual_traits", "IGNORED ERROR")
         .into_iter()
@@ -482,6 +590,155 @@ fn parse_object(
         .and_then(Value::as_bool)
         .unwrap_or(true);
 
+    let rust_path = toml_object
+        .lookup("rust_path")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned);
+    if status != GStatus::Manual && rust_path.is_some() {
+        warn!(
+            "rust_path configuration used for non-manual object {}",
+            name
+        );
+    }
+
+    let extra_imports = toml_object
+        .lookup_vec("extra_imports", "IGNORED ERROR")
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .filter(|import| {
+            // Crates are in scope anyway, a single segment can't be imported
+            let is_path = import.contains("::");
+            if !is_path {
+                warn!(
+                    "Ignoring extra import `{}` of object {}, which isn't a path",
+                    import, name
+                );
+            }
+            is_path
+        })
+        .map(String::from)
+        .collect();
+
+    let prologue = toml_object
+        .lookup("prologue")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned);
+    let epilogue = toml_object
+        .lookup("epilogue")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned);
+
+    let use_boxed_functions = toml_object
+        .lookup("use_boxed_functions")
+        .and_then(Value::as_bool);
+
+    let error_quark = toml_object
+        .lookup("error_quark")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned);
+
+    let get_type = toml_object
+        .lookup("get_type")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned);
+
+    let strip_prefixes = toml_object
+        .lookup_vec("strip_prefixes", "IGNORED ERROR")
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+
+    let version_end = toml_object
+        .lookup("version_end")
+        .and_then(Value::as_str)
+        .and_then(|s| s.parse().ok());
+
+    let local_signals = toml_object
+        .lookup("local_signals")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let wrapper = toml_object
+        .lookup("wrapper")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned);
+
+    let prerequisites = toml_object
+        .lookup_vec("prerequisites", "IGNORED ERROR")
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+
+    let lazy_lists = toml_object
+        .lookup("lazy_lists")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let signal_streams = toml_object
+        .lookup("signal_streams")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let skip_value_traits: Vec<String> = toml_object
+        .lookup_vec("skip_value_traits", "IGNORED ERROR")
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    for trait_name in &skip_value_traits {
+        if !VALUE_TRAITS.contains(&trait_name.as_str()) {
+            warn!(
+                "Unknown trait `{}` in `skip_value_traits` of object {}, expected one of {:?}",
+                trait_name, name, VALUE_TRAITS
+            );
+        }
+    }
+
+    let debug_fields = toml_object
+        .lookup("debug_fields")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let plain_struct = toml_object
+        .lookup("plain_struct")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let module_path = toml_object
+        .lookup("module_path")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .filter(|module_path| {
+            if rust_path.is_some() {
+                error!("module_path and rust_path can't both be used for {}", name);
+                false
+            } else if module_path.is_empty()
+                || !module_path
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                error!("module_path of {} has to be a single module name", name);
+                false
+            } else {
+                true
+            }
+        });
+
+    let generate_default = toml_object
+        .lookup("generate_default")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    let default_constructor = toml_object
+        .lookup("default_constructor")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned);
+    if !generate_default && default_constructor.is_some() {
+        warn!("`default_constructor` is ignored for {name}, `generate_default` is false");
+    }
+
     if generate_trait.is_some() {
         warn!(
             "`trait` configuration is deprecated and replaced by `final_type` for object {}",
@@ -506,6 +763,7 @@ fn parse_object(
         final_type,
         fundamental_type,
         exhaustive,
+        generate_error_trait,
         trait_name,
         child_properties,
         concurrency,
@@ -525,6 +783,26 @@ fn parse_object(
         visibility,
         default_value,
         generate_doc,
+        rust_path,
+        extra_imports,
+        prologue,
+        epilogue,
+        use_boxed_functions,
+        error_quark,
+        get_type,
+        strip_prefixes,
+        version_end,
+        local_signals,
+        wrapper,
+        prerequisites,
+        lazy_lists,
+        signal_streams,
+        skip_value_traits,
+        debug_fields,
+        plain_struct,
+        module_path,
+        generate_default,
+        default_constructor,
     }
 }
 
@@ -534,7 +812,8 @@ pub fn parse_status_shorthands(
     concurrency: library::Concurrency,
     generate_display_trait: bool,
     generate_builder: bool,
-    trust_return_value_nullability: bool,
+    trust_return_value_nullability: &TrustReturnValueNullability,
+    visibility: Visibility,
 ) {
     use self::GStatus::*;
     for &status in &[Manual, Generate, Ignore] {
@@ -546,6 +825,7 @@ pub fn parse_status_shorthands(
             generate_display_trait,
             generate_builder,
             trust_return_value_nullability,
+            visibility,
         );
     }
 }
@@ -557,7 +837,8 @@ fn parse_status_shorthand(
     concurrency: library::Concurrency,
     generate_display_trait: bool,
     generate_builder: bool,
-    trust_return_value_nullability: bool,
+    trust_return_value_nullability: &TrustReturnValueNullability,
+    visibility: Visibility,
 ) {
     let option_name = format!("options.{status:?}").to_ascii_lowercase();
     if let Some(a) = toml.lookup(&option_name).map(|a| a.as_array().unwrap()) {
@@ -571,8 +852,10 @@ fn parse_status_shorthand(
                             status,
                             concurrency,
                             generate_display_trait,
-                            trust_return_value_nullability,
+                            trust_return_value_nullability: trust_return_value_nullability
+                                .for_object(name),
                             generate_builder,
+                            visibility,
                             ..Default::default()
                         },
                     );
@@ -588,7 +871,7 @@ pub fn resolve_type_ids(objects: &mut GObjects, library: &Library) {
     let global_functions_name = format!("{}.*", ns.name);
 
     for (name, object) in objects.iter_mut() {
-        let type_id = library.find_type(0, name);
+        let type_id = library.find_type(MAIN_NAMESPACE, name);
         if type_id.is_none() && name != &global_functions_name && object.status != GStatus::Ignore {
             warn!("Configured object `{}` missing from the library", name);
         } else if object.generate_builder {
@@ -604,6 +887,19 @@ pub fn resolve_type_ids(objects: &mut GObjects, library: &Library) {
                 }
             }
         }
+        if let (Some(type_id), Some(_)) = (type_id, &object.module_path) {
+            if !matches!(
+                library.type_(type_id),
+                library::Type::Class(_) | library::Type::Interface(_) | library::Type::Record(_)
+            ) {
+                error!(
+                    "module_path is only supported for classes, interfaces and records, \
+                     ignoring it for `{}`",
+                    name
+                );
+                object.module_path = None;
+            }
+        }
         object.type_id = type_id;
     }
 }
@@ -628,7 +924,13 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(object.conversion_type, None);
     }
 
@@ -642,7 +944,13 @@ conversion_type = "Option"
 "#,
         );
 
-        let object = parse_object(&toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            &toml,
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(object.conversion_type, Some(ConversionType::Option));
     }
 
@@ -657,7 +965,13 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(object.conversion_type, Some(ConversionType::Option));
     }
 
@@ -672,7 +986,13 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(
             object.conversion_type,
             Some(ConversionType::Result {
@@ -694,7 +1014,13 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(
             object.conversion_type,
             Some(ConversionType::Result {
@@ -717,7 +1043,13 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(
             object.conversion_type,
             Some(ConversionType::Result {
@@ -745,7 +1077,16 @@ status = "generate"
 
         let object = toml
             .lookup("object")
-            .map(|t| parse_toml(t, Concurrency::default(), false, false, false))
+            .map(|t| {
+                parse_toml(
+                    t,
+                    Concurrency::default(),
+                    false,
+                    false,
+                    &Default::default(),
+                    Default::default(),
+                )
+            })
             .expect("parsing failed");
         assert_eq!(
             object["Test"].constants,
@@ -774,7 +1115,7 @@ generate_doc = false
 "#,
         );
 
-        let object = parse_object(r, Concurrency::default(), false, false, false);
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
         assert!(!object.generate_doc);
 
         // Ensure that the default value is "true".
@@ -784,7 +1125,360 @@ name = "Test"
 status = "generate"
 "#,
         );
-        let object = parse_object(r, Concurrency::default(), false, false, false);
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
         assert!(object.generate_doc);
     }
+
+    #[test]
+    fn rust_path() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "manual"
+rust_path = "crate::wrapper::Test"
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.rust_path.as_deref(), Some("crate::wrapper::Test"));
+    }
+
+    #[test]
+    fn extra_imports() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+extra_imports = ["crate::prelude::*", "libc", "std::ptr"]
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.extra_imports, ["crate::prelude::*", "std::ptr"]);
+
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+"#,
+        );
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert!(object.extra_imports.is_empty());
+    }
+
+    #[test]
+    fn prologue_epilogue() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+prologue = "use crate::Foo;"
+epilogue = """
+impl Foo for Test {}
+"""
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.prologue.as_deref(), Some("use crate::Foo;"));
+        assert_eq!(object.epilogue.as_deref(), Some("impl Foo for Test {}\n"));
+    }
+
+    #[test]
+    fn use_boxed_functions() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+use_boxed_functions = true
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.use_boxed_functions, Some(true));
+
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+use_boxed_functions = false
+"#,
+        );
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.use_boxed_functions, Some(false));
+
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+"#,
+        );
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.use_boxed_functions, None);
+    }
+
+    #[test]
+    fn error_quark() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+error_quark = "gst_core_error_quark"
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.error_quark.as_deref(), Some("gst_core_error_quark"));
+    }
+
+    #[test]
+    fn get_type() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+get_type = "test_get_type"
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.get_type.as_deref(), Some("test_get_type"));
+    }
+
+    #[test]
+    fn strip_prefixes() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+strip_prefixes = ["vendor"]
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.strip_prefixes, ["vendor"]);
+    }
+
+    #[test]
+    fn version_end() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+version_end = "3.20"
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.version_end, Some(Version(3, 20, 0)));
+    }
+
+    #[test]
+    fn local_signals() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+concurrency = "send"
+local_signals = true
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert!(object.local_signals);
+    }
+
+    #[test]
+    fn wrapper() {
+        let r = &toml(
+            r#"
+name = "Gst.Buffer"
+status = "generate"
+wrapper = "mini_object"
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.wrapper.as_deref(), Some("mini_object"));
+    }
+
+    #[test]
+    fn prerequisites() {
+        let r = &toml(
+            r#"
+name = "Gtk.Editable"
+status = "generate"
+prerequisites = ["crate::Widget", "gio::ListModel"]
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.prerequisites, ["crate::Widget", "gio::ListModel"]);
+    }
+
+    #[test]
+    fn signal_streams() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+signal_streams = true
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert!(object.signal_streams);
+    }
+
+    #[test]
+    fn trust_return_value_nullability_per_namespace() {
+        let trust = TrustReturnValueNullability {
+            default: false,
+            namespaces: [("GstGL".to_owned(), true)].into_iter().collect(),
+        };
+        let r = &toml(
+            r#"
+name = "GstGL.GLContext"
+status = "generate"
+"#,
+        );
+        let object = parse_object(r, Concurrency::default(), false, false, &trust);
+        assert!(object.trust_return_value_nullability);
+
+        let r = &toml(
+            r#"
+name = "Gst.Element"
+status = "generate"
+"#,
+        );
+        let object = parse_object(r, Concurrency::default(), false, false, &trust);
+        assert!(!object.trust_return_value_nullability);
+
+        let r = &toml(
+            r#"
+name = "GstGL.GLDisplay"
+status = "generate"
+trust_return_value_nullability = false
+"#,
+        );
+        let object = parse_object(r, Concurrency::default(), false, false, &trust);
+        assert!(!object.trust_return_value_nullability);
+    }
+
+    #[test]
+    fn skip_value_traits() {
+        let r = &toml(
+            r#"
+name = "Test"
+status = "generate"
+skip_value_traits = ["ToValue", "From"]
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.skip_value_traits, ["ToValue", "From"]);
+    }
+
+    #[test]
+    fn debug_fields() {
+        let r = &toml(
+            r#"
+name = "Gdk.Rectangle"
+status = "generate"
+debug_fields = true
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert!(object.debug_fields);
+    }
+
+    #[test]
+    fn plain_struct() {
+        let r = &toml(
+            r#"
+name = "Graphene.Point"
+status = "generate"
+plain_struct = true
+"#,
+        );
+
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert!(object.plain_struct);
+    }
+
+    #[test]
+    fn default_visibility() {
+        let r = &toml(
+            r#"
+[[object]]
+name = "Test.Default"
+status = "generate"
+[[object]]
+name = "Test.Public"
+status = "generate"
+visibility = "pub"
+"#,
+        );
+        let objects = parse_toml(
+            r.lookup("object").unwrap(),
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+            Visibility::Crate,
+        );
+        assert_eq!(objects["Test.Default"].visibility, Visibility::Crate);
+        assert_eq!(objects["Test.Public"].visibility, Visibility::Public);
+    }
+
+    #[test]
+    fn module_path() {
+        let r = &toml(
+            r#"
+name = "Gtk.AboutDialog"
+status = "generate"
+module_path = "dialogs"
+"#,
+        );
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.module_path.as_deref(), Some("dialogs"));
+
+        for module_path in ["", "dialogs::about", "../dialogs"] {
+            let r = &toml(&format!(
+                r#"
+name = "Gtk.AboutDialog"
+status = "generate"
+module_path = "{module_path}"
+"#
+            ));
+            let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+            assert_eq!(object.module_path, None);
+        }
+
+        let r = &toml(
+            r#"
+name = "Gtk.AboutDialog"
+status = "manual"
+rust_path = "crate::dialogs::AboutDialog"
+module_path = "dialogs"
+"#,
+        );
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert_eq!(object.module_path, None);
+    }
+
+    #[test]
+    fn default_constructor() {
+        let r = &toml(
+            r#"
+name = "Gtk.TextBuffer"
+status = "generate"
+default_constructor = "new_empty"
+"#,
+        );
+        let object = parse_object(r, Concurrency::default(), false, false, &Default::default());
+        assert!(object.generate_default);
+        assert_eq!(object.default_constructor.as_deref(), Some("new_empty"));
+    }
 }
@@ -14,6 +14,9 @@ pub struct Member {
     pub status: GStatus,
     pub cfg_condition: Option<String>,
     pub generate_doc: bool,
+    /// New Rust name of the member, the generated one is kept as a deprecated
+    /// constant
+    pub rename: Option<String>,
 }
 
 impl Parse for Member {
@@ -39,6 +42,7 @@ impl Parse for Member {
                 "manual",
                 "cfg_condition",
                 "generate_doc",
+                "rename",
             ],
             &format!("member {object_name}"),
         );
@@ -81,6 +85,10 @@ impl Parse for Member {
             .lookup("generate_doc")
             .and_then(Value::as_bool)
             .unwrap_or(true);
+        let rename = toml
+            .lookup("rename")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
 
         Some(Self {
             ident,
@@ -90,6 +98,7 @@ impl Parse for Member {
             status,
             cfg_condition,
             generate_doc,
+            rename,
         })
     }
 }
@@ -129,6 +138,18 @@ alias = true
         assert!(f.alias);
     }
 
+    #[test]
+    fn member_parse_rename() {
+        let toml = toml(
+            r#"
+name = "none"
+rename = "Empty"
+"#,
+        );
+        let f = Member::parse(&toml, "a").unwrap();
+        assert_eq!(f.rename.as_deref(), Some("Empty"));
+    }
+
     #[test]
     fn member_parse_version_default() {
         let toml = toml(
@@ -1,3 +1,4 @@
+pub mod attributes;
 mod child_properties;
 #[allow(clippy::module_inception)]
 pub mod config;
@@ -10,6 +11,8 @@ pub mod gobjects;
 pub mod ident;
 pub mod matchable;
 pub mod members;
+pub mod opaque_types;
+pub mod panic_policy;
 pub mod parameter_matchable;
 pub mod parsable;
 pub mod properties;
@@ -18,6 +21,7 @@ pub mod signals;
 pub mod string_type;
 pub mod virtual_methods;
 pub mod work_mode;
+pub mod wrappers;
 
 pub use self::{
     child_properties::{ChildProperties, ChildProperty},
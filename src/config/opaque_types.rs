@@ -0,0 +1,26 @@
+use std::str::FromStr;
+
+/// How types whose fields are unknown are represented in sys crates
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OpaqueTypes {
+    /// Zero-sized `#[repr(C)]` struct
+    Empty,
+    /// Zero-sized `#[repr(C)]` struct that is neither `Send`, `Sync` nor
+    /// `Unpin`
+    #[default]
+    Phantom,
+    /// `extern type`, which has no size at all but needs a nightly compiler
+    Extern,
+}
+
+impl FromStr for OpaqueTypes {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "empty" => Ok(Self::Empty),
+            "phantom" => Ok(Self::Phantom),
+            "extern" => Ok(Self::Extern),
+            _ => Err(format!("Wrong opaque types representation '{s}'")),
+        }
+    }
+}
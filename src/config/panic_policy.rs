@@ -0,0 +1,19 @@
+use std::str::FromStr;
+
+/// What generated trampolines do when the called closure panics
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PanicPolicy {
+    Abort, // abort the process
+    Log,   // log a critical, and abort if a value has to be returned
+}
+
+impl FromStr for PanicPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "abort" => Ok(Self::Abort),
+            "log" => Ok(Self::Log),
+            _ => Err(format!("Wrong panic policy '{s}'")),
+        }
+    }
+}
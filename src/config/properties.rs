@@ -12,10 +12,16 @@ pub struct Property {
     pub ident: Ident,
     pub status: GStatus,
     pub version: Option<Version>,
+    pub deprecated_version: Option<Version>,
     pub generate: Option<PropertyGenerateFlags>,
     pub bypass_auto_rename: bool,
     pub doc_trait_name: Option<String>,
     pub generate_doc: bool,
+    /// Whether the getter returns an `Option`, overrides
+    /// `options.panic_on_null_properties` for nullable properties
+    pub nullable: Option<bool>,
+    /// Message of the panic of the getter on NULL, implies `nullable = false`
+    pub null_panic_message: Option<String>,
 }
 
 impl Parse for Property {
@@ -36,12 +42,15 @@ impl Parse for Property {
                 "ignore",
                 "manual",
                 "version",
+                "deprecated_version",
                 "name",
                 "pattern",
                 "generate",
                 "bypass_auto_rename",
                 "doc_trait_name",
                 "generate_doc",
+                "nullable",
+                "null_panic_message",
             ],
             &format!("property {object_name}"),
         );
@@ -67,6 +76,10 @@ impl Parse for Property {
             .lookup("version")
             .and_then(Value::as_str)
             .and_then(|s| s.parse().ok());
+        let deprecated_version = toml
+            .lookup("deprecated_version")
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse().ok());
         let generate = toml.lookup("generate").and_then(|v| {
             PropertyGenerateFlags::parse_flags(v, "generate")
                 .map_err(|e| error!("{} for object {}", e, object_name))
@@ -84,15 +97,30 @@ impl Parse for Property {
             .lookup("generate_doc")
             .and_then(Value::as_bool)
             .unwrap_or(true);
+        let nullable = toml.lookup("nullable").and_then(Value::as_bool);
+        let null_panic_message = toml
+            .lookup("null_panic_message")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
+        if nullable == Some(true) && null_panic_message.is_some() {
+            error!(
+                "\"nullable = true\" and \"null_panic_message\" can't be passed at the same \
+                 time for property of object {}, only \"nullable\" will be applied",
+                object_name
+            );
+        }
 
         Some(Self {
             ident,
             status,
             version,
+            deprecated_version,
             generate,
             bypass_auto_rename,
             doc_trait_name,
             generate_doc,
+            nullable,
+            null_panic_message,
         })
     }
 }
@@ -167,6 +195,19 @@ bypass_auto_rename = true
         assert!(f.bypass_auto_rename);
     }
 
+    #[test]
+    fn property_parse_null_panic_message() {
+        let toml = toml(
+            r#"
+name = "prop1"
+null_panic_message = "prop1 isn't set"
+"#,
+        );
+        let p = Property::parse(&toml, "a").unwrap();
+        assert_eq!(p.nullable, None);
+        assert_eq!(p.null_panic_message.as_deref(), Some("prop1 isn't set"));
+    }
+
     #[test]
     fn property_parse_version_default() {
         let toml = toml(
@@ -242,4 +283,16 @@ pattern = 'prop\d+'
         assert_eq!(props.matched("p1.5").len(), 0);
         assert_eq!(props.matched("none").len(), 0);
     }
+
+    #[test]
+    fn property_parse_deprecated_version() {
+        let toml = toml(
+            r#"
+name = "prop1"
+deprecated_version = "3.20"
+"#,
+        );
+        let p = Property::parse(&toml, "a").unwrap();
+        assert_eq!(p.deprecated_version, Some(Version(3, 20, 0)));
+    }
 }
@@ -104,6 +104,7 @@ pub struct Signal {
     pub status: GStatus,
     pub inhibit: bool,
     pub version: Option<Version>,
+    pub deprecated_version: Option<Version>,
     pub parameters: Parameters,
     pub ret: Return,
     pub concurrency: library::Concurrency,
@@ -134,6 +135,7 @@ impl Signal {
                 "manual",
                 "inhibit",
                 "version",
+                "deprecated_version",
                 "parameter",
                 "return",
                 "doc_hidden",
@@ -172,6 +174,10 @@ impl Signal {
             .lookup("version")
             .and_then(Value::as_str)
             .and_then(|s| s.parse().ok());
+        let deprecated_version = toml
+            .lookup("deprecated_version")
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse().ok());
         let parameters = Parameters::parse(toml.lookup("parameter"), object_name);
         let ret = Return::parse(toml.lookup("return"), object_name);
 
@@ -199,6 +205,7 @@ impl Signal {
             status,
             inhibit,
             version,
+            deprecated_version,
             parameters,
             ret,
             concurrency,
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use super::error::TomlHelper;
+
+/// A wrapper macro used instead of `glib::wrapper!` for records that are
+/// neither boxed nor shared, like `GstMiniObject`s
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CustomWrapper {
+    /// Path of the macro, e.g. `gst::mini_object_wrapper!`
+    pub macro_name: String,
+    /// Arguments of the macro, with `{name}`, `{c_type}`, `{get_type}`,
+    /// `{ref}`, `{unref}`, `{copy}`, `{free}` and `{visibility}` replaced
+    pub template: String,
+}
+
+impl CustomWrapper {
+    pub fn expand(&self, replacements: &[(&str, &str)]) -> String {
+        replacements
+            .iter()
+            .fold(self.template.clone(), |template, (key, value)| {
+                template.replace(&format!("{{{key}}}"), value)
+            })
+    }
+}
+
+pub fn read_wrappers(toml: &toml::Value) -> Result<HashMap<String, CustomWrapper>, String> {
+    let table = match toml.lookup("options.wrappers") {
+        Some(v) => v
+            .as_table()
+            .ok_or_else(|| "options.wrappers expected to be a table".to_string())?,
+        None => return Ok(Default::default()),
+    };
+
+    table
+        .iter()
+        .map(|(kind, wrapper)| {
+            let location = format!("options.wrappers.{kind}");
+            wrapper.check_unwanted(&["macro", "template"], &location);
+            let macro_name = wrapper
+                .lookup_str("macro", &format!("No macro in {location}"))?
+                .to_owned();
+            let template = wrapper
+                .lookup_str("template", &format!("No template in {location}"))?
+                .to_owned();
+            Ok((
+                kind.clone(),
+                CustomWrapper {
+                    macro_name,
+                    template,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_and_expand() {
+        let toml: toml::Value = toml::from_str(
+            r#"
+[options.wrappers.mini_object]
+macro = "gst::mini_object_wrapper!"
+template = "{name}, {name}Ref, ffi::{c_type}, || ffi::{get_type}()"
+"#,
+        )
+        .unwrap();
+        let wrappers = read_wrappers(&toml).unwrap();
+        let wrapper = &wrappers["mini_object"];
+        assert_eq!(wrapper.macro_name, "gst::mini_object_wrapper!");
+        assert_eq!(
+            wrapper.expand(&[
+                ("name", "Buffer"),
+                ("c_type", "GstBuffer"),
+                ("get_type", "gst_buffer_get_type"),
+            ]),
+            "Buffer, BufferRef, ffi::GstBuffer, || ffi::gst_buffer_get_type()"
+        );
+
+        let toml: toml::Value = toml::from_str(
+            r#"
+[options.wrappers.mini_object]
+macro = "gst::mini_object_wrapper!"
+"#,
+        )
+        .unwrap();
+        assert!(read_wrappers(&toml).is_err());
+    }
+}
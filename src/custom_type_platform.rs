@@ -0,0 +1,88 @@
+//! Adds custom types for the C platform types configured in
+//! `options.platform_types` and uses them for parameters and aliases with that
+//! C type, or a pointer to it
+
+use crate::{
+    analysis::{c_type::rustify_pointers, conversion_type::ConversionType},
+    config::{Config, WorkMode},
+    library::*,
+    visitors::FunctionsMutVisitor,
+};
+
+impl Library {
+    pub fn add_platform_types(&mut self, config: &Config) {
+        // The sys generation maps these by their C type directly
+        if config.work_mode != WorkMode::Normal {
+            return;
+        }
+
+        for (c_type, rust_type) in &config.platform_types {
+            let tid = self.add_type(
+                INTERNAL_NAMESPACE,
+                c_type,
+                Type::Custom(Custom {
+                    name: rust_type.clone(),
+                    conversion_type: ConversionType::Direct,
+                }),
+            );
+
+            let mut replacer = ReplaceToPlatformType { c_type, tid };
+            self.namespace_mut(MAIN_NAMESPACE)
+                .visit_functions_mut(&mut replacer);
+            for func in &mut self.namespace_mut(MAIN_NAMESPACE).functions {
+                replacer.visit_function_mut(func);
+            }
+            for typ in self
+                .namespace_mut(MAIN_NAMESPACE)
+                .types
+                .iter_mut()
+                .flatten()
+            {
+                if let Type::Alias(alias) = typ {
+                    if is_platform_type(&alias.target_c_type, c_type) {
+                        alias.typ = tid;
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct ReplaceToPlatformType<'a> {
+    pub c_type: &'a str,
+    pub tid: TypeId,
+}
+
+impl FunctionsMutVisitor for ReplaceToPlatformType<'_> {
+    fn visit_function_mut(&mut self, func: &mut Function) -> bool {
+        for par in func
+            .parameters
+            .iter_mut()
+            .chain(std::iter::once(&mut func.ret))
+        {
+            if is_platform_type(&par.c_type, self.c_type) {
+                par.typ = self.tid;
+            }
+        }
+        true
+    }
+}
+
+/// Whether `c_type` is the platform type `platform_type`, or a pointer to it
+fn is_platform_type(c_type: &str, platform_type: &str) -> bool {
+    rustify_pointers(c_type).1 == platform_type
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_platform_type() {
+        assert!(is_platform_type("time_t", "time_t"));
+        assert!(is_platform_type("time_t*", "time_t"));
+        assert!(is_platform_type("const time_t*", "time_t"));
+        assert!(!is_platform_type("gint64", "time_t"));
+        assert!(!is_platform_type("time_t_ext", "time_t"));
+    }
+}
@@ -2,7 +2,10 @@ use std::cell::RefCell;
 
 use crate::{
     analysis::{self, namespaces::NsId},
-    config::{gobjects::GStatus, Config},
+    config::{
+        gobjects::{GObjects, GStatus},
+        Config,
+    },
     library::*,
     nameutil::use_glib_type,
     version::Version,
@@ -24,11 +27,7 @@ impl Env {
         self.library.type_(tid)
     }
     pub fn type_status(&self, name: &str) -> GStatus {
-        self.config
-            .objects
-            .get(name)
-            .map(|o| o.status)
-            .unwrap_or_default()
+        type_status(&self.config.objects, &self.config.manual_namespaces, name)
     }
     pub fn type_status_sys(&self, name: &str) -> GStatus {
         self.config
@@ -70,6 +69,23 @@ impl Env {
         false
     }
 
+    /// Returns the earliest `version_end` of the objects among `types` and the
+    /// types they contain, items using them are removed along with them
+    pub fn types_version_end(&self, types: impl IntoIterator<Item = TypeId>) -> Option<Version> {
+        analysis::types::version_end(&self.library, types, &|name| {
+            self.config
+                .objects
+                .get(name)
+                .and_then(|obj| obj.version_end)
+        })
+    }
+
+    /// Whether one of `types` is removed from the API in all generated versions
+    pub fn uses_removed_type(&self, types: impl IntoIterator<Item = TypeId>) -> bool {
+        self.types_version_end(types)
+            .map_or(false, |v| v <= self.config.min_cfg_version)
+    }
+
     pub fn main_sys_crate_name(&self) -> &str {
         &self.namespaces[MAIN_NAMESPACE].sys_crate_name
     }
@@ -84,3 +100,50 @@ impl Env {
         }
     }
 }
+
+/// Status of the type with the full name `name`, the types of the
+/// `manual_namespaces` are manual unless configured otherwise.
+fn type_status(objects: &GObjects, manual_namespaces: &[String], name: &str) -> GStatus {
+    if let Some(o) = objects.get(name) {
+        return o.status;
+    }
+    let is_manual_namespace = name.split_once('.').map_or(false, |(namespace, _)| {
+        manual_namespaces.iter().any(|ns| ns == namespace)
+    });
+    if is_manual_namespace {
+        GStatus::Manual
+    } else {
+        Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::gobjects::parse_toml, library::Concurrency};
+
+    #[test]
+    fn test_type_status() {
+        let toml: ::toml::Value = ::toml::from_str(
+            r#"
+[[object]]
+name = "Gdk.Display"
+status = "generate"
+"#,
+        )
+        .unwrap();
+        let objects = parse_toml(
+            toml.get("object").unwrap(),
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+            Default::default(),
+        );
+        let manual_namespaces = ["Gdk".to_owned()];
+        let status = |name| type_status(&objects, &manual_namespaces, name);
+        assert_eq!(status("Gdk.Display"), GStatus::Generate);
+        assert_eq!(status("Gdk.Screen"), GStatus::Manual);
+        assert_eq!(status("Gtk.Widget"), GStatus::Ignore);
+    }
+}
@@ -0,0 +1,61 @@
+//! Checks that the `.gir` files of a library and of all the libraries it
+//! includes can be found in the configured directories
+
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::{config::Config, parser::read_includes};
+
+/// Where the `.gir` file of a library was found
+pub struct GirFile {
+    /// Library name with its version, e.g. `Gtk-4.0`
+    pub library: String,
+    /// All the files found for the library, in the order of the configured
+    /// directories, so only the first one is used
+    pub paths: Vec<String>,
+    /// Whether the library may be missing, see `options.ignore_missing_includes`
+    pub ignored: bool,
+}
+
+/// Returns the `.gir` files of the configured library and, transitively, of
+/// all the libraries it includes, sorted by library.
+pub fn check_gir_files(config: &Config) -> Result<Vec<GirFile>, String> {
+    let mut files = BTreeMap::new();
+    let mut queue = VecDeque::from([config.library_full_name()]);
+    while let Some(library) = queue.pop_front() {
+        if files.contains_key(&library) {
+            continue;
+        }
+        let paths = config
+            .gir_files
+            .get(&library)
+            .cloned()
+            .into_iter()
+            .chain(
+                config
+                    .girs_dirs
+                    .iter()
+                    .map(|dir| dir.join(format!("{library}.gir"))),
+            )
+            .filter(|path| path.is_file())
+            .collect::<Vec<_>>();
+        if let Some(path) = paths.first() {
+            queue.extend(read_includes(path)?);
+        }
+        let ignored = config
+            .ignore_missing_includes
+            .iter()
+            .any(|namespace| library.split('-').next() == Some(namespace.as_str()));
+        files.insert(
+            library.clone(),
+            GirFile {
+                library,
+                paths: paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect(),
+                ignored,
+            },
+        );
+    }
+    Ok(files.into_values().collect())
+}
@@ -13,15 +13,19 @@ macro_rules! warn_main {
 mod gir_version;
 
 pub mod analysis;
+mod api_changelog;
+mod api_diff;
 mod case;
 mod chunk;
 mod codegen;
 mod config;
 mod consts;
 mod custom_type_glib_priority;
+mod custom_type_platform;
 mod env;
 mod file_saver;
 pub mod fmt;
+mod gir_files;
 mod git;
 pub mod library;
 mod library_postprocessing;
@@ -29,6 +33,8 @@ mod library_preprocessing;
 mod nameutil;
 mod parser;
 mod traits;
+#[cfg(feature = "typelib")]
+mod typelib;
 pub mod update_version;
 mod version;
 mod visitors;
@@ -40,8 +46,12 @@ pub use crate::{
         class_hierarchy::run as class_hierarchy_run, namespaces::run as namespaces_run,
         run as analysis_run, symbols::run as symbols_run,
     },
+    api_changelog::{breaking_api_changes, collect_api, write_api_changelog},
+    api_diff::api_diff,
     codegen::generate as codegen_generate,
     config::{Config, WorkMode},
     env::Env,
+    gir_files::{check_gir_files, GirFile},
     library::Library,
+    parser::unknown_attributes,
 };
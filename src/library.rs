@@ -372,6 +372,8 @@ pub struct Member {
     pub name: String,
     pub c_identifier: String,
     pub value: String,
+    /// The `glib:nick` of the member
+    pub nick: Option<String>,
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
     pub status: GStatus,
@@ -382,7 +384,11 @@ pub struct Member {
 #[derive(Debug)]
 pub enum ErrorDomain {
     Quark(String),
-    Function(String),
+    /// Name and namespace of the quark function
+    Function {
+        name: String,
+        ns_id: u16,
+    },
 }
 
 #[derive(Debug)]
@@ -527,6 +533,21 @@ pub struct Function {
     pub deprecated_version: Option<Version>,
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
+    /// Custom `<attribute>` annotations as name/value pairs
+    pub attributes: Vec<(String, String)>,
+    /// Name of the function that is replaced by this one
+    pub shadows: Option<String>,
+    /// Name of the function that replaces this one
+    pub shadowed_by: Option<String>,
+    /// Name of the function finishing this asynchronous function
+    pub finish_func: Option<String>,
+    /// Name of the synchronous variant of this asynchronous function
+    pub sync_func: Option<String>,
+    /// Name of the asynchronous variant of this synchronous function, or of
+    /// the asynchronous function this function finishes
+    pub async_func: Option<String>,
+    /// Name of the method calling this virtual method
+    pub invoker: Option<String>,
 }
 
 #[derive(Debug)]
@@ -810,16 +831,22 @@ impl Type {
         library.add_type(INTERNAL_NAMESPACE, &format!("fn<#{param_tids:?}>"), typ)
     }
 
+    /// Adds an anonymous union, which is registered by its C type so that
+    /// nested unions with the same fields stay distinct.
     pub fn union(library: &mut Library, u: Union, ns_id: u16) -> TypeId {
         let field_tids: Vec<TypeId> = u.fields.iter().map(|f| f.typ).collect();
+        let name = format!("#{}{field_tids:?}", u.c_type.as_deref().unwrap_or_default());
         let typ = Self::Union(u);
-        library.add_type(ns_id, &format!("#{field_tids:?}"), typ)
+        library.add_type(ns_id, &name, typ)
     }
 
+    /// Adds an anonymous record, which is registered by its C type so that
+    /// nested records with the same fields stay distinct.
     pub fn record(library: &mut Library, r: Record, ns_id: u16) -> TypeId {
         let field_tids: Vec<TypeId> = r.fields.iter().map(|f| f.typ).collect();
+        let name = format!("#{}{field_tids:?}", r.c_type);
         let typ = Self::Record(r);
-        library.add_type(ns_id, &format!("#{field_tids:?}"), typ)
+        library.add_type(ns_id, &name, typ)
     }
 
     pub fn functions(&self) -> &[Function] {
@@ -834,6 +861,18 @@ impl Type {
         }
     }
 
+    pub fn functions_mut(&mut self) -> Option<&mut Vec<Function>> {
+        match self {
+            Self::Enumeration(e) => Some(&mut e.functions),
+            Self::Bitfield(b) => Some(&mut b.functions),
+            Self::Record(r) => Some(&mut r.functions),
+            Self::Union(u) => Some(&mut u.functions),
+            Self::Interface(i) => Some(&mut i.functions),
+            Self::Class(c) => Some(&mut c.functions),
+            _ => None,
+        }
+    }
+
     pub fn is_basic(&self) -> bool {
         matches!(*self, Self::Basic(_))
     }
@@ -970,6 +1009,9 @@ pub struct Namespace {
     /// C headers, relative to include directories provided by pkg-config
     /// --cflags.
     pub c_includes: Vec<String>,
+    /// C identifiers of the functions skipped because of `moved-to`, together
+    /// with the name they were moved to.
+    pub moved_functions: Vec<(String, String)>,
 }
 
 impl Namespace {
@@ -1144,6 +1186,9 @@ impl Library {
             &self.namespace(MAIN_NAMESPACE).functions,
             "FUNCTION",
         );
+        for (c_identifier, moved_to) in &self.namespace(MAIN_NAMESPACE).moved_functions {
+            println!("[MOVED] {c_identifier} (moved to {namespace_name}.{moved_to})");
+        }
     }
 
     fn not_bound_functions(&self, env: &Env, prefix: &str, functions: &[Function], kind: &str) {
@@ -1288,6 +1333,16 @@ impl Library {
         }
     }
 
+    /// Finds a type by its C name in any namespace.
+    pub fn find_type_by_glib_name(&self, glib_name: &str) -> Option<TypeId> {
+        self.namespaces.iter().enumerate().find_map(|(ns_id, ns)| {
+            ns.glib_name_index.get(glib_name).map(|&id| TypeId {
+                ns_id: ns_id as u16,
+                id,
+            })
+        })
+    }
+
     pub fn find_or_stub_type(&mut self, current_ns_id: u16, name: &str) -> TypeId {
         if let Some(tid) = self.find_type(current_ns_id, name) {
             return tid;
@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
-use log::{error, info};
+use log::{error, info, warn};
 
 use crate::{
-    analysis::types::IsIncomplete,
+    analysis::{conversion_type::ConversionType, types::IsIncomplete},
     config::{
-        gobjects::{GObject, GStatus},
+        gobjects::{GObject, GObjects, GStatus},
         matchable::Matchable,
+        parameter_matchable::ParameterMatchable,
         Config, WorkMode,
     },
     library::*,
@@ -35,7 +36,12 @@ type DetectedCTypes = HashMap<TypeId, String>;
 impl Library {
     pub fn postprocessing(&mut self, config: &Config) {
         self.fix_gtype();
+        self.stub_ignored_includes(config);
         self.check_resolved();
+        self.fill_configured_get_types(&config.objects);
+        self.apply_package_overrides(config);
+        self.apply_callback_overrides(config);
+        self.move_configured_functions(&config.objects);
         self.fill_empty_signals_c_types();
         self.resolve_class_structs();
         self.correlate_class_structs();
@@ -53,9 +59,9 @@ impl Library {
         }
     }
 
-    fn check_resolved(&self) {
-        let list: Vec<_> = self
-            .index
+    /// Full names of the types that are referenced but never declared.
+    pub fn unresolved_types(&self) -> Vec<String> {
+        self.index
             .iter()
             .flat_map(|(name, &id)| {
                 let name = name.clone();
@@ -64,11 +70,231 @@ impl Library {
                     .into_iter()
                     .map(move |s| format!("{name}.{s}"))
             })
-            .collect();
+            .collect()
+    }
+
+    /// Full names of the classes and interfaces of the main namespace without
+    /// a `glib:get-type`, which then has to be configured.
+    pub fn missing_get_types(&self) -> Vec<String> {
+        let ns = self.namespace(MAIN_NAMESPACE);
+        ns.types
+            .iter()
+            .flatten()
+            .filter_map(|typ| match typ {
+                Type::Class(Class {
+                    name,
+                    glib_get_type,
+                    ..
+                })
+                | Type::Interface(Interface {
+                    name,
+                    glib_get_type,
+                    ..
+                }) if glib_get_type.is_empty() => Some(format!("{}.{name}", ns.name)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replaces the types referenced from the namespaces in
+    /// `ignore_missing_includes` by custom types that can't be converted, so
+    /// only manual code can use them.
+    fn stub_ignored_includes(&mut self, config: &Config) {
+        for ns_name in &config.ignore_missing_includes {
+            let ns_id = match self.find_namespace(ns_name) {
+                Some(ns_id) => ns_id,
+                None => continue,
+            };
+            let ns = self.namespace_mut(ns_id);
+            for (name, &id) in &ns.index {
+                let typ = &mut ns.types[id as usize];
+                if typ.is_none() {
+                    *typ = Some(Type::Custom(Custom {
+                        name: format!("{}::{name}", nameutil::crate_name(ns_name)),
+                        conversion_type: ConversionType::Unknown,
+                    }));
+                }
+            }
+        }
+    }
+
+    fn check_resolved(&self) {
+        let list = self.unresolved_types();
 
         assert!(list.is_empty(), "Incomplete library, unresolved: {list:?}");
     }
 
+    fn apply_package_overrides(&mut self, config: &Config) {
+        let ns = self.namespace_mut(MAIN_NAMESPACE);
+        if !config.packages.is_empty() {
+            ns.package_names = config.packages.clone();
+        }
+        if !config.c_includes.is_empty() {
+            ns.c_includes = config.c_includes.clone();
+        }
+    }
+
+    /// Sets the configured user data, destroy notify and scope of callback
+    /// parameters of the main namespace
+    fn apply_callback_overrides(&mut self, config: &Config) {
+        let ns = &mut self.namespaces[MAIN_NAMESPACE as usize];
+        if let Some(obj) = config.objects.get(&format!("{}.*", ns.name)) {
+            apply_callback_overrides_to(&mut ns.functions, obj);
+        }
+        for typ in ns.types.iter_mut().flatten() {
+            let obj = match config
+                .objects
+                .get(&format!("{}.{}", ns.name, typ.get_name()))
+            {
+                Some(obj) => obj,
+                None => continue,
+            };
+            if let Some(functions) = typ.functions_mut() {
+                apply_callback_overrides_to(functions, obj);
+            }
+        }
+    }
+
+    /// Moves the functions configured with `move_to` between the global
+    /// functions and the types of the main namespace
+    fn move_configured_functions(&mut self, objects: &GObjects) {
+        let ns = &mut self.namespaces[MAIN_NAMESPACE as usize];
+        let mut moved = Vec::new();
+        if let Some(obj) = objects.get(&format!("{}.*", ns.name)) {
+            take_moved_functions(&mut ns.functions, obj, None, &mut moved);
+        }
+        for (id, typ) in ns.types.iter_mut().enumerate() {
+            let typ = match typ {
+                Some(typ) => typ,
+                None => continue,
+            };
+            let obj = match objects.get(&format!("{}.{}", ns.name, typ.get_name())) {
+                Some(obj) => obj,
+                None => continue,
+            };
+            let tid = TypeId {
+                ns_id: MAIN_NAMESPACE,
+                id: id as u32,
+            };
+            if let Some(functions) = typ.functions_mut() {
+                take_moved_functions(functions, obj, Some(tid), &mut moved);
+            }
+        }
+
+        for (source, target, mut func) in moved {
+            let destination = match self.move_destination(&target, &func) {
+                Ok(destination) => destination,
+                Err(reason) => {
+                    error!(
+                        "Can't move function `{}` to `{}`: {}",
+                        func.name, target, reason
+                    );
+                    self.functions_of_mut(source).push(func);
+                    continue;
+                }
+            };
+            func.kind = match (destination, func.kind) {
+                (None, _) => FunctionKind::Global,
+                (Some(_), FunctionKind::Global) => FunctionKind::Function,
+                (Some(_), kind) => kind,
+            };
+            self.functions_of_mut(destination).push(func);
+        }
+    }
+
+    /// Returns the type to move the function to, `None` for the global
+    /// functions
+    fn move_destination(&self, target: &str, func: &Function) -> Result<Option<TypeId>, String> {
+        let instance_type = func
+            .parameters
+            .iter()
+            .find(|par| par.instance_parameter)
+            .map(|par| par.typ);
+        if target == "*" {
+            return match instance_type {
+                Some(_) => Err("methods can't be global functions".to_owned()),
+                None => Ok(None),
+            };
+        }
+        let tid = match self.find_type(MAIN_NAMESPACE, target) {
+            Some(tid)
+                if matches!(
+                    self.type_(tid),
+                    Type::Enumeration(_)
+                        | Type::Bitfield(_)
+                        | Type::Record(_)
+                        | Type::Union(_)
+                        | Type::Interface(_)
+                        | Type::Class(_)
+                ) =>
+            {
+                tid
+            }
+            _ => return Err("no such type with functions".to_owned()),
+        };
+        match instance_type {
+            Some(instance_type) if instance_type != tid => Err(format!(
+                "the instance parameter is a `{}`",
+                instance_type.full_name(self)
+            )),
+            _ => Ok(Some(tid)),
+        }
+    }
+
+    fn functions_of_mut(&mut self, tid: Option<TypeId>) -> &mut Vec<Function> {
+        match tid {
+            Some(tid) => self
+                .type_mut(tid)
+                .functions_mut()
+                .expect("Type without functions"),
+            None => &mut self.namespaces[MAIN_NAMESPACE as usize].functions,
+        }
+    }
+
+    fn fill_configured_get_types(&mut self, objects: &GObjects) {
+        for (name, obj) in objects {
+            let get_type = match obj.get_type {
+                Some(ref get_type) => get_type,
+                None => continue,
+            };
+            let tid = match self.find_type(MAIN_NAMESPACE, name) {
+                Some(tid) => tid,
+                None => continue,
+            };
+            match self.type_mut(tid) {
+                Type::Bitfield(Bitfield { glib_get_type, .. })
+                | Type::Enumeration(Enumeration { glib_get_type, .. })
+                | Type::Record(Record { glib_get_type, .. })
+                | Type::Union(Union { glib_get_type, .. }) => {
+                    *glib_get_type = Some(get_type.clone());
+                }
+                Type::Class(Class { glib_get_type, .. })
+                | Type::Interface(Interface { glib_get_type, .. }) => {
+                    *glib_get_type = get_type.clone();
+                }
+                _ => error!("`get_type` can't be configured for {}", name),
+            }
+        }
+
+        // Classes and interfaces can't be used without their GType, those of
+        // the main namespace are rejected by `missing_get_types`
+        for ns in self.namespaces.iter().skip(MAIN_NAMESPACE as usize + 1) {
+            for typ in ns.types.iter().flatten() {
+                if let Type::Class(Class { glib_get_type, .. })
+                | Type::Interface(Interface { glib_get_type, .. }) = typ
+                {
+                    if glib_get_type.is_empty() {
+                        warn!(
+                            "Missing `glib:get-type` and `get_type` configuration of {}.{}",
+                            ns.name,
+                            typ.get_name()
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     fn fill_empty_signals_c_types(&mut self) {
         fn update_empty_signals_c_types(signals: &mut [Signal], c_types: &DetectedCTypes) {
             for signal in signals {
@@ -498,6 +724,29 @@ impl Library {
                 };
 
                 if let Type::Enumeration(enum_) = type_ {
+                    let full_name = format!("{}.{}", ns.name, enum_.name);
+                    if let Some(function_name) = config
+                        .objects
+                        .get(&full_name)
+                        .and_then(|obj| obj.error_quark.as_ref())
+                    {
+                        // The quark function may live in any loaded namespace
+                        let func_ns_id = self.namespaces.iter().position(|ns| {
+                            ns.functions
+                                .iter()
+                                .any(|f| f.c_identifier.as_ref() == Some(function_name))
+                        });
+                        if let Some(func_ns_id) = func_ns_id {
+                            error_domains.push((func_ns_id, enum_tid, None, function_name.clone()));
+                        } else {
+                            error!(
+                                "Error quark function `{}` for `{}` not found",
+                                function_name, full_name
+                            );
+                        }
+                        continue 'next_enum;
+                    }
+
                     if let Some(ErrorDomain::Quark(ref domain)) = enum_.error_domain {
                         let domain = domain.replace('-', "_");
 
@@ -596,8 +845,10 @@ impl Library {
             }
 
             if let Type::Enumeration(enum_) = self.type_mut(enum_tid) {
-                assert!(enum_.error_domain.is_some());
-                enum_.error_domain = Some(ErrorDomain::Function(function_name));
+                enum_.error_domain = Some(ErrorDomain::Function {
+                    name: function_name,
+                    ns_id: ns_id as u16,
+                });
             } else {
                 unreachable!();
             }
@@ -650,3 +901,306 @@ impl Library {
         }
     }
 }
+
+fn apply_callback_overrides_to(functions: &mut [Function], obj: &GObject) {
+    for func in functions {
+        let configured_functions = obj.functions.matched(&func.name);
+        if configured_functions.is_empty() {
+            continue;
+        }
+        // Closure and destroy indices don't count the instance parameter
+        let indices: HashMap<String, usize> = func
+            .parameters
+            .iter()
+            .filter(|par| !par.instance_parameter)
+            .enumerate()
+            .map(|(pos, par)| (par.name.clone(), pos))
+            .collect();
+        for par in &mut func.parameters {
+            for configured in configured_functions.matched_parameters(&par.name) {
+                if let Some(scope) = configured.scope {
+                    par.scope = scope;
+                }
+                for (name, index) in [
+                    (&configured.user_data, &mut par.closure),
+                    (&configured.destroy, &mut par.destroy),
+                ] {
+                    if let Some(name) = name {
+                        match indices.get(name) {
+                            Some(&pos) => *index = Some(pos),
+                            None => error!(
+                                "No parameter `{}` in function `{}` of object {}",
+                                name, func.name, obj.name
+                            ),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn take_moved_functions(
+    functions: &mut Vec<Function>,
+    obj: &GObject,
+    source: Option<TypeId>,
+    moved: &mut Vec<(Option<TypeId>, String, Function)>,
+) {
+    let mut i = 0;
+    while i < functions.len() {
+        let target = obj
+            .functions
+            .matched(&functions[i].name)
+            .iter()
+            .find_map(|f| f.move_to.clone());
+        match target {
+            Some(target) => moved.push((source, target, functions.remove(i))),
+            None => i += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::gobjects::parse_toml;
+
+    fn function(name: &str, instance_type: Option<TypeId>) -> Function {
+        let parameter = |typ: TypeId| Parameter {
+            name: "self".into(),
+            typ,
+            c_type: "gpointer".into(),
+            instance_parameter: true,
+            direction: ParameterDirection::In,
+            transfer: Transfer::None,
+            caller_allocates: false,
+            nullable: Nullable(false),
+            array_length: None,
+            is_error: false,
+            doc: None,
+            scope: ParameterScope::None,
+            closure: None,
+            destroy: None,
+        };
+        Function {
+            name: name.into(),
+            c_identifier: Some(format!("test_{name}")),
+            kind: if instance_type.is_some() {
+                FunctionKind::Method
+            } else {
+                FunctionKind::Function
+            },
+            parameters: instance_type.into_iter().map(parameter).collect(),
+            ret: Parameter {
+                instance_parameter: false,
+                direction: ParameterDirection::Return,
+                ..parameter(TypeId::tid_none())
+            },
+            throws: false,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Vec::new(),
+            shadows: None,
+            shadowed_by: None,
+            finish_func: None,
+            sync_func: None,
+            async_func: None,
+            invoker: None,
+        }
+    }
+
+    fn function_names(library: &Library, tid: Option<TypeId>) -> Vec<&str> {
+        let functions = match tid {
+            Some(tid) => library.type_(tid).functions(),
+            None => &library.namespace(MAIN_NAMESPACE).functions,
+        };
+        functions.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_move_configured_functions() {
+        let toml: ::toml::Value = ::toml::from_str(
+            r#"
+[[object]]
+name = "Test.Foo"
+status = "generate"
+    [[object.function]]
+    name = "to_global"
+    move_to = "*"
+    [[object.function]]
+    name = "to_bar"
+    move_to = "Bar"
+    [[object.function]]
+    name = "method_to_global"
+    move_to = "*"
+    [[object.function]]
+    name = "method_to_bar"
+    move_to = "Bar"
+    [[object.function]]
+    name = "to_missing"
+    move_to = "Missing"
+"#,
+        )
+        .unwrap();
+        let objects = parse_toml(
+            toml.get("object").unwrap(),
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+            Default::default(),
+        );
+
+        let mut library = Library::new("Test");
+        let foo = library.add_type(MAIN_NAMESPACE, "Foo", Type::Record(Default::default()));
+        let bar = library.add_type(MAIN_NAMESPACE, "Bar", Type::Record(Default::default()));
+        if let Type::Record(record) = library.type_mut(foo) {
+            record.name = "Foo".into();
+            record.functions = vec![
+                function("to_global", None),
+                function("to_bar", None),
+                function("method_to_global", Some(foo)),
+                function("method_to_bar", Some(foo)),
+                function("to_missing", None),
+            ];
+        }
+        if let Type::Record(record) = library.type_mut(bar) {
+            record.name = "Bar".into();
+        }
+
+        library.move_configured_functions(&objects);
+        assert_eq!(
+            function_names(&library, Some(foo)),
+            ["method_to_global", "method_to_bar", "to_missing"]
+        );
+        assert_eq!(function_names(&library, Some(bar)), ["to_bar"]);
+        assert_eq!(function_names(&library, None), ["to_global"]);
+        assert_eq!(
+            library.namespace(MAIN_NAMESPACE).functions[0].kind,
+            FunctionKind::Global
+        );
+    }
+
+    #[test]
+    fn test_missing_get_types() {
+        let mut library = Library::new("Test");
+        let class = |name: &str, glib_get_type: &str| {
+            Type::Class(Class {
+                name: name.into(),
+                glib_get_type: glib_get_type.into(),
+                ..Default::default()
+            })
+        };
+        library.add_type(MAIN_NAMESPACE, "Foo", class("Foo", "test_foo_get_type"));
+        library.add_type(MAIN_NAMESPACE, "Bar", class("Bar", ""));
+        library.add_type(
+            MAIN_NAMESPACE,
+            "Baz",
+            Type::Interface(Interface {
+                name: "Baz".into(),
+                ..Default::default()
+            }),
+        );
+        let other = library.add_namespace("Other");
+        library.add_type(other, "Qux", class("Qux", ""));
+
+        assert_eq!(library.missing_get_types(), ["Test.Bar", "Test.Baz"]);
+    }
+
+    #[test]
+    fn test_fill_configured_get_types() {
+        let toml: ::toml::Value = ::toml::from_str(
+            r#"
+[[object]]
+name = "Test.Bar"
+status = "generate"
+get_type = "test_bar_get_type"
+"#,
+        )
+        .unwrap();
+        let objects = parse_toml(
+            toml.get("object").unwrap(),
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+            Default::default(),
+        );
+
+        let mut library = Library::new("Test");
+        let class = |name: &str| {
+            Type::Class(Class {
+                name: name.into(),
+                ..Default::default()
+            })
+        };
+        let bar = library.add_type(MAIN_NAMESPACE, "Bar", class("Bar"));
+        library.add_type(MAIN_NAMESPACE, "Baz", class("Baz"));
+        // Only warned about as it's a dependency
+        let other = library.add_namespace("Other");
+        library.add_type(other, "Qux", class("Qux"));
+
+        library.fill_configured_get_types(&objects);
+        match library.type_(bar) {
+            Type::Class(class) => assert_eq!(class.glib_get_type, "test_bar_get_type"),
+            _ => unreachable!(),
+        }
+        assert_eq!(library.missing_get_types(), ["Test.Baz"]);
+    }
+
+    #[test]
+    fn test_apply_callback_overrides() {
+        let toml: ::toml::Value = ::toml::from_str(
+            r#"
+[[object]]
+name = "Test.Foo"
+status = "generate"
+    [[object.function]]
+    name = "set_func"
+        [[object.function.parameter]]
+        name = "func"
+        user_data = "data"
+        destroy = "notify"
+        scope = "notified"
+        [[object.function.parameter]]
+        name = "other"
+        user_data = "missing"
+"#,
+        )
+        .unwrap();
+        let objects = parse_toml(
+            toml.get("object").unwrap(),
+            Concurrency::default(),
+            false,
+            false,
+            &Default::default(),
+            Default::default(),
+        );
+
+        let mut func = function("set_func", Some(TypeId::tid_none()));
+        for name in ["notify", "other", "data", "func"] {
+            func.parameters.insert(
+                1,
+                Parameter {
+                    name: name.into(),
+                    instance_parameter: false,
+                    ..func.parameters[0].clone()
+                },
+            );
+        }
+        let mut functions = [func];
+        apply_callback_overrides_to(&mut functions, &objects["Test.Foo"]);
+        let parameters = &functions[0].parameters;
+        // The indices don't count the instance parameter
+        assert_eq!(parameters[1].name, "func");
+        assert_eq!(parameters[1].closure, Some(1));
+        assert_eq!(parameters[1].destroy, Some(3));
+        assert_eq!(parameters[1].scope, ParameterScope::Notified);
+        // Unknown parameters are reported and ignored
+        assert_eq!(parameters[3].name, "other");
+        assert_eq!(parameters[3].closure, None);
+        assert_eq!(parameters[3].scope, ParameterScope::None);
+    }
+}
@@ -1,7 +1,8 @@
-use crate::{config::WorkMode, library::*};
+use crate::{config::Config, library::*};
 
 impl Library {
-    pub fn preprocessing(&mut self, work_mode: WorkMode) {
-        self.add_glib_priority(work_mode);
+    pub fn preprocessing(&mut self, config: &Config) {
+        self.add_glib_priority(config.work_mode);
+        self.add_platform_types(config);
     }
 }
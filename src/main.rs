@@ -1,4 +1,4 @@
-use std::{cell::RefCell, env, path::PathBuf, process, str::FromStr};
+use std::{cell::RefCell, collections::BTreeMap, env, path::PathBuf, process, str::FromStr};
 
 use getopts::Options;
 use hprof::Profiler;
@@ -23,8 +23,12 @@ impl<S: AsRef<str>> OptionStr for Option<S> {
 }
 
 enum RunKind {
-    Config(Config),
+    /// The configuration and the public items generated by the previous run
+    Config(Config, BTreeMap<String, String>),
     CheckGirFile(String),
+    Validate(String),
+    Diff(String, String),
+    CheckGirFiles(Config),
 }
 
 fn build_config() -> Result<RunKind, String> {
@@ -45,6 +49,12 @@ fn build_config() -> Result<RunKind, String> {
         "Directories for GIR files",
         "GIRSPATH",
     );
+    options.optmulti(
+        "",
+        "gir-file",
+        "Explicit `.gir` file of the library, or of the given one",
+        "[NAME-VERSION=]PATH",
+    );
     options.optopt(
         "m",
         "mode",
@@ -62,6 +72,28 @@ fn build_config() -> Result<RunKind, String> {
         "Check if the given `.gir` file is valid",
         "PATH",
     );
+    options.optopt(
+        "",
+        "validate",
+        "Report unknown attributes and unresolved types in the given `.gir` file",
+        "PATH",
+    );
+    options.optopt(
+        "",
+        "diff",
+        "Report the API differences between the given `.gir` file and the one given as argument",
+        "OLD_PATH",
+    );
+    options.optflag(
+        "",
+        "semver-check",
+        "Fail if the regenerated code removes or changes public items",
+    );
+    options.optflag(
+        "",
+        "check-gir-files",
+        "Report which `.gir` files of the library and its dependencies are found",
+    );
 
     let matches = options.parse(&args[1..]).map_err(|e| e.to_string())?;
 
@@ -69,6 +101,18 @@ fn build_config() -> Result<RunKind, String> {
         return Ok(RunKind::CheckGirFile(check_gir_file));
     }
 
+    if let Some(validate) = matches.opt_str("validate") {
+        return Ok(RunKind::Validate(validate));
+    }
+
+    if let Some(old) = matches.opt_str("diff") {
+        let new = matches
+            .free
+            .first()
+            .ok_or_else(|| "--diff needs the path of the new `.gir` file".to_owned())?;
+        return Ok(RunKind::Diff(old, new.clone()));
+    }
+
     if matches.opt_present("h") {
         print_usage(&program, options);
         process::exit(0);
@@ -85,10 +129,11 @@ fn build_config() -> Result<RunKind, String> {
         },
     };
 
-    Config::new(
+    let cfg = Config::new(
         matches.opt_str("c").as_str_ref(),
         work_mode,
         &matches.opt_strs("d"),
+        &matches.opt_strs("gir-file"),
         matches.free.get(0).as_str_ref(),
         matches.free.get(1).as_str_ref(),
         matches.opt_str("o").as_str_ref(),
@@ -96,11 +141,24 @@ fn build_config() -> Result<RunKind, String> {
         matches.opt_present("b"),
         matches.opt_present("s"),
         matches.opt_present("disable-format"),
-    )
-    .map(RunKind::Config)
+        matches.opt_present("semver-check"),
+    )?;
+    // The previous API has to be read before its files are removed
+    let previous_api = if cfg.api_changelog.is_some() || cfg.semver_check {
+        gir::collect_api(&cfg.auto_path)
+    } else {
+        BTreeMap::new()
+    };
+    cfg.remove_auto_path()?;
+
+    if matches.opt_present("check-gir-files") {
+        Ok(RunKind::CheckGirFiles(cfg))
+    } else {
+        Ok(RunKind::Config(cfg, previous_api))
+    }
 }
 
-fn run_check(check_gir_file: &str) -> Result<(), String> {
+fn read_gir_file(check_gir_file: &str) -> Result<Library, String> {
     let path = PathBuf::from(check_gir_file);
     if !path.is_file() {
         return Err(format!("`{check_gir_file}`: file not found",));
@@ -116,7 +174,74 @@ fn run_check(check_gir_file: &str) -> Result<(), String> {
         "Failed to get parent directory from `{check_gir_file}`",
     ))?;
 
-    library.read_file(&[parent], &mut vec![lib_name.to_owned()])
+    library.read_file(
+        &[parent],
+        &Default::default(),
+        &mut vec![lib_name.to_owned()],
+        &[],
+    )?;
+    Ok(library)
+}
+
+fn run_check(check_gir_file: &str) -> Result<(), String> {
+    read_gir_file(check_gir_file).map(|_| ())
+}
+
+fn run_validate(gir_file: &str) -> Result<(), String> {
+    let mut problems = gir::unknown_attributes(&PathBuf::from(gir_file))?;
+    match read_gir_file(gir_file) {
+        Ok(library) => {
+            for name in library.unresolved_types() {
+                problems.push(format!("Type `{name}` is referenced but never declared"));
+            }
+            for name in library.missing_get_types() {
+                problems.push(format!("Type `{name}` has no `glib:get-type`"));
+            }
+        }
+        // All unknown elements are reported together, a missing mandatory
+        // attribute stops the reading
+        Err(errors) => problems.extend(errors.lines().map(ToOwned::to_owned)),
+    }
+
+    for problem in &problems {
+        eprintln!("{problem}");
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("`{gir_file}`: {} problem(s) found", problems.len()))
+    }
+}
+
+fn run_diff(old_gir_file: &str, new_gir_file: &str) -> Result<(), String> {
+    let old = read_gir_file(old_gir_file)?;
+    let new = read_gir_file(new_gir_file)?;
+    for line in gir::api_diff(&old, &new) {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+fn run_check_gir_files(cfg: &Config) -> Result<(), String> {
+    let mut missing = 0;
+    for file in gir::check_gir_files(cfg)? {
+        match file.paths.split_first() {
+            None if file.ignored => println!("{}: missing (ignored)", file.library),
+            None => {
+                missing += 1;
+                println!("{}: missing", file.library);
+            }
+            Some((path, [])) => println!("{}: {path}", file.library),
+            Some((path, others)) => {
+                println!("{}: {path} (also in {})", file.library, others.join(", "));
+            }
+        }
+    }
+    if missing == 0 {
+        Ok(())
+    } else {
+        Err(format!("{missing} `.gir` file(s) missing"))
+    }
 }
 
 fn main() -> Result<(), String> {
@@ -125,9 +250,12 @@ fn main() -> Result<(), String> {
     }
     env_logger::init();
 
-    let mut cfg = match build_config() {
+    let (mut cfg, previous_api) = match build_config() {
         Ok(RunKind::CheckGirFile(check_gir_file)) => return run_check(&check_gir_file),
-        Ok(RunKind::Config(cfg)) => cfg,
+        Ok(RunKind::Validate(gir_file)) => return run_validate(&gir_file),
+        Ok(RunKind::Diff(old, new)) => return run_diff(&old, &new),
+        Ok(RunKind::CheckGirFiles(cfg)) => return run_check_gir_files(&cfg),
+        Ok(RunKind::Config(cfg, previous_api)) => (cfg, previous_api),
         Err(err) => return Err(err),
     };
     cfg.check_disable_format();
@@ -141,13 +269,18 @@ fn main() -> Result<(), String> {
         let _watcher = statistics.enter("Loading");
 
         let mut library = Library::new(&cfg.library_name);
-        library.read_file(&cfg.girs_dirs, &mut vec![cfg.library_full_name()])?;
+        library.read_file(
+            &cfg.girs_dirs,
+            &cfg.gir_files,
+            &mut vec![cfg.library_full_name()],
+            &cfg.ignore_missing_includes,
+        )?;
         library
     };
 
     {
         let _watcher = statistics.enter("Preprocessing");
-        library.preprocessing(cfg.work_mode);
+        library.preprocessing(&cfg);
     }
 
     {
@@ -158,6 +291,12 @@ fn main() -> Result<(), String> {
     {
         let _watcher = statistics.enter("Postprocessing");
         library.postprocessing(&cfg);
+        let missing = library.missing_get_types();
+        if !missing.is_empty() {
+            return Err(format!(
+                "Missing `glib:get-type` and `get_type` configuration: {missing:?}"
+            ));
+        }
     }
 
     {
@@ -173,7 +312,7 @@ fn main() -> Result<(), String> {
     let mut env = {
         let _watcher = statistics.enter("Namespace/symbol/class analysis");
 
-        let namespaces = gir::namespaces_run(&library);
+        let namespaces = gir::namespaces_run(&library, cfg.sys_crate_path.as_deref());
         let symbols = gir::symbols_run(&library, &namespaces);
         let class_hierarchy = gir::class_hierarchy_run(&library);
 
@@ -202,6 +341,17 @@ fn main() -> Result<(), String> {
         gir::fmt::format(&env.config.target_path);
     }
 
+    if let Some(ref api_changelog) = env.config.api_changelog {
+        let _watcher = statistics.enter("API changelog");
+        gir::write_api_changelog(api_changelog, &previous_api, &env.config.auto_path)?;
+    }
+
+    let breaking_changes = if env.config.semver_check {
+        gir::breaking_api_changes(&previous_api, &env.config.auto_path)
+    } else {
+        Vec::new()
+    };
+
     drop(watcher_total);
     statistics.end_frame();
 
@@ -212,5 +362,15 @@ fn main() -> Result<(), String> {
         env.library.show_non_bound_types(&env);
     }
 
+    if !breaking_changes.is_empty() {
+        for change in &breaking_changes {
+            eprintln!("{change}");
+        }
+        return Err(format!(
+            "{} public item(s) removed or changed",
+            breaking_changes.len()
+        ));
+    }
+
     Ok(())
 }
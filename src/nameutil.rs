@@ -120,6 +120,19 @@ static KEYWORDS: Lazy<HashMap<&'static str, String>> = Lazy::new(|| {
     .collect()
 });
 
+/// Removes the first matching `prefix_` from a function name, unless nothing
+/// would be left of it.
+pub fn strip_symbol_prefix<'a, 'b>(
+    name: &'a str,
+    prefixes: impl IntoIterator<Item = &'b String>,
+) -> &'a str {
+    prefixes
+        .into_iter()
+        .find_map(|prefix| name.strip_prefix(prefix.as_str())?.strip_prefix('_'))
+        .filter(|stripped| !stripped.is_empty())
+        .unwrap_or(name)
+}
+
 pub fn signal_to_snake(signal: &str) -> String {
     signal.replace("::", "_").replace('-', "_")
 }
@@ -147,52 +160,51 @@ pub fn shared_lib_name_to_link_name(name: &str) -> &str {
     s
 }
 
+/// Replaces the `ffi` module at the start of `import`, an import from the
+/// generated crate, by `sys_crate_path` if it's configured.
+fn sys_crate_import(sys_crate_path: Option<&str>, import: &str) -> Option<String> {
+    let rest = import.strip_prefix("ffi")?;
+    if rest.is_empty() || rest.starts_with("::") {
+        sys_crate_path.map(|path| format!("{path}{rest}"))
+    } else {
+        None
+    }
+}
+
 pub fn use_glib_type(env: &crate::env::Env, import: &str) -> String {
-    format!(
-        "{}::{}",
-        if env.library.is_glib_crate() {
-            "crate"
-        } else {
-            "glib"
-        },
-        import
-    )
+    if env.library.is_glib_crate() {
+        sys_crate_import(env.config.sys_crate_path.as_deref(), import)
+            .unwrap_or_else(|| format!("crate::{import}"))
+    } else {
+        format!("glib::{import}")
+    }
 }
 
 pub fn use_glib_if_needed(env: &crate::env::Env, import: &str) -> String {
-    format!(
-        "{}{}",
-        if env.library.is_glib_crate() {
-            ""
-        } else {
-            "glib::"
-        },
-        import
-    )
+    if env.library.is_glib_crate() {
+        sys_crate_import(env.config.sys_crate_path.as_deref(), import)
+            .unwrap_or_else(|| import.to_owned())
+    } else {
+        format!("glib::{import}")
+    }
 }
 
 pub fn use_gio_type(env: &crate::env::Env, import: &str) -> String {
-    format!(
-        "{}::{}",
-        if env.library.is_crate("Gio") {
-            "crate"
-        } else {
-            "gio"
-        },
-        import
-    )
+    if env.library.is_crate("Gio") {
+        sys_crate_import(env.config.sys_crate_path.as_deref(), import)
+            .unwrap_or_else(|| format!("crate::{import}"))
+    } else {
+        format!("gio::{import}")
+    }
 }
 
 pub fn use_gtk_type(env: &crate::env::Env, import: &str) -> String {
-    format!(
-        "{}::{}",
-        if env.library.is_crate("Gtk") {
-            "crate"
-        } else {
-            "gtk"
-        },
-        import
-    )
+    if env.library.is_crate("Gtk") {
+        sys_crate_import(env.config.sys_crate_path.as_deref(), import)
+            .unwrap_or_else(|| format!("crate::{import}"))
+    } else {
+        format!("gtk::{import}")
+    }
 }
 
 pub fn is_gstring(name: &str) -> bool {
@@ -217,6 +229,21 @@ mod tests {
         assert_eq!(name, "StatusIcon");
     }
 
+    #[test]
+    fn sys_crate_imports() {
+        assert_eq!(
+            sys_crate_import(Some("gtk_sys"), "ffi::gtk_tree_path_new_from_string").as_deref(),
+            Some("gtk_sys::gtk_tree_path_new_from_string")
+        );
+        assert_eq!(
+            sys_crate_import(Some("crate::ffi"), "ffi").as_deref(),
+            Some("crate::ffi")
+        );
+        assert_eq!(sys_crate_import(None, "ffi::gpointer"), None);
+        assert_eq!(sys_crate_import(Some("gtk_sys"), "ffi_helpers::X"), None);
+        assert_eq!(sys_crate_import(Some("gtk_sys"), "Value"), None);
+    }
+
     // #[test]
     // fn strip_prefix_g() {
     // assert_eq!(strip_prefix("G", "GBusType"), "BusType");
@@ -242,6 +269,22 @@ mod tests {
         assert_eq!(file_name_sys("funcs"), "funcs.rs");
     }
 
+    #[test]
+    fn strip_symbol_prefix_works() {
+        let prefixes = ["vendor".to_owned(), "legacy_foo".to_owned()];
+        assert_eq!(
+            strip_symbol_prefix("vendor_do_thing", &prefixes),
+            "do_thing"
+        );
+        assert_eq!(strip_symbol_prefix("legacy_foo_bar", &prefixes), "bar");
+        assert_eq!(
+            strip_symbol_prefix("vendorish_thing", &prefixes),
+            "vendorish_thing"
+        );
+        assert_eq!(strip_symbol_prefix("vendor_", &prefixes), "vendor_");
+        assert_eq!(strip_symbol_prefix("do_thing", None), "do_thing");
+    }
+
     #[test]
     fn signal_to_snake_works() {
         assert_eq!(signal_to_snake("changed"), "changed");
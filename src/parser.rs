@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -13,37 +14,163 @@ use crate::{
 
 const EMPTY_CTYPE: &str = "/*EMPTY*/";
 
+/// Local names of all attributes used by the GIR schema.
+const KNOWN_ATTRIBUTES: &[&str] = &[
+    "abstract",
+    "action",
+    "allow-none",
+    "async-func",
+    "bits",
+    "caller-allocates",
+    "closure",
+    "column",
+    "construct",
+    "construct-only",
+    "copy-function",
+    "default-value",
+    "deprecated",
+    "deprecated-version",
+    "destroy",
+    "detailed",
+    "direction",
+    "disguised",
+    "emitter",
+    "error-domain",
+    "filename",
+    "final",
+    "finish-func",
+    "fixed-size",
+    "foreign",
+    "free-function",
+    "fundamental",
+    "get-property",
+    "get-type",
+    "get-value-func",
+    "getter",
+    "identifier",
+    "identifier-prefixes",
+    "introspectable",
+    "invoker",
+    "is-gtype-struct-for",
+    "length",
+    "line",
+    "moved-to",
+    "name",
+    "nick",
+    "no-hooks",
+    "no-recurse",
+    "nullable",
+    "opaque",
+    "optional",
+    "parent",
+    "pointer",
+    "prefix",
+    "private",
+    "readable",
+    "ref-func",
+    "scope",
+    "set-property",
+    "set-value-func",
+    "setter",
+    "shadowed-by",
+    "shadows",
+    "shared-library",
+    "skip",
+    "space",
+    "stability",
+    "symbol-prefix",
+    "symbol-prefixes",
+    "sync-func",
+    "throws",
+    "transfer-ownership",
+    "type",
+    "type-name",
+    "type-struct",
+    "unref-func",
+    "value",
+    "version",
+    "when",
+    "writable",
+    "zero-terminated",
+];
+
+/// Elements that aren't read in included namespaces.
+const DEPENDENCY_SKIPPED_ELEMENTS: &[&str] = &[
+    "doc",
+    "doc-deprecated",
+    "doc-version",
+    "docsection",
+    "source-position",
+];
+
+/// Returns an error message for every attribute in the given `.gir` file that
+/// is not part of the GIR schema.
+pub fn unknown_attributes(path: &Path) -> Result<Vec<String>, String> {
+    XmlParser::from_path(path)?.unknown_attributes(KNOWN_ATTRIBUTES)
+}
+
+/// Returns the libraries (`name-version`) included by the given `.gir` file.
+pub fn read_includes(path: &Path) -> Result<Vec<String>, String> {
+    XmlParser::from_path(path)?.document(|p, _| {
+        p.element_with_name("repository", |parser, _elem| {
+            let includes = parser.elements(|parser, elem| match elem.name() {
+                "include" => Ok(elem
+                    .attr("name")
+                    .zip(elem.attr("version"))
+                    .map(|(name, version)| format!("{name}-{version}"))),
+                _ => parser.ignore_element().map(|()| None),
+            })?;
+            Ok(includes.into_iter().flatten().collect())
+        })
+    })
+}
+
 pub fn is_empty_c_type(c_type: &str) -> bool {
     c_type == EMPTY_CTYPE
 }
 
 impl Library {
+    /// Reads the given library and, recursively, all the libraries it includes.
+    ///
+    /// Libraries in `gir_files` are read from the given file instead of being
+    /// searched in `dirs`. Included namespaces listed in
+    /// `ignore_missing_includes` are left empty when their `.gir` file can't
+    /// be found.
     pub fn read_file<P: AsRef<Path>>(
         &mut self,
         dirs: &[P],
+        gir_files: &HashMap<String, PathBuf>,
         libs: &mut Vec<String>,
+        ignore_missing_includes: &[String],
     ) -> Result<(), String> {
-        for dir in dirs {
-            let dir: &Path = dir.as_ref();
-            let file_name = make_file_name(dir, &libs[libs.len() - 1]);
-            let mut parser = match XmlParser::from_path(&file_name) {
-                Ok(p) => p,
-                _ => continue,
-            };
-            return parser.document(|p, _| {
-                p.element_with_name("repository", |sub_parser, _elem| {
-                    self.read_repository(dirs, sub_parser, libs)
-                })
-            });
+        let lib = &libs[libs.len() - 1];
+        let file_name = match find_gir_file(dirs, gir_files, lib) {
+            Some(file_name) => file_name,
+            #[cfg(feature = "typelib")]
+            None if has_typelib_file(dirs, lib) => {
+                return self.read_typelib(dirs, gir_files, libs, ignore_missing_includes);
+            }
+            None => return Err(missing_gir_file_message(dirs, libs)),
+        };
+        let mut parser = XmlParser::from_path(&file_name)?;
+        if libs.len() > 1 {
+            // Only the types of included namespaces are needed, not their docs.
+            parser.skip_elements(DEPENDENCY_SKIPPED_ELEMENTS);
         }
-        Err(format!("Couldn't find `{}`...", &libs[libs.len() - 1]))
+        parser.document(|p, _| {
+            p.element_with_name("repository", |sub_parser, _elem| {
+                self.read_repository(dirs, gir_files, sub_parser, libs, ignore_missing_includes)
+            })
+        })
     }
 
     fn read_repository<P: AsRef<Path>>(
         &mut self,
         dirs: &[P],
+        gir_files: &HashMap<String, PathBuf>,
         parser: &mut XmlParser<'_>,
         libs: &mut Vec<String>,
+        ignore_missing_includes: &[String],
     ) -> Result<(), String> {
         let mut packages = Vec::new();
         let mut includes = Vec::new();
@@ -60,8 +187,16 @@ impl Library {
                                     libs.join("::")
                                 ));
                             }
+                            if find_gir_file(dirs, gir_files, &lib).is_none()
+                                && !has_typelib_file(dirs, &lib)
+                                && ignore_missing_includes.iter().any(|x| x == name)
+                            {
+                                warn!("`{lib}.gir` not found, ignoring the `{name}` namespace");
+                                self.add_namespace(name);
+                                return Ok(());
+                            }
                             libs.push(lib);
-                            self.read_file(dirs, libs)?;
+                            self.read_file(dirs, gir_files, libs, ignore_missing_includes)?;
                             libs.pop();
                         }
                     }
@@ -82,7 +217,7 @@ impl Library {
                 std::mem::take(&mut includes),
             ),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
         Ok(())
     }
@@ -160,7 +295,8 @@ impl Library {
         let c_type = self.read_object_c_type(parser, elem)?;
         let symbol_prefix = elem.attr_required("symbol-prefix").map(ToOwned::to_owned)?;
         let type_struct = elem.attr("type-struct").map(ToOwned::to_owned);
-        let get_type = elem.attr_required("get-type")?;
+        // Can be configured with `get_type` if missing
+        let get_type = elem.attr("get-type").unwrap_or_default();
         let version = self.read_version(parser, ns_id, elem)?;
         let deprecated_version = self.read_deprecated_version(parser, ns_id, elem)?;
         let is_fundamental = elem.attr("fundamental").map_or(false, |x| x == "1");
@@ -184,11 +320,15 @@ impl Library {
         let mut doc = None;
         let mut doc_deprecated = None;
         let mut union_count = 1;
+        let mut struct_count = 1;
 
         parser.elements(|parser, elem| match elem.name() {
             "constructor" | "function" | "method" => {
                 self.read_function_to_vec(parser, ns_id, elem, &mut fns)
             }
+            "record" => self
+                .read_nested_record(parser, ns_id, elem, class_name, c_type, &mut struct_count)
+                .map(|f| fields.push(f)),
             "implements" => self.read_type(parser, ns_id, elem).map(|r| {
                 impls.push(r.0);
             }),
@@ -237,7 +377,7 @@ impl Library {
                     union_count += 1;
                 }),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         let parent = elem
@@ -277,7 +417,7 @@ impl Library {
         ns_id: u16,
         elem: &Element,
     ) -> Result<(), String> {
-        if let Some(typ) = self.read_record(parser, ns_id, elem, None, None)? {
+        if let Some(typ) = self.read_record(parser, ns_id, elem, None, None, None)? {
             let name = typ.get_name();
             self.add_type(ns_id, &name, typ);
         }
@@ -291,16 +431,23 @@ impl Library {
         elem: &Element,
         parent_name_prefix: Option<&str>,
         parent_ctype_prefix: Option<&str>,
+        nested_names: Option<(&str, &str)>,
     ) -> Result<Option<Type>, String> {
-        let record_name = elem.attr_required("name")?;
-        // Records starting with `_` are intended to be private and should not be bound
-        if record_name.starts_with('_') {
-            parser.ignore_element()?;
-            return Ok(None);
-        }
+        // Nested records are named after their parent and are always needed
+        // for its layout
+        let (record_name, c_type) = if let Some(names) = nested_names {
+            names
+        } else {
+            let record_name = elem.attr_required("name")?;
+            // Records starting with `_` are intended to be private and should not be bound
+            if record_name.starts_with('_') {
+                parser.ignore_element()?;
+                return Ok(None);
+            }
+            (record_name, elem.attr_required("type")?)
+        };
         let is_class_record = record_name.ends_with("Class");
 
-        let c_type = elem.attr_required("type")?;
         let symbol_prefix = elem.attr("symbol-prefix").map(ToOwned::to_owned);
         let get_type = elem.attr("get-type").map(ToOwned::to_owned);
         let gtype_struct_for = elem.attr("is-gtype-struct-for");
@@ -313,11 +460,33 @@ impl Library {
         let mut doc = None;
         let mut doc_deprecated = None;
         let mut union_count = 1;
+        let mut struct_count = 1;
 
         parser.elements(|parser, elem| match elem.name() {
             "constructor" | "function" | "method" => {
                 self.read_function_to_vec(parser, ns_id, elem, &mut fns)
             }
+            "record" => {
+                let parent_name = format!(
+                    "{}{}",
+                    parent_name_prefix.map_or_else(String::new, |s| { format!("{s}_") }),
+                    record_name
+                );
+                let parent_ctype = format!(
+                    "{}{}",
+                    parent_ctype_prefix.map_or_else(String::new, |s| { format!("{s}_") }),
+                    c_type
+                );
+                self.read_nested_record(
+                    parser,
+                    ns_id,
+                    elem,
+                    &parent_name,
+                    &parent_ctype,
+                    &mut struct_count,
+                )
+                .map(|f| fields.push(f))
+            }
             "union" => self
                 .read_union(parser, ns_id, elem, Some(record_name), Some(c_type))
                 .map(|mut u| {
@@ -383,7 +552,7 @@ impl Library {
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "source-position" => parser.ignore_element(),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         let typ = Type::Record(Record {
@@ -413,6 +582,42 @@ impl Library {
         Ok(Some(typ))
     }
 
+    /// Reads an anonymous struct nested in a record or class as a record named
+    /// `{parent_name}_{field_name}`, and returns the field of the parent
+    /// that contains it.
+    fn read_nested_record(
+        &mut self,
+        parser: &mut XmlParser<'_>,
+        ns_id: u16,
+        elem: &Element,
+        parent_name: &str,
+        parent_ctype: &str,
+        struct_count: &mut usize,
+    ) -> Result<Field, String> {
+        let field_name = if let Some(field_name) = elem.attr("name") {
+            field_name.into()
+        } else {
+            format!("s{struct_count}")
+        };
+        *struct_count += 1;
+
+        let name = format!("{parent_name}_{field_name}");
+        let c_type = format!("{parent_ctype}_{field_name}");
+        let r = match self.read_record(parser, ns_id, elem, None, None, Some((&name, &c_type)))? {
+            Some(Type::Record(r)) => r,
+            _ => unreachable!("nested records are always read"),
+        };
+        let doc = r.doc.clone();
+
+        Ok(Field {
+            name: field_name,
+            typ: Type::record(self, r, ns_id),
+            doc,
+            c_type: Some(c_type),
+            ..Field::default()
+        })
+    }
+
     fn read_named_union(
         &mut self,
         parser: &mut XmlParser<'_>,
@@ -464,12 +669,23 @@ impl Library {
                 self.read_function_to_vec(parser, ns_id, elem, &mut fns)
             }
             "record" => {
+                // Records starting with `_` are intended to be private and should not be bound
+                if elem
+                    .attr("name")
+                    .map_or(false, |name| name.starts_with('_'))
+                {
+                    return parser.ignore_element();
+                }
                 let mut r = match self.read_record(
                     parser,
                     ns_id,
                     elem,
                     parent_name_prefix,
                     parent_ctype_prefix,
+                    Some((
+                        elem.attr("name").unwrap_or(""),
+                        elem.attr("type").unwrap_or(""),
+                    )),
                 )? {
                     Some(Type::Record(r)) => r,
                     _ => return Ok(()),
@@ -514,7 +730,7 @@ impl Library {
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         Ok(Union {
@@ -542,6 +758,7 @@ impl Library {
         let mut ret = None;
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Vec::new();
 
         parser.elements(|parser, elem| match elem.name() {
             "parameters" => self
@@ -557,8 +774,8 @@ impl Library {
             "source-position" => parser.ignore_element(),
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            "attribute" => read_attribute(parser, elem).map(|a| attributes.push(a)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         let throws = elem.attr_bool("throws", false);
@@ -593,6 +810,13 @@ impl Library {
                 deprecated_version,
                 doc,
                 doc_deprecated,
+                attributes,
+                shadows: elem.attr("shadows").map(ToOwned::to_owned),
+                shadowed_by: elem.attr("shadowed-by").map(ToOwned::to_owned),
+                finish_func: elem.attr("finish-func").map(ToOwned::to_owned),
+                sync_func: elem.attr("sync-func").map(ToOwned::to_owned),
+                async_func: elem.attr("async-func").map(ToOwned::to_owned),
+                invoker: elem.attr("invoker").map(ToOwned::to_owned),
             })
         } else {
             Err(parser.fail("Missing <return-value> element"))
@@ -632,7 +856,7 @@ impl Library {
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         if let Some((tid, c_type, array_length)) = typ {
@@ -675,7 +899,8 @@ impl Library {
         let c_type = self.read_object_c_type(parser, elem)?;
         let symbol_prefix = elem.attr_required("symbol-prefix").map(ToOwned::to_owned)?;
         let type_struct = elem.attr("type-struct").map(ToOwned::to_owned);
-        let get_type = elem.attr_required("get-type")?;
+        // Can be configured with `get_type` if missing
+        let get_type = elem.attr("get-type").unwrap_or_default();
         let version = self.read_version(parser, ns_id, elem)?;
         let deprecated_version = self.read_deprecated_version(parser, ns_id, elem)?;
 
@@ -709,7 +934,7 @@ impl Library {
                 .map(|v| vfns.push(v)),
             "source-position" => parser.ignore_element(),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         let typ = Type::Interface(Interface {
@@ -762,7 +987,7 @@ impl Library {
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "source-position" => parser.ignore_element(),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         let typ = Type::Bitfield(Bitfield {
@@ -813,7 +1038,7 @@ impl Library {
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "source-position" => parser.ignore_element(),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         let typ = Type::Enumeration(Enumeration {
@@ -886,7 +1111,7 @@ impl Library {
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "source-position" => parser.ignore_element(),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         if let Some((typ, c_type, _array_length)) = inner {
@@ -946,7 +1171,7 @@ impl Library {
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         if let Some((typ, c_type, _array_length)) = inner {
@@ -977,6 +1202,7 @@ impl Library {
         let member_name = elem.attr_required("name")?;
         let value = elem.attr_required("value")?;
         let c_identifier = elem.attr("identifier").map(|x| x.into());
+        let nick = elem.attr("nick").map(ToOwned::to_owned);
         let version = self.read_version(parser, ns_id, elem)?;
         let deprecated_version = self.read_deprecated_version(parser, ns_id, elem)?;
 
@@ -987,12 +1213,13 @@ impl Library {
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         Ok(Member {
             name: member_name.into(),
             value: value.into(),
+            nick,
             doc,
             doc_deprecated,
             c_identifier: c_identifier.unwrap_or_else(|| member_name.into()),
@@ -1021,6 +1248,7 @@ impl Library {
         let mut ret = None;
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Vec::new();
 
         parser.elements(|parser, elem| match elem.name() {
             "parameters" => self
@@ -1040,8 +1268,8 @@ impl Library {
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "doc-version" => parser.ignore_element(),
             "source-position" => parser.ignore_element(),
-            "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            "attribute" => read_attribute(parser, elem).map(|a| attributes.push(a)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
         // The last argument of a callback is ALWAYS user data, so it has to be marked as such
         // in case it's missing.
@@ -1080,6 +1308,13 @@ impl Library {
                 deprecated_version,
                 doc,
                 doc_deprecated,
+                attributes,
+                shadows: elem.attr("shadows").map(ToOwned::to_owned),
+                shadowed_by: elem.attr("shadowed-by").map(ToOwned::to_owned),
+                finish_func: elem.attr("finish-func").map(ToOwned::to_owned),
+                sync_func: elem.attr("sync-func").map(ToOwned::to_owned),
+                async_func: elem.attr("async-func").map(ToOwned::to_owned),
+                invoker: None,
             })
         } else {
             Err(parser.fail_with_position(
@@ -1110,7 +1345,15 @@ impl Library {
         elem: &Element,
         is_callback: bool,
     ) -> Result<Option<Function>, String> {
-        if elem.attr("moved-to").is_some() {
+        if let Some(moved_to) = elem.attr("moved-to") {
+            // The function is also declared where it was moved to
+            let c_identifier = match elem.attr("identifier") {
+                Some(c_identifier) => c_identifier,
+                None => elem.attr_required("name")?,
+            };
+            self.namespace_mut(ns_id)
+                .moved_functions
+                .push((c_identifier.to_owned(), moved_to.to_owned()));
             return parser.ignore_element().map(|_| None);
         }
         self.read_function(parser, ns_id, kind_str, elem, is_callback)
@@ -1162,7 +1405,7 @@ impl Library {
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
         if let Some(ret) = ret {
             Ok(Signal {
@@ -1191,12 +1434,13 @@ impl Library {
         allow_no_ctype: bool,
         for_method: bool,
     ) -> Result<Vec<Parameter>, String> {
-        parser.elements(|parser, elem| match elem.name() {
-            "parameter" | "instance-parameter" => {
-                self.read_parameter(parser, ns_id, elem, allow_no_ctype, for_method)
-            }
-            _ => Err(parser.unexpected_element(elem)),
-        })
+        let parameters = parser.elements(|parser, elem| match elem.name() {
+            "parameter" | "instance-parameter" => self
+                .read_parameter(parser, ns_id, elem, allow_no_ctype, for_method)
+                .map(Some),
+            _ => parser.skip_unexpected_element(elem).map(|_| None),
+        })?;
+        Ok(parameters.into_iter().flatten().collect())
     }
 
     fn read_parameter(
@@ -1255,17 +1499,28 @@ impl Library {
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         if let Some((tid, c_type, mut array_length)) = typ {
             if for_method {
                 array_length = array_length.map(|l| l + 1);
             }
+            let c_type = c_type.unwrap();
+            // Strings are always allocated by the callee, some GIR files still
+            // mark `gchar **` out parameters as caller-allocates. The type may
+            // not be resolved yet, so only the basic type ids are compared
+            let is_string = [
+                TypeId::tid_utf8(),
+                TypeId::tid_filename(),
+                TypeId::tid_os_string(),
+            ]
+            .contains(&tid);
+            let caller_allocates = caller_allocates && !(is_string && c_type.ends_with("**"));
             Ok(Parameter {
                 name: param_name.into(),
                 typ: tid,
-                c_type: c_type.unwrap(),
+                c_type,
                 instance_parameter,
                 direction,
                 transfer,
@@ -1346,7 +1601,7 @@ impl Library {
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "attribute" => parser.ignore_element(),
-            _ => Err(parser.unexpected_element(elem)),
+            _ => parser.skip_unexpected_element(elem),
         })?;
 
         if has_empty_type_tag {
@@ -1400,10 +1655,14 @@ impl Library {
         let c_type = elem.attr("type").map(|s| s.into());
         let array_length = elem.attr("length").and_then(|s| s.parse().ok());
 
-        let inner = parser.elements(|parser, elem| match elem.name() {
-            "type" | "array" => self.read_type(parser, ns_id, elem),
-            _ => Err(parser.unexpected_element(elem)),
-        })?;
+        let inner = parser
+            .elements(|parser, elem| match elem.name() {
+                "type" | "array" => self.read_type(parser, ns_id, elem).map(Some),
+                _ => parser.skip_unexpected_element(elem).map(|_| None),
+            })?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
 
         if inner.is_empty() || type_name == "GLib.ByteArray" {
             if type_name == "array" {
@@ -1493,9 +1752,152 @@ impl Library {
     }
 }
 
+/// Reads a custom `<attribute name="..." value="..."/>` annotation.
+fn read_attribute(parser: &mut XmlParser<'_>, elem: &Element) -> Result<(String, String), String> {
+    let name = elem.attr_required("name")?.to_owned();
+    let value = elem.attr("value").unwrap_or_default().to_owned();
+    parser.ignore_element()?;
+    Ok((name, value))
+}
+
 fn make_file_name(dir: &Path, name: &str) -> PathBuf {
     let mut path = dir.to_path_buf();
     let name = format!("{name}.gir");
     path.push(name);
     path
 }
+
+fn find_gir_file<P: AsRef<Path>>(
+    dirs: &[P],
+    gir_files: &HashMap<String, PathBuf>,
+    name: &str,
+) -> Option<PathBuf> {
+    if let Some(path) = gir_files.get(name) {
+        return Some(path.clone());
+    }
+    dirs.iter()
+        .map(|dir| make_file_name(dir.as_ref(), name))
+        .find(|path| path.is_file())
+}
+
+#[cfg(feature = "typelib")]
+fn has_typelib_file<P: AsRef<Path>>(dirs: &[P], name: &str) -> bool {
+    crate::typelib::find_typelib_file(dirs, name).is_some()
+}
+
+#[cfg(not(feature = "typelib"))]
+fn has_typelib_file<P: AsRef<Path>>(_dirs: &[P], _name: &str) -> bool {
+    false
+}
+
+/// Lists the searched paths of the last library in `libs` and the `.gir` files
+/// of the same namespace in other versions or with a different case.
+fn missing_gir_file_message<P: AsRef<Path>>(dirs: &[P], libs: &[String]) -> String {
+    let name = &libs[libs.len() - 1];
+    let namespace = name.split('-').next().unwrap_or(name).to_lowercase();
+    let mut message = format!("Couldn't find `{name}.gir`");
+    if libs.len() > 1 {
+        message.push_str(&format!(
+            ", included by `{}`",
+            libs[..libs.len() - 1].join("::")
+        ));
+    }
+    message.push_str(
+        ". If its types are all configured as manual, add the namespace to \
+         `options.ignore_missing_includes`.\nSearched:",
+    );
+    let mut suggestions = Vec::new();
+    for dir in dirs {
+        let dir = dir.as_ref();
+        message.push_str(&format!("\n  {}", make_file_name(dir, name).display()));
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            let stem = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(stem) if path.extension().map_or(false, |ext| ext == "gir") => stem,
+                _ => continue,
+            };
+            if stem.split('-').next().unwrap_or(stem).to_lowercase() == namespace {
+                suggestions.push(path.display().to_string());
+            }
+        }
+    }
+    if !suggestions.is_empty() {
+        suggestions.sort();
+        message.push_str("\nSimilarly named files:");
+        for suggestion in suggestions {
+            message.push_str(&format!("\n  {suggestion}"));
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_parameter(xml: &str) -> Parameter {
+        let mut library = Library::new("Test");
+        let mut parser = XmlParser::new(xml.as_bytes());
+        parser
+            .document(|p, _| {
+                p.element_with_name("parameter", |p, elem| {
+                    library.read_parameter(p, MAIN_NAMESPACE, elem, false, false)
+                })
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_string_out_parameter_caller_allocates() {
+        let par = parse_parameter(
+            r#"<parameter xmlns:c="http://www.gtk.org/introspection/c/1.0" name="text"
+                direction="out" caller-allocates="1" transfer-ownership="full">
+                <type name="utf8" c:type="gchar**"/>
+            </parameter>"#,
+        );
+        assert_eq!(par.direction, ParameterDirection::Out);
+        assert!(!par.caller_allocates);
+
+        let par = parse_parameter(
+            r#"<parameter xmlns:c="http://www.gtk.org/introspection/c/1.0" name="value"
+                direction="out" caller-allocates="1" transfer-ownership="none">
+                <type name="GObject.Value" c:type="GValue*"/>
+            </parameter>"#,
+        );
+        assert!(par.caller_allocates);
+    }
+
+    #[test]
+    fn test_union_private_record() {
+        let mut library = Library::new("Test");
+        let mut parser = XmlParser::new(
+            r#"<union xmlns:c="http://www.gtk.org/introspection/c/1.0" name="Data"
+                c:type="TestData">
+                <record name="_priv">
+                    <field name="x"><type name="gint" c:type="gint"/></field>
+                </record>
+                <record name="s">
+                    <field name="y"><type name="gint" c:type="gint"/></field>
+                </record>
+            </union>"#
+                .as_bytes(),
+        );
+        let union = parser
+            .document(|p, _| {
+                p.element_with_name("union", |p, elem| {
+                    library.read_union(p, MAIN_NAMESPACE, elem, None, None)
+                })
+            })
+            .unwrap();
+        let fields = union
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, ["s"]);
+        assert_eq!(union.fields[0].c_type.as_deref(), Some("TestData_s"));
+    }
+}
@@ -0,0 +1,1312 @@
+//! Reads libraries from their compiled `.typelib` files through
+//! libgirepository, for libraries that don't ship their `.gir` file.
+//!
+//! Typelibs don't contain documentation, versions, C headers, packages and
+//! most C type names, so the C types used in the generated code are derived
+//! from the GType names or from the C prefix of the namespace.
+//!
+//! This uses the `libgirepository-1.0` API of gobject-introspection. GLib 2.80
+//! deprecated it in favor of its own `girepository-2.0`, whose API is quite
+//! different (e.g. there's no default repository and info types are GTypes),
+//! but both read the same typelib format, and gobject-introspection still
+//! ships the former.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+    path::{Path, PathBuf},
+    ptr,
+    sync::Mutex,
+};
+
+use log::warn;
+use once_cell::sync::Lazy;
+
+use crate::{case::CaseExt, config::gobjects::GStatus, library::*};
+
+#[allow(non_camel_case_types, dead_code)]
+mod ffi {
+    use std::os::raw::{c_char, c_int, c_void};
+
+    pub type GIRepository = c_void;
+    pub type GITypelib = c_void;
+    /// All `GI*Info` types are `GIBaseInfo`s
+    pub type GIBaseInfo = c_void;
+
+    #[repr(C)]
+    pub struct GError {
+        pub domain: u32,
+        pub code: c_int,
+        pub message: *mut c_char,
+    }
+
+    #[repr(C)]
+    pub union GIArgument {
+        pub v_boolean: c_int,
+        pub v_int8: i8,
+        pub v_uint8: u8,
+        pub v_int16: i16,
+        pub v_uint16: u16,
+        pub v_int32: i32,
+        pub v_uint32: u32,
+        pub v_int64: i64,
+        pub v_uint64: u64,
+        pub v_float: f32,
+        pub v_double: f64,
+        pub v_string: *mut c_char,
+        pub v_pointer: *mut c_void,
+    }
+
+    pub const GI_INFO_TYPE_FUNCTION: c_int = 1;
+    pub const GI_INFO_TYPE_CALLBACK: c_int = 2;
+    pub const GI_INFO_TYPE_STRUCT: c_int = 3;
+    pub const GI_INFO_TYPE_BOXED: c_int = 4;
+    pub const GI_INFO_TYPE_ENUM: c_int = 5;
+    pub const GI_INFO_TYPE_FLAGS: c_int = 6;
+    pub const GI_INFO_TYPE_OBJECT: c_int = 7;
+    pub const GI_INFO_TYPE_INTERFACE: c_int = 8;
+    pub const GI_INFO_TYPE_CONSTANT: c_int = 9;
+    pub const GI_INFO_TYPE_UNION: c_int = 11;
+
+    pub const GI_TYPE_TAG_VOID: c_int = 0;
+    pub const GI_TYPE_TAG_BOOLEAN: c_int = 1;
+    pub const GI_TYPE_TAG_INT8: c_int = 2;
+    pub const GI_TYPE_TAG_UINT8: c_int = 3;
+    pub const GI_TYPE_TAG_INT16: c_int = 4;
+    pub const GI_TYPE_TAG_UINT16: c_int = 5;
+    pub const GI_TYPE_TAG_INT32: c_int = 6;
+    pub const GI_TYPE_TAG_UINT32: c_int = 7;
+    pub const GI_TYPE_TAG_INT64: c_int = 8;
+    pub const GI_TYPE_TAG_UINT64: c_int = 9;
+    pub const GI_TYPE_TAG_FLOAT: c_int = 10;
+    pub const GI_TYPE_TAG_DOUBLE: c_int = 11;
+    pub const GI_TYPE_TAG_GTYPE: c_int = 12;
+    pub const GI_TYPE_TAG_UTF8: c_int = 13;
+    pub const GI_TYPE_TAG_FILENAME: c_int = 14;
+    pub const GI_TYPE_TAG_ARRAY: c_int = 15;
+    pub const GI_TYPE_TAG_INTERFACE: c_int = 16;
+    pub const GI_TYPE_TAG_GLIST: c_int = 17;
+    pub const GI_TYPE_TAG_GSLIST: c_int = 18;
+    pub const GI_TYPE_TAG_GHASH: c_int = 19;
+    pub const GI_TYPE_TAG_ERROR: c_int = 20;
+    pub const GI_TYPE_TAG_UNICHAR: c_int = 21;
+
+    pub const GI_ARRAY_TYPE_C: c_int = 0;
+    pub const GI_ARRAY_TYPE_ARRAY: c_int = 1;
+    pub const GI_ARRAY_TYPE_PTR_ARRAY: c_int = 2;
+
+    pub const GI_DIRECTION_OUT: c_int = 1;
+    pub const GI_DIRECTION_INOUT: c_int = 2;
+
+    pub const GI_TRANSFER_CONTAINER: c_int = 1;
+    pub const GI_TRANSFER_EVERYTHING: c_int = 2;
+
+    pub const GI_SCOPE_TYPE_CALL: c_int = 1;
+    pub const GI_SCOPE_TYPE_ASYNC: c_int = 2;
+    pub const GI_SCOPE_TYPE_NOTIFIED: c_int = 3;
+
+    pub const GI_FUNCTION_IS_METHOD: c_int = 1 << 0;
+    pub const GI_FUNCTION_IS_CONSTRUCTOR: c_int = 1 << 1;
+
+    pub const G_PARAM_READABLE: c_int = 1 << 0;
+    pub const G_PARAM_WRITABLE: c_int = 1 << 1;
+    pub const G_PARAM_CONSTRUCT: c_int = 1 << 2;
+    pub const G_PARAM_CONSTRUCT_ONLY: c_int = 1 << 3;
+
+    pub const G_SIGNAL_DETAILED: c_int = 1 << 4;
+    pub const G_SIGNAL_ACTION: c_int = 1 << 5;
+
+    #[link(name = "glib-2.0")]
+    extern "C" {
+        pub fn g_error_free(error: *mut GError);
+        pub fn g_strfreev(str_array: *mut *mut c_char);
+    }
+
+    #[link(name = "girepository-1.0")]
+    extern "C" {
+        pub fn g_irepository_get_default() -> *mut GIRepository;
+        pub fn g_irepository_prepend_search_path(directory: *const c_char);
+        pub fn g_irepository_require(
+            repository: *mut GIRepository,
+            namespace_: *const c_char,
+            version: *const c_char,
+            flags: c_int,
+            error: *mut *mut GError,
+        ) -> *mut GITypelib;
+        pub fn g_irepository_get_immediate_dependencies(
+            repository: *mut GIRepository,
+            namespace_: *const c_char,
+        ) -> *mut *mut c_char;
+        pub fn g_irepository_get_n_infos(
+            repository: *mut GIRepository,
+            namespace_: *const c_char,
+        ) -> c_int;
+        pub fn g_irepository_get_info(
+            repository: *mut GIRepository,
+            namespace_: *const c_char,
+            index: c_int,
+        ) -> *mut GIBaseInfo;
+        pub fn g_irepository_find_by_name(
+            repository: *mut GIRepository,
+            namespace_: *const c_char,
+            name: *const c_char,
+        ) -> *mut GIBaseInfo;
+        pub fn g_irepository_get_shared_library(
+            repository: *mut GIRepository,
+            namespace_: *const c_char,
+        ) -> *const c_char;
+        pub fn g_irepository_get_c_prefix(
+            repository: *mut GIRepository,
+            namespace_: *const c_char,
+        ) -> *const c_char;
+
+        pub fn g_base_info_ref(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_base_info_unref(info: *mut GIBaseInfo);
+        pub fn g_base_info_get_type(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_base_info_get_name(info: *mut GIBaseInfo) -> *const c_char;
+        pub fn g_base_info_get_namespace(info: *mut GIBaseInfo) -> *const c_char;
+        pub fn g_base_info_get_container(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+
+        pub fn g_registered_type_info_get_type_name(info: *mut GIBaseInfo) -> *const c_char;
+        pub fn g_registered_type_info_get_type_init(info: *mut GIBaseInfo) -> *const c_char;
+
+        pub fn g_callable_info_is_method(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_callable_info_can_throw_gerror(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_callable_info_get_n_args(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_callable_info_get_arg(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_callable_info_get_return_type(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_callable_info_get_caller_owns(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_callable_info_may_return_null(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_callable_info_get_instance_ownership_transfer(info: *mut GIBaseInfo) -> c_int;
+
+        pub fn g_function_info_get_symbol(info: *mut GIBaseInfo) -> *const c_char;
+        pub fn g_function_info_get_flags(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_vfunc_info_get_invoker(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_signal_info_get_flags(info: *mut GIBaseInfo) -> c_int;
+
+        pub fn g_arg_info_get_direction(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_arg_info_get_ownership_transfer(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_arg_info_may_be_null(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_arg_info_is_caller_allocates(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_arg_info_get_scope(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_arg_info_get_closure(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_arg_info_get_destroy(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_arg_info_get_type(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+
+        pub fn g_type_info_get_tag(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_type_info_is_pointer(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_type_info_get_param_type(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_type_info_get_interface(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_type_info_get_array_length(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_type_info_get_array_fixed_size(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_type_info_get_array_type(info: *mut GIBaseInfo) -> c_int;
+
+        pub fn g_enum_info_get_n_values(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_enum_info_get_value(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_enum_info_get_n_methods(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_enum_info_get_method(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_enum_info_get_error_domain(info: *mut GIBaseInfo) -> *const c_char;
+        pub fn g_value_info_get_value(info: *mut GIBaseInfo) -> i64;
+
+        pub fn g_struct_info_get_n_fields(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_struct_info_get_field(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_struct_info_get_n_methods(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_struct_info_get_method(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+
+        pub fn g_union_info_get_n_fields(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_union_info_get_field(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_union_info_get_n_methods(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_union_info_get_method(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+
+        pub fn g_field_info_get_type(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_field_info_get_size(info: *mut GIBaseInfo) -> c_int;
+
+        pub fn g_property_info_get_flags(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_property_info_get_type(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_property_info_get_ownership_transfer(info: *mut GIBaseInfo) -> c_int;
+
+        pub fn g_object_info_get_parent(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_object_info_get_abstract(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_object_info_get_fundamental(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_object_info_get_class_struct(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_object_info_get_ref_function(info: *mut GIBaseInfo) -> *const c_char;
+        pub fn g_object_info_get_unref_function(info: *mut GIBaseInfo) -> *const c_char;
+        pub fn g_object_info_get_n_interfaces(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_object_info_get_interface(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_object_info_get_n_fields(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_object_info_get_field(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_object_info_get_n_properties(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_object_info_get_property(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_object_info_get_n_methods(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_object_info_get_method(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_object_info_get_n_signals(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_object_info_get_signal(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_object_info_get_n_vfuncs(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_object_info_get_vfunc(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+
+        pub fn g_interface_info_get_n_prerequisites(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_interface_info_get_prerequisite(
+            info: *mut GIBaseInfo,
+            n: c_int,
+        ) -> *mut GIBaseInfo;
+        pub fn g_interface_info_get_iface_struct(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_interface_info_get_n_properties(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_interface_info_get_property(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_interface_info_get_n_methods(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_interface_info_get_method(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_interface_info_get_n_signals(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_interface_info_get_signal(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+        pub fn g_interface_info_get_n_vfuncs(info: *mut GIBaseInfo) -> c_int;
+        pub fn g_interface_info_get_vfunc(info: *mut GIBaseInfo, n: c_int) -> *mut GIBaseInfo;
+
+        pub fn g_constant_info_get_type(info: *mut GIBaseInfo) -> *mut GIBaseInfo;
+        pub fn g_constant_info_get_value(info: *mut GIBaseInfo, value: *mut GIArgument) -> c_int;
+        pub fn g_constant_info_free_value(info: *mut GIBaseInfo, value: *mut GIArgument);
+    }
+}
+
+type CountFn = unsafe extern "C" fn(*mut ffi::GIBaseInfo) -> c_int;
+type ChildFn = unsafe extern "C" fn(*mut ffi::GIBaseInfo, c_int) -> *mut ffi::GIBaseInfo;
+
+/// An owned reference to a `GIBaseInfo`
+struct Info(*mut ffi::GIBaseInfo);
+
+impl Info {
+    /// Takes ownership of a reference returned by libgirepository
+    fn from_ptr(ptr: *mut ffi::GIBaseInfo) -> Option<Self> {
+        if ptr.is_null() {
+            None
+        } else {
+            Some(Self(ptr))
+        }
+    }
+
+    fn info_type(&self) -> c_int {
+        unsafe { ffi::g_base_info_get_type(self.0) }
+    }
+
+    fn name(&self) -> String {
+        unsafe { string(ffi::g_base_info_get_name(self.0)) }.unwrap_or_default()
+    }
+
+    fn namespace(&self) -> String {
+        unsafe { string(ffi::g_base_info_get_namespace(self.0)) }.unwrap_or_default()
+    }
+
+    fn children(&self, count: CountFn, child: ChildFn) -> Vec<Info> {
+        let n = unsafe { count(self.0) };
+        (0..n)
+            .filter_map(|i| Info::from_ptr(unsafe { child(self.0, i) }))
+            .collect()
+    }
+}
+
+impl Drop for Info {
+    fn drop(&mut self) {
+        unsafe { ffi::g_base_info_unref(self.0) }
+    }
+}
+
+/// Copies a string owned by libgirepository
+unsafe fn string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+    }
+}
+
+fn c_string(s: &str) -> CString {
+    CString::new(s).expect("no NUL in names")
+}
+
+fn transfer(transfer: c_int) -> Transfer {
+    match transfer {
+        ffi::GI_TRANSFER_CONTAINER => Transfer::Container,
+        ffi::GI_TRANSFER_EVERYTHING => Transfer::Full,
+        _ => Transfer::None,
+    }
+}
+
+/// Index of another argument, `-1` if there's none
+fn index(index: c_int) -> Option<usize> {
+    usize::try_from(index).ok()
+}
+
+pub fn find_typelib_file<P: AsRef<Path>>(dirs: &[P], name: &str) -> Option<PathBuf> {
+    dirs.iter()
+        .map(|dir| dir.as_ref().join(format!("{name}.typelib")))
+        .find(|path| path.is_file())
+}
+
+impl Library {
+    /// Reads the last library in `libs` from its `.typelib` file and,
+    /// recursively, the libraries it depends on, which may be `.gir` files.
+    pub fn read_typelib<P: AsRef<Path>>(
+        &mut self,
+        dirs: &[P],
+        gir_files: &HashMap<String, PathBuf>,
+        libs: &mut Vec<String>,
+        ignore_missing_includes: &[String],
+    ) -> Result<(), String> {
+        // Directories already added to the search path of the default repository
+        static SEARCH_PATH: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(Default::default);
+
+        let lib = libs[libs.len() - 1].clone();
+        let (name, version) = lib
+            .split_once('-')
+            .ok_or_else(|| format!("Library `{lib}` has no version"))?;
+        let repo = unsafe { ffi::g_irepository_get_default() };
+        let mut search_path = SEARCH_PATH.lock().unwrap();
+        // Prepending in reverse order keeps the first directory first
+        for dir in dirs.iter().rev() {
+            if search_path.insert(dir.as_ref().to_owned()) {
+                let dir = c_string(&dir.as_ref().to_string_lossy());
+                unsafe { ffi::g_irepository_prepend_search_path(dir.as_ptr()) };
+            }
+        }
+        drop(search_path);
+
+        let namespace = c_string(name);
+        let version = c_string(version);
+        let mut error = ptr::null_mut();
+        let typelib = unsafe {
+            ffi::g_irepository_require(repo, namespace.as_ptr(), version.as_ptr(), 0, &mut error)
+        };
+        if typelib.is_null() {
+            let message = unsafe {
+                let message = string((*error).message);
+                ffi::g_error_free(error);
+                message
+            };
+            return Err(format!(
+                "Couldn't load `{lib}.typelib`: {}",
+                message.unwrap_or_default()
+            ));
+        }
+
+        // Like the includes of `.gir` files, the dependencies are read first
+        for dependency in dependencies(repo, &namespace) {
+            let dependency_name = dependency.split('-').next().unwrap_or(&dependency);
+            if self.find_namespace(dependency_name).is_some() {
+                continue;
+            }
+            if libs.iter().any(|x| *x == dependency) {
+                return Err(format!(
+                    "`{}` includes itself (full path:`{}`)!",
+                    dependency,
+                    libs.join("::")
+                ));
+            }
+            libs.push(dependency);
+            self.read_file(dirs, gir_files, libs, ignore_missing_includes)?;
+            libs.pop();
+        }
+
+        let ns_id = self.add_namespace(name);
+        Reader {
+            library: self,
+            repo,
+            ns_id,
+        }
+        .read_namespace(&namespace);
+        Ok(())
+    }
+}
+
+fn dependencies(repo: *mut ffi::GIRepository, namespace: &CStr) -> Vec<String> {
+    let mut dependencies = Vec::new();
+    unsafe {
+        let array = ffi::g_irepository_get_immediate_dependencies(repo, namespace.as_ptr());
+        if array.is_null() {
+            return dependencies;
+        }
+        let mut i = 0;
+        while !(*array.add(i)).is_null() {
+            dependencies.extend(string(*array.add(i)));
+            i += 1;
+        }
+        ffi::g_strfreev(array);
+    }
+    dependencies
+}
+
+struct Reader<'a> {
+    library: &'a mut Library,
+    repo: *mut ffi::GIRepository,
+    ns_id: u16,
+}
+
+impl Reader<'_> {
+    fn read_namespace(&mut self, namespace: &CStr) {
+        let shared_library = unsafe {
+            string(ffi::g_irepository_get_shared_library(
+                self.repo,
+                namespace.as_ptr(),
+            ))
+        };
+        let c_prefix = unsafe {
+            string(ffi::g_irepository_get_c_prefix(
+                self.repo,
+                namespace.as_ptr(),
+            ))
+        };
+        {
+            let ns = self.library.namespace_mut(self.ns_id);
+            if let Some(shared_library) = shared_library {
+                ns.shared_library = shared_library
+                    .split(',')
+                    .filter(|x| !x.is_empty())
+                    .map(String::from)
+                    .collect();
+            }
+            if let Some(c_prefix) = c_prefix {
+                ns.identifier_prefixes = c_prefix.split(',').map(String::from).collect();
+                ns.symbol_prefixes = ns
+                    .identifier_prefixes
+                    .iter()
+                    .map(|p| p.to_snake())
+                    .collect();
+            }
+        }
+
+        let n = unsafe { ffi::g_irepository_get_n_infos(self.repo, namespace.as_ptr()) };
+        for i in 0..n {
+            let info = match Info::from_ptr(unsafe {
+                ffi::g_irepository_get_info(self.repo, namespace.as_ptr(), i)
+            }) {
+                Some(info) => info,
+                None => continue,
+            };
+            let typ = match info.info_type() {
+                ffi::GI_INFO_TYPE_FUNCTION => {
+                    let func = self.read_function(&info, FunctionKind::Global);
+                    self.library.add_function(self.ns_id, func);
+                    continue;
+                }
+                ffi::GI_INFO_TYPE_CONSTANT => {
+                    if let Some(constant) = self.read_constant(&info) {
+                        self.library.add_constant(self.ns_id, constant);
+                    }
+                    continue;
+                }
+                ffi::GI_INFO_TYPE_CALLBACK => {
+                    let c_identifier = self.c_type(&info);
+                    Type::Function(self.read_callable(
+                        &info,
+                        FunctionKind::Function,
+                        Some(c_identifier),
+                        false,
+                    ))
+                }
+                ffi::GI_INFO_TYPE_STRUCT => Type::Record(self.read_struct(&info)),
+                ffi::GI_INFO_TYPE_UNION => Type::Union(self.read_union(&info)),
+                ffi::GI_INFO_TYPE_ENUM => Type::Enumeration(self.read_enumeration(&info)),
+                ffi::GI_INFO_TYPE_FLAGS => Type::Bitfield(self.read_bitfield(&info)),
+                ffi::GI_INFO_TYPE_OBJECT => Type::Class(self.read_class(&info)),
+                ffi::GI_INFO_TYPE_INTERFACE => Type::Interface(self.read_interface(&info)),
+                _ => {
+                    warn!("Unsupported typelib entry `{}`", info.name());
+                    continue;
+                }
+            };
+            self.library.add_type(self.ns_id, &info.name(), typ);
+        }
+    }
+
+    /// C type of the type described by `info`, without pointer
+    fn c_type(&self, info: &Info) -> String {
+        if matches!(
+            info.info_type(),
+            ffi::GI_INFO_TYPE_STRUCT
+                | ffi::GI_INFO_TYPE_BOXED
+                | ffi::GI_INFO_TYPE_ENUM
+                | ffi::GI_INFO_TYPE_FLAGS
+                | ffi::GI_INFO_TYPE_OBJECT
+                | ffi::GI_INFO_TYPE_INTERFACE
+                | ffi::GI_INFO_TYPE_UNION
+        ) {
+            if let Some(name) = unsafe { string(ffi::g_registered_type_info_get_type_name(info.0)) }
+            {
+                return name;
+            }
+        }
+        let namespace = c_string(&info.namespace());
+        let c_prefix = unsafe {
+            string(ffi::g_irepository_get_c_prefix(
+                self.repo,
+                namespace.as_ptr(),
+            ))
+        }
+        .unwrap_or_default();
+        format!(
+            "{}{}",
+            c_prefix.split(',').next().unwrap_or_default(),
+            info.name()
+        )
+    }
+
+    fn get_type(&self, info: &Info) -> Option<String> {
+        unsafe { string(ffi::g_registered_type_info_get_type_init(info.0)) }
+            .filter(|get_type| get_type != "intern")
+    }
+
+    /// The `c:symbol-prefix` of a type, which doesn't include the symbol
+    /// prefix of the namespace
+    fn symbol_prefix(&self, get_type: Option<&str>, c_type: &str) -> String {
+        let symbol = get_type
+            .and_then(|get_type| get_type.strip_suffix("_get_type"))
+            .map_or_else(|| c_type.to_snake(), ToOwned::to_owned);
+        self.library
+            .namespace(self.ns_id)
+            .symbol_prefixes
+            .iter()
+            .find_map(|prefix| symbol.strip_prefix(&format!("{prefix}_")))
+            .map_or_else(|| symbol.clone(), ToOwned::to_owned)
+    }
+
+    fn type_id(&mut self, info: &Info) -> TypeId {
+        let name = format!("{}.{}", info.namespace(), info.name());
+        self.library.find_or_stub_type(self.ns_id, &name)
+    }
+
+    fn basic_type(&mut self, name: &str) -> TypeId {
+        self.library.find_or_stub_type(self.ns_id, name)
+    }
+
+    fn read_type(&mut self, typ: &Info) -> (TypeId, String) {
+        let (name, c_type) = match unsafe { ffi::g_type_info_get_tag(typ.0) } {
+            ffi::GI_TYPE_TAG_VOID if unsafe { ffi::g_type_info_is_pointer(typ.0) } != 0 => {
+                ("gpointer", "gpointer")
+            }
+            ffi::GI_TYPE_TAG_VOID => ("none", "void"),
+            ffi::GI_TYPE_TAG_BOOLEAN => ("gboolean", "gboolean"),
+            ffi::GI_TYPE_TAG_INT8 => ("gint8", "gint8"),
+            ffi::GI_TYPE_TAG_UINT8 => ("guint8", "guint8"),
+            ffi::GI_TYPE_TAG_INT16 => ("gint16", "gint16"),
+            ffi::GI_TYPE_TAG_UINT16 => ("guint16", "guint16"),
+            ffi::GI_TYPE_TAG_INT32 => ("gint32", "gint32"),
+            ffi::GI_TYPE_TAG_UINT32 => ("guint32", "guint32"),
+            ffi::GI_TYPE_TAG_INT64 => ("gint64", "gint64"),
+            ffi::GI_TYPE_TAG_UINT64 => ("guint64", "guint64"),
+            ffi::GI_TYPE_TAG_FLOAT => ("gfloat", "gfloat"),
+            ffi::GI_TYPE_TAG_DOUBLE => ("gdouble", "gdouble"),
+            ffi::GI_TYPE_TAG_GTYPE => ("GType", "GType"),
+            ffi::GI_TYPE_TAG_UTF8 => ("utf8", "gchar*"),
+            ffi::GI_TYPE_TAG_FILENAME => ("filename", "gchar*"),
+            ffi::GI_TYPE_TAG_UNICHAR => ("gunichar", "gunichar"),
+            ffi::GI_TYPE_TAG_ERROR => ("GLib.Error", "GError*"),
+            ffi::GI_TYPE_TAG_ARRAY => return self.read_array(typ),
+            ffi::GI_TYPE_TAG_GLIST => return self.read_container(typ, "GLib.List", "GList*", 1),
+            ffi::GI_TYPE_TAG_GSLIST => return self.read_container(typ, "GLib.SList", "GSList*", 1),
+            ffi::GI_TYPE_TAG_GHASH => {
+                return self.read_container(typ, "GLib.HashTable", "GHashTable*", 2)
+            }
+            ffi::GI_TYPE_TAG_INTERFACE => return self.read_interface_type(typ),
+            tag => {
+                warn!("Unknown typelib type tag {tag}");
+                ("gpointer", "gpointer")
+            }
+        };
+        (self.basic_type(name), c_type.to_owned())
+    }
+
+    fn read_array(&mut self, typ: &Info) -> (TypeId, String) {
+        let (inner_tid, inner_c_type) =
+            match Info::from_ptr(unsafe { ffi::g_type_info_get_param_type(typ.0, 0) }) {
+                Some(inner) => self.read_type(&inner),
+                None => (self.basic_type("gpointer"), "gpointer".to_owned()),
+            };
+        match unsafe { ffi::g_type_info_get_array_type(typ.0) } {
+            ffi::GI_ARRAY_TYPE_C => {
+                let fixed_size =
+                    u16::try_from(unsafe { ffi::g_type_info_get_array_fixed_size(typ.0) }).ok();
+                let c_type = if fixed_size.is_some() {
+                    inner_c_type.clone()
+                } else {
+                    format!("{inner_c_type}*")
+                };
+                let tid = Type::c_array(self.library, inner_tid, fixed_size, Some(inner_c_type));
+                (tid, c_type)
+            }
+            ffi::GI_ARRAY_TYPE_ARRAY => {
+                let tid = Type::container(self.library, "GLib.Array", vec![inner_tid])
+                    .expect("GLib.Array container");
+                (tid, "GArray*".to_owned())
+            }
+            ffi::GI_ARRAY_TYPE_PTR_ARRAY => {
+                let tid = Type::container(self.library, "GLib.PtrArray", vec![inner_tid])
+                    .expect("GLib.PtrArray container");
+                (tid, "GPtrArray*".to_owned())
+            }
+            _ => (self.basic_type("GLib.ByteArray"), "GByteArray*".to_owned()),
+        }
+    }
+
+    fn read_container(
+        &mut self,
+        typ: &Info,
+        name: &str,
+        c_type: &str,
+        n_params: c_int,
+    ) -> (TypeId, String) {
+        let mut inner = Vec::new();
+        for i in 0..n_params {
+            inner.push(
+                match Info::from_ptr(unsafe { ffi::g_type_info_get_param_type(typ.0, i) }) {
+                    Some(param) => self.read_type(&param).0,
+                    None => self.basic_type("gpointer"),
+                },
+            );
+        }
+        let tid =
+            Type::container(self.library, name, inner).unwrap_or_else(|| self.basic_type(name));
+        (tid, c_type.to_owned())
+    }
+
+    fn read_interface_type(&mut self, typ: &Info) -> (TypeId, String) {
+        let info = match Info::from_ptr(unsafe { ffi::g_type_info_get_interface(typ.0) }) {
+            Some(info) => info,
+            None => return (self.basic_type("gpointer"), "gpointer".to_owned()),
+        };
+        let tid = self.type_id(&info);
+        let mut c_type = self.c_type(&info);
+        if unsafe { ffi::g_type_info_is_pointer(typ.0) } != 0
+            || matches!(
+                info.info_type(),
+                ffi::GI_INFO_TYPE_OBJECT | ffi::GI_INFO_TYPE_INTERFACE
+            )
+        {
+            c_type.push('*');
+        }
+        (tid, c_type)
+    }
+
+    /// Reads the callback of a field that isn't declared on its own, like the
+    /// virtual methods of class structs.
+    fn read_inline_callback(&mut self, typ: &Info) -> Option<TypeId> {
+        if unsafe { ffi::g_type_info_get_tag(typ.0) } != ffi::GI_TYPE_TAG_INTERFACE {
+            return None;
+        }
+        let info = Info::from_ptr(unsafe { ffi::g_type_info_get_interface(typ.0) })?;
+        if info.info_type() != ffi::GI_INFO_TYPE_CALLBACK {
+            return None;
+        }
+        let namespace = c_string(&info.namespace());
+        let name = c_string(&info.name());
+        if Info::from_ptr(unsafe {
+            ffi::g_irepository_find_by_name(self.repo, namespace.as_ptr(), name.as_ptr())
+        })
+        .is_some()
+        {
+            return None;
+        }
+        let func = self.read_callable(&info, FunctionKind::Function, None, false);
+        Some(Type::function(self.library, func))
+    }
+
+    fn array_length(typ: &Info, for_method: bool) -> Option<u32> {
+        let length = u32::try_from(unsafe { ffi::g_type_info_get_array_length(typ.0) }).ok();
+        length.map(|length| if for_method { length + 1 } else { length })
+    }
+
+    fn read_function(&mut self, info: &Info, kind: FunctionKind) -> Function {
+        let flags = unsafe { ffi::g_function_info_get_flags(info.0) };
+        let kind = if flags & ffi::GI_FUNCTION_IS_CONSTRUCTOR != 0 {
+            FunctionKind::Constructor
+        } else if flags & ffi::GI_FUNCTION_IS_METHOD != 0 {
+            FunctionKind::Method
+        } else {
+            kind
+        };
+        let symbol = unsafe { string(ffi::g_function_info_get_symbol(info.0)) };
+        self.read_callable(info, kind, symbol, false)
+    }
+
+    fn read_functions(&mut self, info: &Info, count: CountFn, child: ChildFn) -> Vec<Function> {
+        info.children(count, child)
+            .iter()
+            .map(|func| self.read_function(func, FunctionKind::Function))
+            .collect()
+    }
+
+    fn read_virtual_methods(
+        &mut self,
+        info: &Info,
+        count: CountFn,
+        child: ChildFn,
+    ) -> Vec<Function> {
+        info.children(count, child)
+            .iter()
+            .map(|vfunc| {
+                let mut func = self.read_callable(
+                    vfunc,
+                    FunctionKind::VirtualMethod,
+                    Some(vfunc.name()),
+                    false,
+                );
+                func.invoker = Info::from_ptr(unsafe { ffi::g_vfunc_info_get_invoker(vfunc.0) })
+                    .map(|invoker| invoker.name());
+                func
+            })
+            .collect()
+    }
+
+    /// Signals don't have an instance parameter, unlike the other methods.
+    fn read_callable(
+        &mut self,
+        info: &Info,
+        kind: FunctionKind,
+        c_identifier: Option<String>,
+        is_signal: bool,
+    ) -> Function {
+        let is_method = !is_signal && unsafe { ffi::g_callable_info_is_method(info.0) } != 0;
+        let mut parameters = Vec::new();
+        if is_method {
+            parameters.push(self.read_instance_parameter(info));
+        }
+        for arg in info.children(
+            ffi::g_callable_info_get_n_args,
+            ffi::g_callable_info_get_arg,
+        ) {
+            parameters.push(self.read_arg(&arg, is_method));
+        }
+        // The last argument of a callback is ALWAYS user data, see the parser
+        if info.info_type() == ffi::GI_INFO_TYPE_CALLBACK {
+            if let Some(last) = parameters.last_mut().filter(|p| p.closure.is_none()) {
+                last.closure = Some(2000);
+            }
+        }
+
+        let throws = unsafe { ffi::g_callable_info_can_throw_gerror(info.0) } != 0;
+        if throws {
+            parameters.push(Parameter {
+                name: "error".into(),
+                typ: self.basic_type("GLib.Error"),
+                c_type: "GError**".into(),
+                instance_parameter: false,
+                direction: ParameterDirection::Out,
+                transfer: Transfer::Full,
+                caller_allocates: false,
+                nullable: Nullable(true),
+                array_length: None,
+                is_error: true,
+                doc: None,
+                scope: ParameterScope::None,
+                closure: None,
+                destroy: None,
+            });
+        }
+
+        let typ = Info(unsafe { ffi::g_callable_info_get_return_type(info.0) });
+        let (tid, c_type) = self.read_type(&typ);
+        let ret = Parameter {
+            name: String::new(),
+            typ: tid,
+            c_type,
+            instance_parameter: false,
+            direction: ParameterDirection::Return,
+            transfer: transfer(unsafe { ffi::g_callable_info_get_caller_owns(info.0) }),
+            caller_allocates: false,
+            nullable: Nullable(unsafe { ffi::g_callable_info_may_return_null(info.0) } != 0),
+            array_length: Self::array_length(&typ, is_method),
+            is_error: false,
+            doc: None,
+            scope: ParameterScope::None,
+            closure: None,
+            destroy: None,
+        };
+
+        Function {
+            name: info.name(),
+            c_identifier,
+            kind,
+            parameters,
+            ret,
+            throws,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Vec::new(),
+            shadows: None,
+            shadowed_by: None,
+            finish_func: None,
+            sync_func: None,
+            async_func: None,
+            invoker: None,
+        }
+    }
+
+    fn read_instance_parameter(&mut self, info: &Info) -> Parameter {
+        // The caller doesn't own the returned container
+        let container =
+            Info(unsafe { ffi::g_base_info_ref(ffi::g_base_info_get_container(info.0)) });
+        let typ = self.type_id(&container);
+        let c_type = format!("{}*", self.c_type(&container));
+        Parameter {
+            name: container.name().to_snake(),
+            typ,
+            c_type,
+            instance_parameter: true,
+            direction: ParameterDirection::In,
+            transfer: transfer(unsafe {
+                ffi::g_callable_info_get_instance_ownership_transfer(info.0)
+            }),
+            caller_allocates: false,
+            nullable: Nullable(false),
+            array_length: None,
+            is_error: false,
+            doc: None,
+            scope: ParameterScope::None,
+            closure: None,
+            destroy: None,
+        }
+    }
+
+    fn read_arg(&mut self, arg: &Info, for_method: bool) -> Parameter {
+        let typ = Info(unsafe { ffi::g_arg_info_get_type(arg.0) });
+        let (tid, mut c_type) = self.read_type(&typ);
+        let direction = match unsafe { ffi::g_arg_info_get_direction(arg.0) } {
+            ffi::GI_DIRECTION_OUT => ParameterDirection::Out,
+            ffi::GI_DIRECTION_INOUT => ParameterDirection::InOut,
+            _ => ParameterDirection::In,
+        };
+        let caller_allocates = unsafe { ffi::g_arg_info_is_caller_allocates(arg.0) } != 0;
+        if direction.is_out() && !caller_allocates {
+            c_type.push('*');
+        }
+        Parameter {
+            name: arg.name(),
+            typ: tid,
+            c_type,
+            instance_parameter: false,
+            direction,
+            transfer: transfer(unsafe { ffi::g_arg_info_get_ownership_transfer(arg.0) }),
+            caller_allocates,
+            nullable: Nullable(unsafe { ffi::g_arg_info_may_be_null(arg.0) } != 0),
+            array_length: Self::array_length(&typ, for_method),
+            is_error: false,
+            doc: None,
+            scope: match unsafe { ffi::g_arg_info_get_scope(arg.0) } {
+                ffi::GI_SCOPE_TYPE_CALL => ParameterScope::Call,
+                ffi::GI_SCOPE_TYPE_ASYNC => ParameterScope::Async,
+                ffi::GI_SCOPE_TYPE_NOTIFIED => ParameterScope::Notified,
+                _ => ParameterScope::None,
+            },
+            closure: index(unsafe { ffi::g_arg_info_get_closure(arg.0) }),
+            destroy: index(unsafe { ffi::g_arg_info_get_destroy(arg.0) }),
+        }
+    }
+
+    fn read_fields(&mut self, info: &Info, count: CountFn, child: ChildFn) -> Vec<Field> {
+        info.children(count, child)
+            .iter()
+            .map(|field| {
+                let typ = Info(unsafe { ffi::g_field_info_get_type(field.0) });
+                let (typ, c_type) = match self.read_inline_callback(&typ) {
+                    Some(tid) => (tid, None),
+                    None => {
+                        let (tid, c_type) = self.read_type(&typ);
+                        (tid, Some(c_type))
+                    }
+                };
+                let bits = unsafe { ffi::g_field_info_get_size(field.0) };
+                Field {
+                    name: field.name(),
+                    typ,
+                    c_type,
+                    bits: u8::try_from(bits).ok().filter(|&bits| bits > 0),
+                    ..Field::default()
+                }
+            })
+            .collect()
+    }
+
+    fn read_properties(&mut self, info: &Info, count: CountFn, child: ChildFn) -> Vec<Property> {
+        info.children(count, child)
+            .iter()
+            .map(|property| {
+                let flags = unsafe { ffi::g_property_info_get_flags(property.0) };
+                let typ = Info(unsafe { ffi::g_property_info_get_type(property.0) });
+                let (typ, c_type) = self.read_type(&typ);
+                Property {
+                    name: property.name(),
+                    readable: flags & ffi::G_PARAM_READABLE != 0,
+                    writable: flags & ffi::G_PARAM_WRITABLE != 0,
+                    construct: flags & ffi::G_PARAM_CONSTRUCT != 0,
+                    construct_only: flags & ffi::G_PARAM_CONSTRUCT_ONLY != 0,
+                    typ,
+                    c_type: Some(c_type),
+                    transfer: transfer(unsafe {
+                        ffi::g_property_info_get_ownership_transfer(property.0)
+                    }),
+                    version: None,
+                    deprecated_version: None,
+                    doc: None,
+                    doc_deprecated: None,
+                }
+            })
+            .collect()
+    }
+
+    fn read_signals(&mut self, info: &Info, count: CountFn, child: ChildFn) -> Vec<Signal> {
+        info.children(count, child)
+            .iter()
+            .map(|signal| {
+                let flags = unsafe { ffi::g_signal_info_get_flags(signal.0) };
+                let func = self.read_callable(signal, FunctionKind::Function, None, true);
+                Signal {
+                    name: func.name,
+                    parameters: func.parameters,
+                    ret: func.ret,
+                    is_action: flags & ffi::G_SIGNAL_ACTION != 0,
+                    is_detailed: flags & ffi::G_SIGNAL_DETAILED != 0,
+                    version: None,
+                    deprecated_version: None,
+                    doc: None,
+                    doc_deprecated: None,
+                }
+            })
+            .collect()
+    }
+
+    fn read_class(&mut self, info: &Info) -> Class {
+        let c_type = self.c_type(info);
+        let glib_get_type = self.get_type(info);
+        let symbol_prefix = self.symbol_prefix(glib_get_type.as_deref(), &c_type);
+        let parent = Info::from_ptr(unsafe { ffi::g_object_info_get_parent(info.0) })
+            .map(|parent| self.type_id(&parent));
+        let implements = info
+            .children(
+                ffi::g_object_info_get_n_interfaces,
+                ffi::g_object_info_get_interface,
+            )
+            .iter()
+            .map(|iface| self.type_id(iface))
+            .collect();
+        let is_fundamental = unsafe { ffi::g_object_info_get_fundamental(info.0) } != 0;
+        let (ref_fn, unref_fn) = if is_fundamental {
+            unsafe {
+                (
+                    string(ffi::g_object_info_get_ref_function(info.0)),
+                    string(ffi::g_object_info_get_unref_function(info.0)),
+                )
+            }
+        } else {
+            (None, None)
+        };
+
+        Class {
+            name: info.name(),
+            c_type,
+            symbol_prefix,
+            type_struct: Info::from_ptr(unsafe { ffi::g_object_info_get_class_struct(info.0) })
+                .map(|type_struct| type_struct.name()),
+            c_class_type: None, // this will be resolved during postprocessing
+            glib_get_type: glib_get_type.unwrap_or_default(),
+            fields: self.read_fields(
+                info,
+                ffi::g_object_info_get_n_fields,
+                ffi::g_object_info_get_field,
+            ),
+            functions: self.read_functions(
+                info,
+                ffi::g_object_info_get_n_methods,
+                ffi::g_object_info_get_method,
+            ),
+            virtual_methods: self.read_virtual_methods(
+                info,
+                ffi::g_object_info_get_n_vfuncs,
+                ffi::g_object_info_get_vfunc,
+            ),
+            signals: self.read_signals(
+                info,
+                ffi::g_object_info_get_n_signals,
+                ffi::g_object_info_get_signal,
+            ),
+            properties: self.read_properties(
+                info,
+                ffi::g_object_info_get_n_properties,
+                ffi::g_object_info_get_property,
+            ),
+            parent,
+            implements,
+            final_type: false, // this will be set during postprocessing
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            is_abstract: unsafe { ffi::g_object_info_get_abstract(info.0) } != 0,
+            is_fundamental,
+            ref_fn,
+            unref_fn,
+        }
+    }
+
+    fn read_interface(&mut self, info: &Info) -> Interface {
+        let c_type = self.c_type(info);
+        let glib_get_type = self.get_type(info);
+        let symbol_prefix = self.symbol_prefix(glib_get_type.as_deref(), &c_type);
+        let prerequisites = info
+            .children(
+                ffi::g_interface_info_get_n_prerequisites,
+                ffi::g_interface_info_get_prerequisite,
+            )
+            .iter()
+            .map(|prerequisite| self.type_id(prerequisite))
+            .collect();
+
+        Interface {
+            name: info.name(),
+            c_type,
+            symbol_prefix,
+            type_struct: Info::from_ptr(unsafe { ffi::g_interface_info_get_iface_struct(info.0) })
+                .map(|type_struct| type_struct.name()),
+            c_class_type: None, // this will be resolved during postprocessing
+            glib_get_type: glib_get_type.unwrap_or_default(),
+            functions: self.read_functions(
+                info,
+                ffi::g_interface_info_get_n_methods,
+                ffi::g_interface_info_get_method,
+            ),
+            virtual_methods: self.read_virtual_methods(
+                info,
+                ffi::g_interface_info_get_n_vfuncs,
+                ffi::g_interface_info_get_vfunc,
+            ),
+            signals: self.read_signals(
+                info,
+                ffi::g_interface_info_get_n_signals,
+                ffi::g_interface_info_get_signal,
+            ),
+            properties: self.read_properties(
+                info,
+                ffi::g_interface_info_get_n_properties,
+                ffi::g_interface_info_get_property,
+            ),
+            prerequisites,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+        }
+    }
+
+    fn read_struct(&mut self, info: &Info) -> Record {
+        let c_type = self.c_type(info);
+        let glib_get_type = self.get_type(info);
+        Record {
+            name: info.name(),
+            symbol_prefix: Some(self.symbol_prefix(glib_get_type.as_deref(), &c_type)),
+            c_type,
+            glib_get_type,
+            fields: self.read_fields(
+                info,
+                ffi::g_struct_info_get_n_fields,
+                ffi::g_struct_info_get_field,
+            ),
+            functions: self.read_functions(
+                info,
+                ffi::g_struct_info_get_n_methods,
+                ffi::g_struct_info_get_method,
+            ),
+            ..Record::default()
+        }
+    }
+
+    fn read_union(&mut self, info: &Info) -> Union {
+        let c_type = self.c_type(info);
+        let glib_get_type = self.get_type(info);
+        Union {
+            name: info.name(),
+            symbol_prefix: Some(self.symbol_prefix(glib_get_type.as_deref(), &c_type)),
+            c_type: Some(c_type),
+            glib_get_type,
+            fields: self.read_fields(
+                info,
+                ffi::g_union_info_get_n_fields,
+                ffi::g_union_info_get_field,
+            ),
+            functions: self.read_functions(
+                info,
+                ffi::g_union_info_get_n_methods,
+                ffi::g_union_info_get_method,
+            ),
+            doc: None,
+        }
+    }
+
+    /// The C identifiers of members aren't part of typelibs, so they are
+    /// derived from the C type like `GTK_ALIGN_START` from `GtkAlign`.
+    fn read_members(&mut self, info: &Info, c_type: &str, is_flags: bool) -> Vec<Member> {
+        let prefix = c_type.to_snake().to_uppercase();
+        info.children(ffi::g_enum_info_get_n_values, ffi::g_enum_info_get_value)
+            .iter()
+            .map(|member| {
+                let name = member.name();
+                let value = unsafe { ffi::g_value_info_get_value(member.0) };
+                Member {
+                    c_identifier: format!("{prefix}_{}", name.to_uppercase()),
+                    name,
+                    value: if is_flags {
+                        (value as u32).to_string()
+                    } else {
+                        value.to_string()
+                    },
+                    nick: None,
+                    doc: None,
+                    doc_deprecated: None,
+                    status: GStatus::Generate,
+                    version: None,
+                    deprecated_version: None,
+                }
+            })
+            .collect()
+    }
+
+    fn read_enumeration(&mut self, info: &Info) -> Enumeration {
+        let c_type = self.c_type(info);
+        Enumeration {
+            name: info.name(),
+            symbol_prefix: None,
+            members: self.read_members(info, &c_type, false),
+            functions: self.read_functions(
+                info,
+                ffi::g_enum_info_get_n_methods,
+                ffi::g_enum_info_get_method,
+            ),
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            error_domain: unsafe { string(ffi::g_enum_info_get_error_domain(info.0)) }
+                .map(ErrorDomain::Quark),
+            glib_get_type: self.get_type(info),
+            c_type,
+        }
+    }
+
+    fn read_bitfield(&mut self, info: &Info) -> Bitfield {
+        let c_type = self.c_type(info);
+        Bitfield {
+            name: info.name(),
+            symbol_prefix: None,
+            members: self.read_members(info, &c_type, true),
+            functions: self.read_functions(
+                info,
+                ffi::g_enum_info_get_n_methods,
+                ffi::g_enum_info_get_method,
+            ),
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            glib_get_type: self.get_type(info),
+            c_type,
+        }
+    }
+
+    fn read_constant(&mut self, info: &Info) -> Option<Constant> {
+        let typ = Info(unsafe { ffi::g_constant_info_get_type(info.0) });
+        let tag = unsafe { ffi::g_type_info_get_tag(typ.0) };
+        let (tid, c_type) = self.read_type(&typ);
+        let mut arg = ffi::GIArgument { v_uint64: 0 };
+        let value = unsafe {
+            ffi::g_constant_info_get_value(info.0, &mut arg);
+            let value = match tag {
+                ffi::GI_TYPE_TAG_BOOLEAN => Some((arg.v_boolean != 0).to_string()),
+                ffi::GI_TYPE_TAG_INT8 => Some(arg.v_int8.to_string()),
+                ffi::GI_TYPE_TAG_UINT8 => Some(arg.v_uint8.to_string()),
+                ffi::GI_TYPE_TAG_INT16 => Some(arg.v_int16.to_string()),
+                ffi::GI_TYPE_TAG_UINT16 => Some(arg.v_uint16.to_string()),
+                ffi::GI_TYPE_TAG_INT32 => Some(arg.v_int32.to_string()),
+                ffi::GI_TYPE_TAG_UINT32 => Some(arg.v_uint32.to_string()),
+                ffi::GI_TYPE_TAG_INT64 => Some(arg.v_int64.to_string()),
+                ffi::GI_TYPE_TAG_UINT64 => Some(arg.v_uint64.to_string()),
+                ffi::GI_TYPE_TAG_FLOAT => Some(arg.v_float.to_string()),
+                ffi::GI_TYPE_TAG_DOUBLE => Some(arg.v_double.to_string()),
+                ffi::GI_TYPE_TAG_UTF8 | ffi::GI_TYPE_TAG_FILENAME => string(arg.v_string),
+                _ => None,
+            };
+            ffi::g_constant_info_free_value(info.0, &mut arg);
+            value
+        };
+        let name = info.name();
+        let value = match value {
+            Some(value) => value,
+            None => {
+                warn!("Unsupported type of constant `{name}` in typelib");
+                return None;
+            }
+        };
+        let c_prefix = self
+            .library
+            .namespace(self.ns_id)
+            .symbol_prefixes
+            .first()
+            .map(|prefix| format!("{}_", prefix.to_uppercase()))
+            .unwrap_or_default();
+
+        Some(Constant {
+            c_identifier: format!("{c_prefix}{name}"),
+            name,
+            typ: tid,
+            c_type,
+            value,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The directory of the installed `GObject-2.0.typelib`
+    fn typelib_dir() -> PathBuf {
+        [
+            "/usr/lib/x86_64-linux-gnu/girepository-1.0",
+            "/usr/lib64/girepository-1.0",
+            "/usr/lib/girepository-1.0",
+            "/usr/local/lib/girepository-1.0",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .find(|dir| dir.join("GObject-2.0.typelib").is_file())
+        .expect("GObject-2.0.typelib isn't installed")
+    }
+
+    #[test]
+    fn test_read_typelib() {
+        let dirs = [typelib_dir()];
+        let mut library = Library::new("GObject");
+        library
+            .read_typelib(&dirs, &HashMap::new(), &mut vec!["GObject-2.0".into()], &[])
+            .unwrap();
+
+        // Dependencies are read as well
+        assert!(library.find_namespace("GLib").is_some());
+
+        let tid = library.find_type(MAIN_NAMESPACE, "GObject.Object").unwrap();
+        let class = match library.type_(tid) {
+            Type::Class(class) => class,
+            _ => panic!("GObject.Object isn't a class"),
+        };
+        assert_eq!(class.c_type, "GObject");
+        assert_eq!(class.glib_get_type, "g_object_get_type");
+        assert!(class.functions.iter().any(|f| f.name == "notify"));
+        assert!(class.signals.iter().any(|s| s.name == "notify"));
+
+        // The directories are only added once to the search path
+        let mut library = Library::new("GLib");
+        library
+            .read_typelib(&dirs, &HashMap::new(), &mut vec!["GLib-2.0".into()], &[])
+            .unwrap();
+        assert!(library.find_type(MAIN_NAMESPACE, "GLib.MainLoop").is_some());
+    }
+}
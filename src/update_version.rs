@@ -38,6 +38,7 @@ fn check_versions(param: &Parameter, current_version: &mut Option<Version>, lib:
         library::Type::Enumeration(c) => c.version,
         library::Type::Bitfield(c) => c.version,
         library::Type::Record(c) => c.version,
+        library::Type::Union(c) => c.version,
         library::Type::Interface(c) => c.version,
         _ => None,
     };
@@ -93,6 +94,7 @@ fn fix_versions_by_config(library: &mut Library, cfg: &Config) {
             Class(class) => class.version = version,
             Interface(interface) => interface.version = version,
             Record(record) => record.version = version,
+            Union(union) => union.version = version,
             Bitfield(flags) => flags.version = version,
             Enumeration(enum_) => enum_.version = version,
             _ => (),
@@ -118,6 +118,15 @@ impl ToCode for Chunk {
                 let s = format_block_one_line(&prefix, suffix, &value_strings, "", "");
                 vec![s]
             }
+            ResultMapReturn {
+                ref result,
+                ref value,
+            } => {
+                let value_strings = value.to_code(env);
+                let prefix = format!("{result}.map(|_| ");
+                let s = format_block_one_line(&prefix, ")", &value_strings, "", "");
+                vec![s]
+            }
             AssertErrorSanity => {
                 let assert = format!(
                     "debug_assert_eq!(is_ok == {}, !error.is_null());",
@@ -202,6 +211,22 @@ impl ToCode for Chunk {
                 let s = format_block_one_line("(", ")", &args, "", ",");
                 vec![format!("{func_name}{s};")]
             }
+            CatchUnwind {
+                ref body,
+                ref on_panic,
+            } => {
+                let mut code = format_block(
+                    "match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {",
+                    "})) {",
+                    &body.to_code(env),
+                );
+                let arms = [
+                    "Ok(ret) => ret,".to_owned(),
+                    format!("Err(_) => {on_panic},"),
+                ];
+                code.extend(format_block("", "}", &arms));
+                code
+            }
         }
     }
 }
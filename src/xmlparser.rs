@@ -29,6 +29,11 @@ pub struct XmlParser<'a> {
     /// Used to emits errors. Rc so that it can be cheaply shared with Element
     /// type.
     error_emitter: Rc<ErrorEmitter>,
+    /// Errors for the unexpected elements that were skipped, returned at the
+    /// end of the document.
+    unexpected_elements: Vec<String>,
+    /// Elements that `elements` skips without passing them to the callback.
+    skipped_elements: &'static [&'static str],
 }
 
 struct ErrorEmitter {
@@ -153,6 +158,8 @@ impl<'a> XmlParser<'a> {
                 error_emitter: Rc::new(ErrorEmitter {
                     path: Some(path.to_owned()),
                 }),
+                unexpected_elements: Vec::new(),
+                skipped_elements: &[],
             }),
         }
     }
@@ -164,9 +171,16 @@ impl<'a> XmlParser<'a> {
             peek_event: None,
             peek_position: TextPosition::new(),
             error_emitter: Rc::new(ErrorEmitter { path: None }),
+            unexpected_elements: Vec::new(),
+            skipped_elements: &[],
         }
     }
 
+    /// Makes `elements` skip the subtrees of elements with the given names.
+    pub fn skip_elements(&mut self, names: &'static [&'static str]) {
+        self.skipped_elements = names;
+    }
+
     /// Returns an error that combines current position and given error message.
     pub fn fail(&self, message: &str) -> String {
         self.error_emitter.emit(message, self.position())
@@ -182,6 +196,14 @@ impl<'a> XmlParser<'a> {
         self.error_emitter.emit(&message, elem.position())
     }
 
+    /// Skips an unexpected element and remembers the error, so that all of
+    /// them are reported together at the end of the document.
+    pub fn skip_unexpected_element(&mut self, elem: &Element) -> Result<(), String> {
+        let error = self.unexpected_element(elem);
+        self.unexpected_elements.push(error);
+        self.ignore_element()
+    }
+
     fn unexpected_event(&self, event: &XmlEvent) -> String {
         let message = format!("Unexpected event {event:?}");
         self.error_emitter.emit(&message, self.position())
@@ -230,7 +252,11 @@ impl<'a> XmlParser<'a> {
         let doc = self.start_document()?;
         let result = f(self, doc)?;
         self.end_document()?;
-        Ok(result)
+        if self.unexpected_elements.is_empty() {
+            Ok(result)
+        } else {
+            Err(self.unexpected_elements.join("\n"))
+        }
     }
 
     fn start_document(&mut self) -> Result<Document, String> {
@@ -253,7 +279,16 @@ impl<'a> XmlParser<'a> {
     {
         let mut results = Vec::new();
         loop {
+            let skipped = self.skipped_elements;
             match *self.peek_event() {
+                Ok(XmlEvent::StartElement { ref name, .. })
+                    if skipped.contains(&name.local_name.as_str()) =>
+                {
+                    // Ignore warning about unused result, we know event is OK.
+                    drop(self.next_event());
+                    self.ignore_element()?;
+                    self.end_element()?;
+                }
                 Ok(XmlEvent::StartElement { .. }) => {
                     let element = self.start_element()?;
                     results.push(f(self, &element)?);
@@ -322,26 +357,63 @@ impl<'a> XmlParser<'a> {
         Ok(result)
     }
 
+    /// Reads the rest of the document and returns an error message for every
+    /// attribute whose local name is not in `known`.
+    pub fn unknown_attributes(&mut self, known: &[&str]) -> Result<Vec<String>, String> {
+        let mut messages = Vec::new();
+        loop {
+            match self.next_event()? {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => {
+                    for attr in attributes {
+                        if !known.contains(&attr.name.local_name.as_str()) {
+                            let message = format!(
+                                "Unknown attribute `{}` on element <{}>",
+                                attr.name.local_name, name.local_name
+                            );
+                            messages.push(self.fail(&message));
+                        }
+                    }
+                }
+                XmlEvent::EndDocument => return Ok(messages),
+                _ => (),
+            }
+        }
+    }
+
     /// Ignore everything within current element.
+    ///
+    /// Events are pulled from the inner parser and dropped right away, only
+    /// the end of the current element is kept as the next event.
     pub fn ignore_element(&mut self) -> Result<(), String> {
         let mut depth = 1;
-        loop {
-            match *self.peek_event() {
-                Ok(XmlEvent::StartElement { .. }) => {
-                    // Ignore warning about unused result, we know event is OK.
-                    drop(self.next_event());
-                    depth += 1;
+        if let Some(event) = self.peek_event.take() {
+            match event? {
+                XmlEvent::StartElement { .. } => depth += 1,
+                e @ XmlEvent::EndElement { .. } => {
+                    self.peek_event = Some(Ok(e));
+                    return Ok(());
                 }
-                Ok(XmlEvent::EndElement { .. }) => {
+                _ => (),
+            }
+        }
+        loop {
+            match self.parser.next() {
+                Ok(XmlEvent::StartElement { .. }) => depth += 1,
+                Ok(e @ XmlEvent::EndElement { .. }) => {
                     depth -= 1;
-                    if depth > 0 {
-                        drop(self.next_event());
-                    } else {
+                    if depth == 0 {
+                        self.peek_event = Some(Ok(e));
+                        self.peek_position = self.parser.position();
                         return Ok(());
                     }
                 }
-                Ok(_) => drop(self.next_event()),
-                Err(_) => return self.next_event().map(|_| ()),
+                Ok(XmlEvent::EndDocument) => {
+                    return Err(self.fail("Unexpected end of document"));
+                }
+                Ok(_) => (),
+                Err(e) => return Err(self.error_emitter.emit_error(&e)),
             }
         }
     }
@@ -415,6 +487,63 @@ mod tests {
         assert_eq!("a.b.c", result);
     }
 
+    #[test]
+    fn test_skip_elements() {
+        let xml = br#"<?xml version="1.0"?>
+            <root>
+                <child name="a"><doc>text <b>in</b> doc</doc></child>
+                <doc><child name="x" /></doc>
+                <child name="b" />
+            </root>"#;
+
+        let result: String = with_parser(xml, |mut p| {
+            p.skip_elements(&["doc"]);
+            p.document(|p, _| {
+                p.element_with_name("root", |p, _| {
+                    p.elements(|p, elem| {
+                        let name = elem.attr_required("name")?.to_owned();
+                        p.elements(|_, elem| Err(format!("<{}> not skipped", elem.name())))?;
+                        Ok(name)
+                    })
+                    .map(|v| v.join("."))
+                })
+            })
+        })
+        .unwrap();
+
+        assert_eq!("a.b", result);
+    }
+
+    #[test]
+    fn test_unexpected_elements() {
+        let xml = br#"<?xml version="1.0"?>
+            <root>
+                <x><child name="ignored" /></x>
+                <child name="a" />
+                <y />
+                <child name="b" />
+            </root>"#;
+
+        let mut names = Vec::new();
+        let error = with_parser(xml, |mut p| {
+            p.document(|p, _| {
+                p.element_with_name("root", |p, _| {
+                    p.elements(|p, elem| match elem.name() {
+                        "child" => elem.attr_required("name").map(|s| names.push(s.to_owned())),
+                        _ => p.skip_unexpected_element(elem),
+                    })
+                })
+            })
+        })
+        .unwrap_err();
+
+        assert_eq!(names, ["a", "b"]);
+        let errors = error.lines().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].ends_with("Unexpected element <x>"));
+        assert!(errors[1].ends_with("Unexpected element <y>"));
+    }
+
     #[test]
     fn test_text() {
         let xml = br#"<?xml version="1.0"?>
@@ -463,4 +592,18 @@ mod tests {
         })
         .unwrap();
     }
+
+    #[test]
+    fn test_unknown_attributes() {
+        let xml = br#"<?xml version="1.0"?>
+            <root name="a">
+                <child name="b" colour="red" />
+                <child size="1">text</child>
+            </root>"#;
+
+        let messages = with_parser(xml, |mut p| p.unknown_attributes(&["name"])).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("Unknown attribute `colour` on element <child>"));
+        assert!(messages[1].contains("Unknown attribute `size` on element <child>"));
+    }
 }